@@ -1,8 +1,8 @@
-use clap::{value_parser, Parser, Subcommand, ValueEnum};
+use clap::{value_parser, Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 use crate::config::DriverKind;
-use crate::core::Channel;
+use crate::core::{Channel, Command as BlindCommand};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -59,6 +59,11 @@ pub enum Command {
         #[command(subcommand)]
         command: RemoteCommand,
     },
+    /// Learn physical RTS remotes, bind them to blinds, and decode captured pulse trains
+    Rts {
+        #[command(subcommand)]
+        command: RtsCommand,
+    },
     /// Inspect or reset HomeKit pairing state
     Homekit {
         #[command(subcommand)]
@@ -66,6 +71,33 @@ pub enum Command {
     },
     /// Read service logs
     Logs(LogsArgs),
+    /// Show recently executed commands
+    History(HistoryArgs),
+    /// Lock a channel for maintenance so nothing moves it (every blind when omitted)
+    Lock(LockArgs),
+    /// Remove a maintenance lock (the global lock when no channel is given)
+    Unlock { channel: Option<Channel> },
+    /// List active maintenance locks
+    Locks {
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Inspect `[[schedule]]` entries
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+    /// Measure a blind's travel times interactively and save them to config
+    Calibrate {
+        channel: Channel,
+        /// Also time these positions (percent open) to record a travel curve
+        #[arg(long, value_delimiter = ',', value_parser = value_parser!(u8).range(1..=99))]
+        points: Vec<u8>,
+        /// Write the config without restarting; restart once after the final change
+        #[arg(long)]
+        no_restart: bool,
+    },
     /// Inspect configuration
     Config {
         #[command(subcommand)]
@@ -82,11 +114,29 @@ pub enum UpgradeChannel {
 #[derive(Subcommand, Debug)]
 pub enum RemoteCommand {
     /// Raise the selected or provided channel
-    Up { channel: Option<Channel> },
+    Up {
+        channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
     /// Lower the selected or provided channel
-    Down { channel: Option<Channel> },
-    /// Send the middle-button stop/favorite command
-    Stop { channel: Option<Channel> },
+    Down {
+        channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
+    /// Send the middle-button stop command
+    Stop {
+        channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
+    /// Send idle blinds to their stored favourite ("My") position
+    My {
+        channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
     /// Select a channel
     Select { channel: Channel },
     /// Pair or unpair an RTS virtual remote on a channel (requires `driver = "rts"`)
@@ -102,6 +152,21 @@ pub enum RemoteCommand {
         #[arg(value_parser = value_parser!(u8).range(0..=100))]
         position: u8,
         channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
+    /// Tilt venetian slats to an angle in degrees (-90 to 90)
+    Tilt {
+        #[arg(value_parser = value_parser!(i8).range(-90..=90), allow_hyphen_values = true)]
+        angle: i8,
+        channel: Option<Channel>,
+        #[command(flatten)]
+        group: GroupArg,
+    },
+    /// Move blinds to a configured `[[scenes]]` preset
+    Scene {
+        /// Scene name (e.g. "movie")
+        name: String,
     },
     /// Print current selected channel
     Status,
@@ -109,6 +174,71 @@ pub enum RemoteCommand {
     Watch,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum RtsCommand {
+    /// Wait for a button press and record the remote's address under NAME
+    Learn {
+        name: String,
+        /// Seconds to wait for the press
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+    },
+    /// Set the channels a learned remote moves
+    Bind {
+        name: String,
+        #[arg(required = true)]
+        channels: Vec<Channel>,
+    },
+    /// List learned remotes
+    Remotes {
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Forget a learned remote
+    Forget { name: String },
+    /// Decode RTS frames from a captured pulse train (Flipper `.sub` RAW or microsecond list)
+    Decode {
+        /// Capture file, or `-` for stdin
+        file: PathBuf,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write the waveform a press would transmit, consuming its rolling code
+    Export {
+        #[arg(long)]
+        channel: Channel,
+        /// up, down, stop, my, or prog
+        #[arg(long)]
+        command: BlindCommand,
+        /// Repeat the frame ~20 times, like holding the button (prog_long implies it)
+        #[arg(long)]
+        long: bool,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Sub)]
+        format: ExportFormat,
+        /// File to write; stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ExportFormat {
+    /// Flipper Zero SubGhz RAW file
+    Sub,
+    /// JSON with the frame fields and signed microsecond durations
+    Json,
+}
+
+/// `--group <NAME>`: target a configured `[[groups]]` entry instead of a channel.
+#[derive(Args, Clone, Debug, Default)]
+pub struct GroupArg {
+    /// Configured group name (e.g. "living room")
+    #[arg(long, conflicts_with = "channel")]
+    pub group: Option<String>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum HomekitCommand {
     /// Show HomeKit identity, pairing status, and pairing QR when unpaired
@@ -145,6 +275,50 @@ pub struct LogsArgs {
     pub debug: bool,
 }
 
+#[derive(Clone, Debug, Parser)]
+pub struct HistoryArgs {
+    /// Only commands sent to this channel
+    #[arg(long)]
+    pub channel: Option<Channel>,
+    /// Start of the time range: Unix seconds or an age such as 30m, 2h, 7d
+    #[arg(long, value_parser = crate::commands::history::parse_time)]
+    pub since: Option<u64>,
+    /// End of the time range: Unix seconds or an age such as 30m, 2h, 7d
+    #[arg(long, value_parser = crate::commands::history::parse_time)]
+    pub until: Option<u64>,
+    /// Maximum number of entries
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
+    /// Print machine-readable JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct LockArgs {
+    /// Channel to lock; omit to lock every blind
+    pub channel: Option<Channel>,
+    /// Release the lock automatically after this long, e.g. 30m, 2h, 1d
+    #[arg(long = "for", value_parser = crate::commands::history::parse_duration_secs)]
+    pub duration: Option<u64>,
+    /// Note shown when a command is refused, e.g. "window cleaning"
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ScheduleCommand {
+    /// List schedule entries with their next runs
+    List {
+        /// Number of upcoming runs to show per entry
+        #[arg(long, default_value_t = 3)]
+        count: usize,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommand {
     /// Print the resolved config file path
@@ -0,0 +1,205 @@
+//! Interactive travel-time calibration against the running service.
+
+use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+
+use crate::commands::config::{ensure_positioning_channel, write_positioning};
+use crate::commands::remote::post_control;
+use crate::config::ResolvedConfig;
+use crate::core::{Channel, Command};
+use crate::service::ControlRequest;
+
+/// Full-travel timings measured by the wizard, in milliseconds.
+#[derive(Debug, PartialEq, Eq)]
+struct MeasuredTiming {
+    open_ms: u64,
+    close_ms: u64,
+    slack_ms: u64,
+    open_curve: Vec<(u8, u64)>,
+    close_curve: Vec<(u8, u64)>,
+}
+
+/// Stopwatch readings for one full travel: the marks passed on the way, then
+/// the end of travel.
+struct Travel {
+    marks: Vec<(u8, Duration)>,
+    end: Duration,
+}
+
+pub async fn run(
+    channel: Channel,
+    mut points: Vec<u8>,
+    no_restart: bool,
+    resolved: &ResolvedConfig,
+) -> Result<()> {
+    ensure_positioning_channel(resolved, channel)?;
+    points.sort_unstable();
+    points.dedup();
+    println!("Calibrating {channel}. Keep the blind in sight and press Enter at each prompt.");
+
+    press(channel, Command::Down, resolved).await?;
+    prompt("Closing. Press Enter once the blind is fully closed and the motor has stopped")?;
+
+    press(channel, Command::Up, resolved).await?;
+    let opening = time_travel(
+        Instant::now(),
+        points.iter().copied(),
+        "Opening. Press Enter the moment the blind reaches the top",
+    )?;
+
+    press(channel, Command::Down, resolved).await?;
+    let started = Instant::now();
+    let closing = time_travel(
+        started,
+        points.iter().rev().copied(),
+        "Closing. Press Enter when the bottom bar reaches the sill",
+    )?;
+    prompt("Press Enter when the motor stops")?;
+    let close = started.elapsed();
+
+    let timing = measured_timing(&opening, &closing, close)?;
+    println!(
+        "measured open {:.1}s, close {:.1}s, slack {:.1}s",
+        opening.end.as_secs_f64(),
+        close.as_secs_f64(),
+        close.saturating_sub(closing.end).as_secs_f64()
+    );
+    write_positioning(resolved, channel, no_restart, |options| {
+        options.open_ms = timing.open_ms;
+        options.close_ms = timing.close_ms;
+        options.slack_ms = timing.slack_ms;
+        options.open_curve = timing.open_curve;
+        options.close_curve = timing.close_curve;
+    })
+}
+
+/// Time a travel that began at `started`, prompting at each mark and then at
+/// `end_message`.
+fn time_travel(
+    started: Instant,
+    marks: impl Iterator<Item = u8>,
+    end_message: &str,
+) -> Result<Travel> {
+    let mut readings = Vec::new();
+    for percent in marks {
+        prompt(&format!("Press Enter when the blind is {percent}% open"))?;
+        readings.push((percent, started.elapsed()));
+    }
+    prompt(end_message)?;
+    Ok(Travel {
+        marks: readings,
+        end: started.elapsed(),
+    })
+}
+
+async fn press(channel: Channel, command: Command, resolved: &ResolvedConfig) -> Result<()> {
+    post_control(
+        ControlRequest::Driver {
+            command,
+            channel: Some(channel),
+        },
+        resolved,
+    )
+    .await
+}
+
+fn prompt(message: &str) -> Result<()> {
+    print!("{message} ");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    let read = io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("reading calibration input")?;
+    if read == 0 {
+        bail!("calibration aborted: stdin closed");
+    }
+    Ok(())
+}
+
+/// Convert the wizard's stopwatch readings into config timings. Closing ends
+/// when the bottom bar reaches the sill; `close` is when the motor stopped, so
+/// the difference is the closed-end slack.
+fn measured_timing(opening: &Travel, closing: &Travel, close: Duration) -> Result<MeasuredTiming> {
+    if opening.end.is_zero() || close.is_zero() {
+        bail!("measured travel time must be greater than 0");
+    }
+    if closing.end > close {
+        bail!("the blind must reach the sill before the motor stops");
+    }
+    let curve = |travel: &Travel| {
+        travel
+            .marks
+            .iter()
+            .map(|(percent, elapsed)| (*percent, duration_ms(*elapsed)))
+            .collect()
+    };
+    Ok(MeasuredTiming {
+        open_ms: duration_ms(opening.end),
+        close_ms: duration_ms(close),
+        slack_ms: duration_ms(close - closing.end),
+        open_curve: curve(opening),
+        close_curve: curve(closing),
+    })
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn travel(marks: &[(u8, u64)], end_ms: u64) -> Travel {
+        Travel {
+            marks: marks
+                .iter()
+                .map(|(percent, ms)| (*percent, Duration::from_millis(*ms)))
+                .collect(),
+            end: Duration::from_millis(end_ms),
+        }
+    }
+
+    #[test]
+    fn measured_timing_derives_slack_from_close_readings() {
+        let timing = measured_timing(
+            &travel(&[], 27_100),
+            &travel(&[], 22_700),
+            Duration::from_millis(25_400),
+        )
+        .unwrap();
+
+        assert_eq!(
+            timing,
+            MeasuredTiming {
+                open_ms: 27_100,
+                close_ms: 25_400,
+                slack_ms: 2_700,
+                open_curve: Vec::new(),
+                close_curve: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn measured_timing_records_curve_marks() {
+        let timing = measured_timing(
+            &travel(&[(25, 9_000), (75, 21_000)], 27_000),
+            &travel(&[(75, 4_000), (25, 15_000)], 22_000),
+            Duration::from_millis(24_000),
+        )
+        .unwrap();
+
+        assert_eq!(timing.open_curve, vec![(25, 9_000), (75, 21_000)]);
+        assert_eq!(timing.close_curve, vec![(75, 4_000), (25, 15_000)]);
+    }
+
+    #[test]
+    fn measured_timing_rejects_empty_or_inverted_readings() {
+        let second = Duration::from_secs(1);
+        assert!(measured_timing(&travel(&[], 0), &travel(&[], 500), second).is_err());
+        assert!(measured_timing(&travel(&[], 1_000), &travel(&[], 2_000), second).is_err());
+    }
+}
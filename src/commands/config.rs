@@ -1,7 +1,7 @@
 use anyhow::{bail, Result};
 
-use crate::config::DriverKind;
 use crate::config::{self, ResolvedConfig};
+use crate::config::{BlindTimingOptions, DriverKind};
 use crate::core::Channel;
 use crate::deploy::{atomic_write, prepare_driver_prereqs, restart_somfy};
 
@@ -24,7 +24,7 @@ pub fn set_driver(resolved: &ResolvedConfig, kind: DriverKind) -> Result<()> {
     next.driver = kind;
     config::validate(&next)?;
 
-    prepare_driver_prereqs(kind)?;
+    prepare_driver_prereqs(&next)?;
 
     atomic_write(&resolved.path, &config::to_toml(&next)?)?;
     println!("wrote {} (driver={kind})", resolved.path.display());
@@ -42,9 +42,7 @@ pub fn set_positioning(
     slack_seconds: Option<f64>,
     no_restart: bool,
 ) -> Result<()> {
-    if matches!(channel, Channel::All) {
-        bail!("positioning timing must target one blind: L1, L2, L3, or L4");
-    }
+    ensure_positioning_channel(resolved, channel)?;
 
     let open_ms = seconds_to_positive_ms("open", open_seconds)?;
     let close_ms = seconds_to_positive_ms("close", close_seconds)?;
@@ -52,25 +50,53 @@ pub fn set_positioning(
         .map(|seconds| seconds_to_nonnegative_ms("slack", seconds))
         .transpose()?;
 
+    write_positioning(resolved, channel, no_restart, |timing| {
+        timing.open_ms = open_ms;
+        timing.close_ms = close_ms;
+        if let Some(slack_ms) = slack_ms {
+            timing.slack_ms = slack_ms;
+        }
+    })
+}
+
+/// Reject `ALL` and channels without a `[[blinds]]` entry before any timing is
+/// measured or written.
+pub(crate) fn ensure_positioning_channel(
+    resolved: &ResolvedConfig,
+    channel: Channel,
+) -> Result<()> {
+    if !matches!(channel, Channel::Individual(_)) {
+        bail!("positioning timing must target one blind, not {channel}");
+    }
+    if !resolved
+        .config
+        .blinds
+        .iter()
+        .any(|blind| blind.channel == channel)
+    {
+        bail!("channel {channel} is not configured in [[blinds]]");
+    }
+    Ok(())
+}
+
+/// Apply `update` to one blind's timing, validate, persist, then restart the
+/// service unless `no_restart` is set.
+pub(crate) fn write_positioning(
+    resolved: &ResolvedConfig,
+    channel: Channel,
+    no_restart: bool,
+    update: impl FnOnce(&mut BlindTimingOptions),
+) -> Result<()> {
     let mut next = resolved.config.clone();
     let Some(timing) = next.positioning.timing_mut(channel) else {
-        bail!("positioning timing must target one blind: L1, L2, L3, or L4");
+        bail!("positioning timing must target one blind, not {channel}");
     };
-    timing.open_ms = open_ms;
-    timing.close_ms = close_ms;
-    if let Some(slack_ms) = slack_ms {
-        timing.slack_ms = slack_ms;
-    }
+    update(timing);
+    let summary = timing_summary(timing);
     config::validate(&next)?;
 
-    let slack_message = slack_ms
-        .map(|slack_ms| format!(", slack_ms={slack_ms}"))
-        .unwrap_or_default();
     atomic_write(&resolved.path, &config::to_toml(&next)?)?;
-    println!(
-        "wrote {} ({channel}: open_ms={open_ms}, close_ms={close_ms}{slack_message})",
-        resolved.path.display()
-    );
+    println!("wrote {} ({channel}: {summary})", resolved.path.display());
 
     if no_restart {
         println!("somfy not restarted (--no-restart); restart once after the final config change");
@@ -81,6 +107,22 @@ pub fn set_positioning(
     Ok(())
 }
 
+fn timing_summary(timing: &BlindTimingOptions) -> String {
+    let mut summary = format!(
+        "open_ms={}, close_ms={}, slack_ms={}",
+        timing.open_ms, timing.close_ms, timing.slack_ms
+    );
+    for (name, curve) in [
+        ("open_curve", &timing.open_curve),
+        ("close_curve", &timing.close_curve),
+    ] {
+        if !curve.is_empty() {
+            summary.push_str(&format!(", {name}={} points", curve.len()));
+        }
+    }
+    summary
+}
+
 fn seconds_to_positive_ms(name: &str, seconds: f64) -> Result<u64> {
     if seconds <= 0.0 {
         bail!("{name} seconds must be greater than 0");
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use super::check::{read_write_file, readable_file, Check};
 use super::Status;
-use crate::config::{AppConfig, DriverKind};
+use crate::config::{AppConfig, DriverKind, RtsTransmitterKind};
 use crate::driver::{pigpiod_addr_list, pigpiod_addrs, PIGPIOD_PORT};
 use crate::gpio::MAX_BCM_GPIO;
 use crate::persist;
@@ -16,16 +16,38 @@ pub fn driver_checks(config: &AppConfig) -> Vec<Check> {
             "GPIO",
             &config.gpio.chip,
         )],
-        DriverKind::Rts => vec![
-            read_write_file("rts_spi_device", "RTS SPI", &config.rts.spi_device),
-            rts_gdo0(config.rts.gpio.gdo0),
-            pigpiod(),
-            Check::new("pigpiod_localhost_only", "pigpiod local").detail(format!(
-                "loopback only ({}, port {PIGPIOD_PORT})",
-                pigpiod_addr_list()
-            )),
-            rts_state_file(),
-        ],
+        DriverKind::Rts => {
+            let mut checks = vec![read_write_file(
+                "rts_spi_device",
+                "RTS SPI",
+                &config.rts.spi_device,
+            )];
+            match config.rts.transmitter {
+                RtsTransmitterKind::Pigpio => checks.extend([
+                    rts_gdo0(config.rts.gpio.gdo0),
+                    pigpiod(),
+                    Check::new("pigpiod_localhost_only", "pigpiod local").detail(format!(
+                        "loopback only ({}, port {PIGPIOD_PORT})",
+                        pigpiod_addr_list()
+                    )),
+                ]),
+                // The radio clocks the waveform itself; GDO0 and pigpiod are unused.
+                RtsTransmitterKind::Packet => checks.push(
+                    Check::new("rts_transmitter", "RTS transmitter")
+                        .detail("CC1101 packet mode (TX FIFO over SPI)"),
+                ),
+            }
+            checks.push(rts_state_file());
+            // Receiving reads GDO2 through the GPIO character device.
+            if config.rts.gpio.gdo2.is_some() {
+                checks.push(readable_file(
+                    "gpio_chip_accessible",
+                    "GPIO",
+                    &config.gpio.chip,
+                ));
+            }
+            checks
+        }
         DriverKind::Fake => vec![Check::new("gpio_chip_accessible", "GPIO")
             .skipped()
             .detail("fake driver selected")],
@@ -0,0 +1,156 @@
+use anyhow::{bail, Context, Result};
+
+use crate::cli::HistoryArgs;
+use crate::history::{HistoryEntry, HistoryFilter, Outcome};
+use crate::positioning::state::unix_now;
+use crate::server::base_url;
+
+pub async fn run(args: HistoryArgs) -> Result<()> {
+    let filter = HistoryFilter {
+        channel: args.channel,
+        since: args.since,
+        until: args.until,
+        limit: Some(args.limit),
+    };
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    if let Some(channel) = filter.channel {
+        query.append_pair("channel", &channel.to_string());
+    }
+    if let Some(since) = filter.since {
+        query.append_pair("since", &since.to_string());
+    }
+    if let Some(until) = filter.until {
+        query.append_pair("until", &until.to_string());
+    }
+    if let Some(limit) = filter.limit {
+        query.append_pair("limit", &limit.to_string());
+    }
+    let url = format!("{}/history?{}", base_url(), query.finish());
+    let entries: Vec<HistoryEntry> = reqwest::get(&url)
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?
+        .error_for_status()
+        .context("reading command history from somfy service")?
+        .json()
+        .await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No commands recorded.");
+        return Ok(());
+    }
+    // Oldest first, so the latest command ends up next to the prompt.
+    for entry in entries.iter().rev() {
+        println!("{}", format_entry(entry));
+    }
+    Ok(())
+}
+
+fn format_entry(entry: &HistoryEntry) -> String {
+    let channel = entry
+        .channel
+        .map(|channel| channel.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let target = entry
+        .target
+        .map(|target| target.to_string())
+        .unwrap_or_default();
+    let outcome = match (&entry.outcome, &entry.error) {
+        (Outcome::Ok, _) => "ok".to_string(),
+        (Outcome::Error, Some(error)) => format!("error: {}", first_line(error)),
+        (Outcome::Error, None) => "error".to_string(),
+    };
+    format!(
+        "{}  {:<20} {:<9} {:<4} {:>4}  {}",
+        format_utc(entry.at),
+        entry.source.to_string(),
+        entry.command,
+        channel,
+        target,
+        outcome
+    )
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+/// Parse `--since`/`--until`: Unix seconds, or an age (`90s`, `30m`, `2h`, `7d`).
+pub fn parse_time(value: &str) -> Result<u64> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(secs);
+    }
+    let age = parse_duration_secs(value).with_context(|| {
+        format!("expected Unix seconds or an age like 30m, 2h, 7d; got `{value}`")
+    })?;
+    Ok(unix_now().saturating_sub(age))
+}
+
+/// Parse a span such as `90s`, `30m`, `2h`, or `7d` into seconds.
+pub fn parse_duration_secs(value: &str) -> Result<u64> {
+    let split = value.len().saturating_sub(1);
+    let (amount, unit) = value.split_at(split);
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => bail!("expected a duration like 30m, 2h, 7d; got `{value}`"),
+    };
+    let amount: u64 = amount
+        .parse()
+        .with_context(|| format!("invalid duration `{value}`"))?;
+    Ok(amount.saturating_mul(unit_secs))
+}
+
+/// `YYYY-MM-DD HH:MM:SSZ` for Unix seconds.
+fn format_utc(secs: u64) -> String {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Gregorian date for days since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_time_accepts_unix_seconds_and_ages() {
+        assert_eq!(parse_time("1700000000").unwrap(), 1_700_000_000);
+        let now = unix_now();
+        let two_hours_ago = parse_time("2h").unwrap();
+        assert!((now - 2 * 3600..=now - 2 * 3600 + 1).contains(&two_hours_ago));
+        assert!(parse_time("2w").is_err());
+        assert!(parse_time("h").is_err());
+    }
+
+    #[test]
+    fn format_utc_renders_calendar_dates() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00:00Z");
+        assert_eq!(format_utc(1_792_243_805), "2026-10-17 13:30:05Z");
+    }
+}
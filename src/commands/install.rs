@@ -68,7 +68,7 @@ pub(crate) fn refresh(
     ensure_somfy_group()?;
     ensure_user_in_somfy_group(&service_user)?;
 
-    prepare_driver_prereqs(resolved_config.config.driver)?;
+    prepare_driver_prereqs(&resolved_config.config)?;
 
     ensure_config_file(resolved_config)?;
     apply_config_acl(resolved_config)?;
@@ -0,0 +1,93 @@
+use anyhow::{bail, Context, Result};
+
+use crate::cli::LockArgs;
+use crate::core::Channel;
+use crate::locks::Lock;
+use crate::positioning::state::unix_now;
+use crate::server::{base_url, CLIENT_HEADER};
+use crate::service::LockRequest;
+
+pub async fn lock(args: LockArgs) -> Result<()> {
+    let request = LockRequest {
+        channel: args.channel,
+        until: args.duration.map(|secs| unix_now().saturating_add(secs)),
+        reason: args.reason,
+    };
+    let url = format!("{}/locks", base_url());
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header(CLIENT_HEADER, "cli")
+        .json(&request)
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("service rejected lock: HTTP {status}: {}", body.trim());
+    }
+    let lock: Lock = response.json().await?;
+    println!("Locked {}", describe(&lock));
+    Ok(())
+}
+
+pub async fn unlock(channel: Option<Channel>) -> Result<()> {
+    let channel = channel.unwrap_or(Channel::All);
+    let url = format!("{}/locks/{channel}", base_url());
+    let response = reqwest::Client::new()
+        .delete(&url)
+        .header(CLIENT_HEADER, "cli")
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        println!("{channel} was not locked.");
+        return Ok(());
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("service rejected unlock: HTTP {status}: {}", body.trim());
+    }
+    println!("Unlocked {channel}");
+    Ok(())
+}
+
+pub async fn list(json: bool) -> Result<()> {
+    let url = format!("{}/locks", base_url());
+    let locks: Vec<Lock> = reqwest::get(&url)
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?
+        .error_for_status()
+        .context("reading locks from somfy service")?
+        .json()
+        .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&locks)?);
+        return Ok(());
+    }
+    if locks.is_empty() {
+        println!("No channels locked.");
+        return Ok(());
+    }
+    for lock in &locks {
+        println!("{}", describe(lock));
+    }
+    Ok(())
+}
+
+/// `L2 until 2026-10-17T15:30:00Z (window cleaning)`.
+fn describe(lock: &Lock) -> String {
+    let until = lock
+        .until
+        .and_then(|until| jiff::Timestamp::from_second(until as i64).ok())
+        .map_or_else(
+            || "until unlocked".to_string(),
+            |until| format!("until {until}"),
+        );
+    match &lock.reason {
+        Some(reason) => format!("{} {until} ({reason})", lock.channel),
+        None => format!("{} {until}", lock.channel),
+    }
+}
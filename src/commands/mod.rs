@@ -1,10 +1,15 @@
+pub mod calibrate;
 pub mod config;
 pub mod doctor;
+pub mod history;
 pub mod homekit;
 pub mod install;
+pub mod lock;
 pub mod logs;
 pub mod remote;
 pub mod restart;
+pub mod rts;
+pub mod schedule;
 pub mod serve;
 pub mod uninstall;
 pub mod upgrade;
@@ -1,39 +1,52 @@
 use anyhow::{bail, Context, Result};
 use futures_util::StreamExt;
 
-use crate::cli::RemoteCommand;
+use crate::cli::{GroupArg, RemoteCommand};
 use crate::config::ResolvedConfig;
-use crate::core::Command;
-use crate::server::base_url;
-use crate::service::{validate_control_request, CommandRequest, ControlRequest};
+use crate::core::{Channel, Command};
+use crate::positioning::inventory::BlindInventory;
+use crate::server::{base_url, CLIENT_HEADER};
+use crate::service::{
+    ensure_configured_channel, validate_control_request, CommandRequest, ControlRequest,
+};
 
 pub async fn run(command: RemoteCommand, resolved: &ResolvedConfig) -> Result<()> {
     match command {
-        RemoteCommand::Up { channel } => {
+        RemoteCommand::Up { channel, group } => {
             post_control(
                 ControlRequest::Driver {
                     command: Command::Up,
-                    channel,
+                    channel: resolve_channel(channel, group, resolved)?,
                 },
                 resolved,
             )
             .await
         }
-        RemoteCommand::Down { channel } => {
+        RemoteCommand::Down { channel, group } => {
             post_control(
                 ControlRequest::Driver {
                     command: Command::Down,
-                    channel,
+                    channel: resolve_channel(channel, group, resolved)?,
                 },
                 resolved,
             )
             .await
         }
-        RemoteCommand::Stop { channel } => {
+        RemoteCommand::Stop { channel, group } => {
             post_control(
                 ControlRequest::Driver {
                     command: Command::Stop,
-                    channel,
+                    channel: resolve_channel(channel, group, resolved)?,
+                },
+                resolved,
+            )
+            .await
+        }
+        RemoteCommand::My { channel, group } => {
+            post_control(
+                ControlRequest::Driver {
+                    command: Command::My,
+                    channel: resolve_channel(channel, group, resolved)?,
                 },
                 resolved,
             )
@@ -64,22 +77,56 @@ pub async fn run(command: RemoteCommand, resolved: &ResolvedConfig) -> Result<()
             )
             .await
         }
-        RemoteCommand::Target { position, channel } => {
+        RemoteCommand::Target {
+            position,
+            channel,
+            group,
+        } => {
+            let channel = resolve_channel(channel, group, resolved)?;
             post_control(ControlRequest::Position { channel, position }, resolved).await
         }
+        RemoteCommand::Tilt {
+            angle,
+            channel,
+            group,
+        } => {
+            let channel = resolve_channel(channel, group, resolved)?;
+            post_control(ControlRequest::Tilt { channel, angle }, resolved).await
+        }
+        RemoteCommand::Scene { name } => post_scene(&name, resolved).await,
         RemoteCommand::Status => status().await,
         RemoteCommand::Watch => watch().await,
     }
 }
 
-async fn post_control(request: ControlRequest, resolved: &ResolvedConfig) -> Result<()> {
+/// Resolve `--group <name>` to its `G<n>` channel from the local config.
+fn resolve_channel(
+    channel: Option<Channel>,
+    group: GroupArg,
+    resolved: &ResolvedConfig,
+) -> Result<Option<Channel>> {
+    let Some(name) = group.group else {
+        return Ok(channel);
+    };
+    let group = resolved
+        .config
+        .groups
+        .iter()
+        .find(|group| group.name == name)
+        .with_context(|| format!("unknown group `{name}`; see [[groups]] in config.toml"))?;
+    Ok(Some(group.channel))
+}
+
+pub(crate) async fn post_control(request: ControlRequest, resolved: &ResolvedConfig) -> Result<()> {
     let request = validate_control_request(resolved.config.driver, request)?;
+    ensure_configured_channel(&BlindInventory::from_config(&resolved.config), &request)?;
     let payload = CommandRequest::from_control(request);
 
     let client = reqwest::Client::new();
     let url = format!("{}/command", base_url());
     let response = client
         .post(&url)
+        .header(CLIENT_HEADER, "cli")
         .json(&payload)
         .send()
         .await
@@ -98,6 +145,39 @@ async fn post_control(request: ControlRequest, resolved: &ResolvedConfig) -> Res
     );
 }
 
+async fn post_scene(name: &str, resolved: &ResolvedConfig) -> Result<()> {
+    if !resolved
+        .config
+        .scenes
+        .iter()
+        .any(|scene| scene.name == name)
+    {
+        bail!("unknown scene `{name}`; see [[scenes]] in config.toml");
+    }
+    let mut url = reqwest::Url::parse(&base_url())?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow::anyhow!("invalid service URL"))?
+        .pop_if_empty()
+        .extend(["scenes", name]);
+    let response = reqwest::Client::new()
+        .post(url.clone())
+        .header(CLIENT_HEADER, "cli")
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+
+    if response.status().is_success() {
+        return Ok(());
+    }
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    bail!(
+        "service rejected scene {name}: HTTP {status}: {}",
+        body.trim()
+    );
+}
+
 async fn status() -> Result<()> {
     let url = format!("{}/channel", base_url());
     let text = reqwest::get(&url)
@@ -0,0 +1,258 @@
+use anyhow::{bail, Context, Result};
+use reqwest::{Response, Url};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{ExportFormat, RtsCommand};
+use crate::config::{DriverKind, ResolvedConfig};
+use crate::core::{Channel, Command};
+use crate::positioning::inventory::BlindInventory;
+use crate::remotes::{BindRequest, LearnRequest, LearnedRemote};
+use crate::rts::capture;
+use crate::rts::decoder::{decode_capture, CapturedFrame};
+use crate::rts::export::{self, ExportRequest, ExportedWaveform};
+use crate::rts::frame::RtsCommand as RadioCommand;
+use crate::rts::state::RtsStateStore;
+use crate::server::{base_url, CLIENT_HEADER};
+
+pub async fn run(command: RtsCommand, resolved: &ResolvedConfig) -> Result<()> {
+    match command {
+        RtsCommand::Learn { name, timeout } => learn(name, timeout).await,
+        RtsCommand::Bind { name, channels } => bind(&name, channels).await,
+        RtsCommand::Remotes { json } => list(json).await,
+        RtsCommand::Forget { name } => forget(&name).await,
+        RtsCommand::Decode { file, json } => decode(&file, json),
+        RtsCommand::Export {
+            channel,
+            command,
+            long,
+            format,
+            output,
+        } => export(channel, command, long, format, output, resolved).await,
+    }
+}
+
+async fn export(
+    channel: Channel,
+    command: Command,
+    long: bool,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+    resolved: &ResolvedConfig,
+) -> Result<()> {
+    if resolved.config.driver != DriverKind::Rts {
+        bail!("exporting RTS waveforms requires driver = \"rts\"");
+    }
+    if !BlindInventory::from_config(&resolved.config).contains_channel(channel) {
+        bail!("channel {channel} is not configured in [[blinds]]");
+    }
+    let request = ExportRequest {
+        channel,
+        command: RadioCommand::try_from(command)?,
+        long: long || command == Command::ProgLong,
+    };
+    let exported = reserve_export(&request).await?;
+    let rendered = match format {
+        ExportFormat::Sub => exported.to_sub(),
+        ExportFormat::Json => format!("{}\n", serde_json::to_string_pretty(&exported)?),
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)
+                .with_context(|| format!("writing {}", path.display()))?;
+            eprintln!(
+                "Wrote {} {} on {channel} (remote {:06X}, rolling code {}) to {}",
+                command,
+                if exported.long { "long press" } else { "press" },
+                exported.remote_id,
+                exported.rolling_code,
+                path.display()
+            );
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Ask the running service, which owns `rts.json`, for the waveform. When
+/// it is not running the state file is safe to use directly.
+async fn reserve_export(request: &ExportRequest) -> Result<ExportedWaveform> {
+    let url = format!("{}/rts/export", base_url());
+    match reqwest::Client::new()
+        .post(&url)
+        .header(CLIENT_HEADER, "cli")
+        .json(request)
+        .send()
+        .await
+    {
+        Ok(response) => Ok(accepted(response, "export").await?.json().await?),
+        Err(e) if e.is_connect() => {
+            eprintln!("somfy service is not running; reserving the rolling code in rts.json");
+            let mut state = RtsStateStore::load_or_init_default()?;
+            export::reserve(&mut state, request.channel, request.command, request.long)
+        }
+        Err(e) => Err(e).with_context(|| format!("connecting to somfy service at {url}")),
+    }
+}
+
+fn decode(file: &Path, json: bool) -> Result<()> {
+    let text = if file == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("reading capture from stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(file)
+            .with_context(|| format!("reading capture {}", file.display()))?
+    };
+    let runs = capture::parse(&text)?;
+    let frames = decode_capture(runs);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&frames)?);
+        return Ok(());
+    }
+    if frames.is_empty() {
+        println!("No RTS frames found: no software sync followed by 56 Manchester bits.");
+        return Ok(());
+    }
+    for (index, frame) in frames.iter().enumerate() {
+        println!("frame {}: {}", index + 1, describe_captured(frame));
+    }
+    Ok(())
+}
+
+/// `wake-up, 2 hardware syncs: down from 123456, rolling code 42 [a7…]`.
+fn describe_captured(captured: &CapturedFrame) -> String {
+    let preamble = format!(
+        "{}{} hardware syncs",
+        if captured.wakeup { "wake-up, " } else { "" },
+        captured.hardware_syncs
+    );
+    let payload = match (&captured.frame, &captured.error) {
+        (Some(frame), _) => format!(
+            "{} from {:06X}, rolling code {}",
+            Command::from(frame.command),
+            frame.remote_id,
+            frame.rolling_code
+        ),
+        (None, error) => format!("invalid ({})", error.as_deref().unwrap_or("unknown")),
+    };
+    format!("{preamble}: {payload} [{}]", hex::encode(captured.bytes))
+}
+
+async fn learn(name: String, timeout: u64) -> Result<()> {
+    let url = format!("{}/remotes/learn", base_url());
+    println!("Press a button on the remote to learn as `{name}` (waiting {timeout}s)...");
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header(CLIENT_HEADER, "cli")
+        .json(&LearnRequest {
+            name,
+            timeout_secs: Some(timeout),
+        })
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+    let remote: LearnedRemote = accepted(response, "learn").await?.json().await?;
+    println!("Learned {}", describe(&remote));
+    if remote.channels.is_empty() {
+        println!(
+            "Bind it to blinds with: somfy rts bind {:?} <CHANNEL>...",
+            remote.name
+        );
+    }
+    Ok(())
+}
+
+async fn bind(name: &str, channels: Vec<Channel>) -> Result<()> {
+    let url = remote_url(name)?;
+    let response = reqwest::Client::new()
+        .put(url.clone())
+        .header(CLIENT_HEADER, "cli")
+        .json(&BindRequest { channels })
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+    let remote: LearnedRemote = accepted(response, "bind").await?.json().await?;
+    println!("Bound {}", describe(&remote));
+    Ok(())
+}
+
+async fn list(json: bool) -> Result<()> {
+    let url = format!("{}/remotes", base_url());
+    let remotes: Vec<LearnedRemote> = reqwest::get(&url)
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?
+        .error_for_status()
+        .context("reading learned remotes from somfy service")?
+        .json()
+        .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&remotes)?);
+        return Ok(());
+    }
+    if remotes.is_empty() {
+        println!("No remotes learned.");
+        return Ok(());
+    }
+    for remote in &remotes {
+        println!("{}", describe(remote));
+    }
+    Ok(())
+}
+
+async fn forget(name: &str) -> Result<()> {
+    let url = remote_url(name)?;
+    let response = reqwest::Client::new()
+        .delete(url.clone())
+        .header(CLIENT_HEADER, "cli")
+        .send()
+        .await
+        .with_context(|| format!("connecting to somfy service at {url}"))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        println!("No remote named `{name}` was learned.");
+        return Ok(());
+    }
+    accepted(response, "forget").await?;
+    println!("Forgot `{name}`");
+    Ok(())
+}
+
+/// `/remotes/{name}`, with the name percent-encoded.
+fn remote_url(name: &str) -> Result<Url> {
+    let mut url = Url::parse(&base_url())?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow::anyhow!("service URL cannot hold a path"))?
+        .extend(["remotes", name]);
+    Ok(url)
+}
+
+async fn accepted(response: Response, action: &str) -> Result<Response> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("service rejected {action}: HTTP {status}: {}", body.trim());
+    }
+    Ok(response)
+}
+
+/// `hallway (0A1B2C, code 42) -> L1, L2`.
+fn describe(remote: &LearnedRemote) -> String {
+    let channels = if remote.channels.is_empty() {
+        "unbound".to_string()
+    } else {
+        remote
+            .channels
+            .iter()
+            .map(Channel::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "{} ({:06X}, code {}) -> {channels}",
+        remote.name, remote.remote_id, remote.rolling_code
+    )
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use jiff::{Timestamp, Zoned};
+use serde::Serialize;
+
+use crate::cli::ScheduleCommand;
+use crate::config::ResolvedConfig;
+use crate::scheduler::{schedules_from_config, Schedule};
+
+#[derive(Serialize)]
+struct ScheduleReport {
+    name: String,
+    rule: String,
+    timezone: String,
+    action: String,
+    /// RFC 3339 timestamps with the schedule's UTC offset.
+    next_runs: Vec<String>,
+}
+
+pub fn run(command: ScheduleCommand, resolved_config: &ResolvedConfig) -> Result<()> {
+    match command {
+        ScheduleCommand::List { count, json } => list(count, json, resolved_config),
+    }
+}
+
+fn list(count: usize, json: bool, resolved_config: &ResolvedConfig) -> Result<()> {
+    let schedules = schedules_from_config(&resolved_config.config)?;
+    let now = Timestamp::now();
+    let runs: Vec<Vec<Zoned>> = schedules
+        .iter()
+        .map(|schedule| next_runs(schedule, now, count))
+        .collect();
+    let reports: Vec<ScheduleReport> = schedules
+        .iter()
+        .zip(&runs)
+        .map(|(schedule, runs)| ScheduleReport {
+            name: schedule.name.clone(),
+            rule: schedule.rule_text.clone(),
+            timezone: schedule
+                .timezone
+                .iana_name()
+                .unwrap_or("system")
+                .to_string(),
+            action: schedule.action.to_string(),
+            next_runs: runs
+                .iter()
+                .map(|run| run.strftime("%Y-%m-%dT%H:%M:%S%:z").to_string())
+                .collect(),
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+    if reports.is_empty() {
+        println!(
+            "No [[schedule]] entries in {}",
+            resolved_config.path.display()
+        );
+        return Ok(());
+    }
+    for (report, runs) in reports.iter().zip(&runs) {
+        println!(
+            "{}: {} ({}) -> {}",
+            report.name, report.rule, report.timezone, report.action
+        );
+        if runs.is_empty() {
+            println!("  never runs");
+        }
+        for run in runs {
+            println!("  {}", run.strftime("%a %Y-%m-%d %H:%M %Z"));
+        }
+    }
+    Ok(())
+}
+
+fn next_runs(schedule: &Schedule, now: Timestamp, count: usize) -> Vec<Zoned> {
+    let mut runs = Vec::with_capacity(count);
+    let mut after = now;
+    while runs.len() < count {
+        let Some(next) = schedule.next_after(after) else {
+            break;
+        };
+        after = next.timestamp();
+        runs.push(next);
+    }
+    runs
+}
@@ -5,6 +5,10 @@ use crate::commands::doctor;
 use crate::config::ResolvedConfig;
 use crate::controller::BlindController;
 use crate::homekit;
+use crate::positioning::inventory::BlindInventory;
+use crate::remotes;
+use crate::safety;
+use crate::scheduler;
 use crate::server::{serve, AppState};
 
 pub async fn run(resolved_config: &ResolvedConfig) -> Result<()> {
@@ -17,11 +21,20 @@ pub async fn run(resolved_config: &ResolvedConfig) -> Result<()> {
     let controller = Arc::new(
         BlindController::with_driver(
             resolved_config.config.driver_config(),
+            BlindInventory::from_config(&resolved_config.config),
             resolved_config.config.positioning.clone(),
         )
         .await?,
     );
     let shared_state = Arc::new(AppState::new(controller.clone()));
+    let schedules = scheduler::schedules_from_config(&resolved_config.config)?;
+    let scheduler_handle = scheduler::start(controller.clone(), schedules);
+    let remotes_handle = remotes::start(controller.clone());
+    let safety_handles = safety::start(
+        controller.clone(),
+        &resolved_config.config.safety,
+        &resolved_config.config.gpio.chip,
+    );
 
     let hap_handles = if resolved_config.config.homekit {
         match homekit::start(controller.clone()).await {
@@ -45,6 +58,15 @@ pub async fn run(resolved_config: &ResolvedConfig) -> Result<()> {
             if let Some(handles) = hap_handles {
                 handles.abort();
             }
+            if let Some(handle) = scheduler_handle {
+                handle.abort();
+            }
+            if let Some(handle) = remotes_handle {
+                handle.abort();
+            }
+            for handle in safety_handles {
+                handle.abort();
+            }
             Ok(())
         }
     }
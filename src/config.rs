@@ -3,6 +3,7 @@
 use anyhow::{bail, Context, Result};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::path::{Path, PathBuf};
 
@@ -53,6 +54,7 @@ impl fmt::Display for DriverKind {
 #[serde(default, deny_unknown_fields)]
 pub struct RtsOptions {
     pub spi_device: String,
+    pub transmitter: RtsTransmitterKind,
     pub gpio: RtsGpioOptions,
 }
 
@@ -60,20 +62,40 @@ impl Default for RtsOptions {
     fn default() -> Self {
         Self {
             spi_device: "/dev/spidev0.0".to_string(),
+            transmitter: RtsTransmitterKind::default(),
             gpio: RtsGpioOptions::default(),
         }
     }
 }
 
+/// How the RTS driver gets the waveform into the CC1101.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RtsTransmitterKind {
+    /// pigpiod clocks GDO0 with the radio in asynchronous serial mode.
+    #[default]
+    Pigpio,
+    /// The waveform is pre-encoded at a fixed symbol rate and streamed into
+    /// the TX FIFO over SPI. GDO0 is unused and pigpiod is not needed.
+    Packet,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(default, deny_unknown_fields)]
 pub struct RtsGpioOptions {
     pub gdo0: u8,
+    /// Input wired to the CC1101 GDO2 pin. When set, the radio also
+    /// receives and decodes frames from other RTS remotes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gdo2: Option<u8>,
 }
 
 impl Default for RtsGpioOptions {
     fn default() -> Self {
-        Self { gdo0: 18 }
+        Self {
+            gdo0: 18,
+            gdo2: None,
+        }
     }
 }
 
@@ -117,6 +139,27 @@ pub struct BlindTimingOptions {
     pub open_ms: u64,
     pub close_ms: u64,
     pub slack_ms: u64,
+    /// Favourite position stored in the motor, reached by `my` while idle.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_position: Option<u8>,
+    /// Venetian slat travel from -90° to 90°; defaults to 1.5 s.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tilt_ms: Option<u64>,
+    /// Measured `[percent, ms]` points while opening from fully closed. When
+    /// set, the planner interpolates them instead of assuming linear travel.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub open_curve: Vec<(u8, u64)>,
+    /// Measured `[percent, ms]` points while closing from fully open.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub close_curve: Vec<(u8, u64)>,
+    /// Route the next interior move through an end stop after this many
+    /// interior moves in a row.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rehome_after_moves: Option<u32>,
+    /// Route the next interior move through an end stop once this many hours
+    /// have passed since the blind last reached one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rehome_after_hours: Option<u32>,
 }
 
 impl Default for BlindTimingOptions {
@@ -125,44 +168,358 @@ impl Default for BlindTimingOptions {
             open_ms: 10_000,
             close_ms: 10_000,
             slack_ms: 0,
+            my_position: None,
+            tilt_ms: None,
+            open_curve: Vec::new(),
+            close_curve: Vec::new(),
+            rehome_after_moves: None,
+            rehome_after_hours: None,
         }
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(default, deny_unknown_fields)]
+/// Per-blind timing keyed by individual channel (`[positioning.l1]`, `[positioning.l7]`, …).
+///
+/// Channels without an entry use [`BlindTimingOptions::default`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(try_from = "PositioningTable", into = "PositioningTable")]
 pub struct PositioningOptions {
-    pub l1: BlindTimingOptions,
-    pub l2: BlindTimingOptions,
-    pub l3: BlindTimingOptions,
-    pub l4: BlindTimingOptions,
+    /// How often a moving blind publishes its interpolated position; `0`
+    /// reports only the start and end of each move.
+    pub update_interval_ms: u64,
+    /// Motor run time each blind may spend within `run_window_secs`; `0`
+    /// disables duty-cycle protection.
+    pub run_budget_secs: u64,
+    /// Period over which a full `run_budget_secs` cools down again.
+    pub run_window_secs: u64,
+    channels: BTreeMap<Channel, BlindTimingOptions>,
+}
+
+const DEFAULT_UPDATE_INTERVAL_MS: u64 = 1_000;
+/// Somfy tubular motors trip their thermal cut-out after about four minutes
+/// of continuous running.
+const DEFAULT_RUN_BUDGET_SECS: u64 = 240;
+const DEFAULT_RUN_WINDOW_SECS: u64 = 900;
+
+impl Default for PositioningOptions {
+    fn default() -> Self {
+        Self {
+            update_interval_ms: DEFAULT_UPDATE_INTERVAL_MS,
+            run_budget_secs: DEFAULT_RUN_BUDGET_SECS,
+            run_window_secs: DEFAULT_RUN_WINDOW_SECS,
+            channels: BTreeMap::new(),
+        }
+    }
+}
+
+/// `[positioning]` as written in config.toml: shared keys next to the
+/// per-channel tables.
+#[derive(Deserialize, Serialize)]
+struct PositioningTable {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    update_interval_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_budget_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_window_secs: Option<u64>,
+    #[serde(flatten)]
+    channels: BTreeMap<String, BlindTimingOptions>,
 }
 
 impl PositioningOptions {
+    #[cfg(test)]
+    pub(crate) fn timing(&self, channel: Channel) -> BlindTimingOptions {
+        self.channels.get(&channel).cloned().unwrap_or_default()
+    }
+
     pub(crate) fn timing_mut(&mut self, channel: Channel) -> Option<&mut BlindTimingOptions> {
         match channel {
-            Channel::L1 => Some(&mut self.l1),
-            Channel::L2 => Some(&mut self.l2),
-            Channel::L3 => Some(&mut self.l3),
-            Channel::L4 => Some(&mut self.l4),
-            Channel::All => None,
+            Channel::Individual(_) => Some(self.channels.entry(channel).or_default()),
+            Channel::All | Channel::Group(_) => None,
         }
     }
 
-    fn named_timings(&self) -> [(&'static str, &BlindTimingOptions); 4] {
-        [
-            ("positioning.l1", &self.l1),
-            ("positioning.l2", &self.l2),
-            ("positioning.l3", &self.l3),
-            ("positioning.l4", &self.l4),
-        ]
+    /// Channels with an explicit `[positioning.lN]` table.
+    pub(crate) fn configured(&self) -> impl Iterator<Item = (Channel, &BlindTimingOptions)> {
+        self.channels
+            .iter()
+            .map(|(channel, timing)| (*channel, timing))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_timing(&mut self, channel: Channel, timing: BlindTimingOptions) {
+        if let Some(slot) = self.timing_mut(channel) {
+            *slot = timing;
+        }
+    }
+
+    fn named_timings(&self) -> impl Iterator<Item = (String, &BlindTimingOptions)> {
+        self.channels
+            .iter()
+            .map(|(channel, timing)| (format!("positioning.{}", positioning_key(*channel)), timing))
+    }
+}
+
+fn positioning_key(channel: Channel) -> String {
+    channel.to_string().to_lowercase()
+}
+
+impl TryFrom<PositioningTable> for PositioningOptions {
+    type Error = String;
+
+    fn try_from(value: PositioningTable) -> Result<Self, Self::Error> {
+        let channels = value
+            .channels
+            .into_iter()
+            .map(
+                |(key, timing)| match key.to_uppercase().parse::<Channel>() {
+                    Ok(channel @ Channel::Individual(_)) if key == positioning_key(channel) => {
+                        Ok((channel, timing))
+                    }
+                    _ => Err(format!(
+                        "unknown positioning channel `{key}`; expected l1..=l{}",
+                        Channel::MAX_INDIVIDUAL
+                    )),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            update_interval_ms: value
+                .update_interval_ms
+                .unwrap_or(DEFAULT_UPDATE_INTERVAL_MS),
+            run_budget_secs: value.run_budget_secs.unwrap_or(DEFAULT_RUN_BUDGET_SECS),
+            run_window_secs: value.run_window_secs.unwrap_or(DEFAULT_RUN_WINDOW_SECS),
+            channels,
+        })
+    }
+}
+
+impl From<PositioningOptions> for PositioningTable {
+    fn from(value: PositioningOptions) -> Self {
+        Self {
+            update_interval_ms: (value.update_interval_ms != DEFAULT_UPDATE_INTERVAL_MS)
+                .then_some(value.update_interval_ms),
+            run_budget_secs: (value.run_budget_secs != DEFAULT_RUN_BUDGET_SECS)
+                .then_some(value.run_budget_secs),
+            run_window_secs: (value.run_window_secs != DEFAULT_RUN_WINDOW_SECS)
+                .then_some(value.run_window_secs),
+            channels: value
+                .channels
+                .into_iter()
+                .map(|(channel, timing)| (positioning_key(channel), timing))
+                .collect(),
+        }
+    }
+}
+
+/// One `[[blinds]]` entry: a motor, the channel (virtual remote or Telis row)
+/// that drives it, and the HomeKit accessory id it is exposed under.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BlindOptions {
+    pub name: String,
+    pub channel: Channel,
+    /// HomeKit accessory id. Must stay stable once paired; `1` is the bridge.
+    pub aid: u64,
+    #[serde(default, skip_serializing_if = "BlindKind::is_roller")]
+    pub kind: BlindKind,
+}
+
+/// Motor/fabric type of a blind.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlindKind {
+    /// Roller shade or awning: position only.
+    #[default]
+    Roller,
+    /// Venetian blind: short Up/Down pulses tilt the slats.
+    Venetian,
+}
+
+impl BlindKind {
+    fn is_roller(&self) -> bool {
+        *self == Self::Roller
     }
+}
+
+/// The four blinds exposed before `[[blinds]]` existed: `Blind 1`–`Blind 4` on
+/// `L1`–`L4` with AIDs 2–5.
+pub(crate) fn default_blinds() -> Vec<BlindOptions> {
+    Channel::TELIS_ROWS
+        .iter()
+        .zip(2..)
+        .enumerate()
+        .map(|(index, (channel, aid))| BlindOptions {
+            name: format!("Blind {}", index + 1),
+            channel: *channel,
+            aid,
+            kind: BlindKind::Roller,
+        })
+        .collect()
+}
+
+/// One `[[groups]]` entry: a named set of blinds addressed together as a
+/// group channel (`G1`–`G16`).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GroupOptions {
+    pub name: String,
+    pub channel: Channel,
+    /// Member blinds by channel; each must appear in `[[blinds]]`.
+    pub blinds: Vec<Channel>,
+    /// HomeKit accessory id for a group tile. Groups without one stay off HomeKit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aid: Option<u64>,
+    /// RTS only: the group's own virtual remote has been paired with every
+    /// member motor (`somfy remote prog G1`), so group moves start with one
+    /// transmission instead of one per blind.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub paired: bool,
+}
+
+/// One `[[scenes]]` entry: a named preset of blind target positions,
+/// activated together through one `set_target_positions` call.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SceneOptions {
+    pub name: String,
+    /// Target position (0 closed, 100 open) per blind channel, e.g.
+    /// `positions = { L1 = 0, L2 = 30 }`.
+    pub positions: BTreeMap<Channel, u8>,
+    /// HomeKit accessory id for a stateless scene switch. Scenes without one
+    /// stay off HomeKit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aid: Option<u64>,
+}
+
+/// Day of the week for a `[[schedule]]` `at` rule.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleDay {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
 
-    pub(crate) fn individual_timings(&self) -> [&BlindTimingOptions; 4] {
-        [&self.l1, &self.l2, &self.l3, &self.l4]
+/// Solar event for a `[[schedule]]` `sun` rule.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+/// `[location]`: where sunrise and sunset are computed for `sun` schedules.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LocationOptions {
+    /// Degrees, positive north.
+    pub latitude: f64,
+    /// Degrees, positive east.
+    pub longitude: f64,
+}
+
+// Validation rejects non-finite coordinates, so equality is reflexive.
+impl Eq for LocationOptions {}
+
+/// One `[[schedule]]` entry: a time rule and the command or scene it fires.
+/// Exactly one of `at`, `cron`, or `sun`, and one of `command` or `scene`,
+/// is set.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleOptions {
+    pub name: String,
+    /// Local time of day, `HH:MM`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+    /// Days `at` or `sun` fires on; empty means every day.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<ScheduleDay>,
+    /// Five-field cron expression: minute, hour, day of month, month, day of week.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+    /// Fire at sunrise or sunset at `[location]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sun: Option<SunEvent>,
+    /// Minutes after (or, when negative, before) the `sun` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_minutes: Option<i32>,
+    /// Earliest local `HH:MM` a `sun` rule fires; later events are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    /// Latest local `HH:MM` a `sun` rule fires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<String>,
+    /// IANA time zone such as `Europe/Paris`; defaults to the system zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Command as sent to `POST /command`, e.g. `up` or `target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<i16>,
+    /// `[[scenes]]` name to activate instead of a command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene: Option<String>,
+}
+
+/// `[safety]`: dry-contact inputs such as an anemometer or rain sensor that
+/// retract blinds and hold them up while active.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SafetyOptions {
+    /// How long an input must stay active before it trips.
+    pub debounce_ms: u64,
+    /// How long a tripped input must stay clear before downward moves are
+    /// allowed again.
+    pub hold_off_secs: u64,
+    pub inputs: Vec<SafetyInputOptions>,
+}
+
+impl Default for SafetyOptions {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 5_000,
+            hold_off_secs: 600,
+            inputs: Vec::new(),
+        }
     }
 }
 
+impl SafetyOptions {
+    fn is_unused(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+/// One `[[safety.inputs]]` GPIO line.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyInputOptions {
+    pub name: String,
+    /// BCM GPIO number.
+    pub gpio: u8,
+    /// The contact pulls the line low when triggered. The line is biased
+    /// towards its inactive level either way.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub active_low: bool,
+    /// Blind or group channels to retract; empty means every blind.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<Channel>,
+    /// HomeKit accessory id for an occupancy sensor mirroring the input.
+    /// Inputs without one stay off HomeKit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aid: Option<u64>,
+}
+
 /// Resolved driver settings passed to the driver router.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum DriverConfig {
@@ -172,6 +529,7 @@ pub(crate) enum DriverConfig {
         telis: TelisOptions,
     },
     Rts {
+        gpio: GpioOptions,
         rts: RtsOptions,
     },
 }
@@ -199,6 +557,17 @@ impl DriverConfig {
 pub struct AppConfig {
     pub driver: DriverKind,
     pub homekit: bool,
+    pub blinds: Vec<BlindOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scenes: Vec<SceneOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<ScheduleOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<LocationOptions>,
+    #[serde(skip_serializing_if = "SafetyOptions::is_unused")]
+    pub safety: SafetyOptions,
     pub positioning: PositioningOptions,
     pub gpio: GpioOptions,
     pub rts: RtsOptions,
@@ -210,6 +579,12 @@ impl Default for AppConfig {
         Self {
             driver: DriverKind::default_for_target(),
             homekit: false,
+            blinds: default_blinds(),
+            groups: Vec::new(),
+            scenes: Vec::new(),
+            schedule: Vec::new(),
+            location: None,
+            safety: SafetyOptions::default(),
             positioning: PositioningOptions::default(),
             gpio: GpioOptions::default(),
             rts: RtsOptions::default(),
@@ -228,6 +603,7 @@ impl AppConfig {
                 telis: self.telis.clone(),
             },
             DriverKind::Rts => DriverConfig::Rts {
+                gpio: self.gpio.clone(),
                 rts: self.rts.clone(),
             },
         }
@@ -275,6 +651,9 @@ pub(crate) fn validate(config: &AppConfig) -> Result<()> {
     if config.rts.gpio.gdo0 > MAX_BCM_GPIO {
         bail!("rts.gpio.gdo0 must be a BCM GPIO in 0..={MAX_BCM_GPIO}");
     }
+    if config.rts.gpio.gdo2.is_some_and(|gdo2| gdo2 > MAX_BCM_GPIO) {
+        bail!("rts.gpio.gdo2 must be a BCM GPIO in 0..={MAX_BCM_GPIO}");
+    }
     validate_gpio_pins(&[
         ("telis.gpio.up", config.telis.gpio.up),
         ("telis.gpio.stop", config.telis.gpio.stop),
@@ -285,6 +664,23 @@ pub(crate) fn validate(config: &AppConfig) -> Result<()> {
         ("telis.gpio.led3", config.telis.gpio.led3),
         ("telis.gpio.led4", config.telis.gpio.led4),
     ])?;
+    validate_blinds(config)?;
+    if let Some(location) = &config.location {
+        if !(-90.0..=90.0).contains(&location.latitude) {
+            bail!("location.latitude must be between -90 and 90");
+        }
+        if !(-180.0..=180.0).contains(&location.longitude) {
+            bail!("location.longitude must be between -180 and 180");
+        }
+    }
+    crate::scheduler::schedules_from_config(config)?;
+    if (1..MIN_UPDATE_INTERVAL_MS).contains(&config.positioning.update_interval_ms) {
+        bail!("positioning.update_interval_ms must be 0 or at least {MIN_UPDATE_INTERVAL_MS}");
+    }
+    let positioning = &config.positioning;
+    if positioning.run_budget_secs > positioning.run_window_secs {
+        bail!("positioning.run_window_secs must be at least positioning.run_budget_secs");
+    }
     for (name, timing) in config.positioning.named_timings() {
         if timing.open_ms == 0 {
             bail!("{name}.open_ms must be greater than 0");
@@ -298,10 +694,296 @@ pub(crate) fn validate(config: &AppConfig) -> Result<()> {
         if timing.slack_ms > timing.close_ms {
             bail!("{name}.slack_ms must be <= {name}.close_ms");
         }
+        if timing.tilt_ms == Some(0) {
+            bail!("{name}.tilt_ms must be greater than 0");
+        }
+        if timing.my_position.is_some_and(|position| position > 100) {
+            bail!("{name}.my_position must be between 0 and 100");
+        }
+        if timing.rehome_after_moves == Some(0) {
+            bail!("{name}.rehome_after_moves must be greater than 0");
+        }
+        if timing.rehome_after_hours == Some(0) {
+            bail!("{name}.rehome_after_hours must be greater than 0");
+        }
+        validate_curve(&name, "open", &timing.open_curve, timing.open_ms, true)?;
+        validate_curve(&name, "close", &timing.close_curve, timing.close_ms, false)?;
+    }
+    Ok(())
+}
+
+/// Faster position updates would only churn client event streams.
+const MIN_UPDATE_INTERVAL_MS: u64 = 100;
+
+/// Curve points must be interior positions reached in travel order, strictly
+/// inside the full travel time.
+fn validate_curve(
+    name: &str,
+    direction: &str,
+    points: &[(u8, u64)],
+    full_ms: u64,
+    opening: bool,
+) -> Result<()> {
+    for (percent, ms) in points {
+        if !(1..=99).contains(percent) {
+            bail!("{name}.{direction}_curve positions must be between 1 and 99");
+        }
+        if *ms == 0 || *ms >= full_ms {
+            bail!("{name}.{direction}_curve times must be between 0 and {name}.{direction}_ms");
+        }
+    }
+    for pair in points.windows(2) {
+        let ((from, from_ms), (to, to_ms)) = (pair[0], pair[1]);
+        let in_order = if opening { to > from } else { to < from };
+        if !in_order || to_ms <= from_ms {
+            bail!("{name}.{direction}_curve points must be listed in travel order");
+        }
+    }
+    Ok(())
+}
+
+fn validate_blinds(config: &AppConfig) -> Result<()> {
+    if config.blinds.is_empty() {
+        bail!("at least one [[blinds]] entry is required");
+    }
+    // Each blind needs its own individual channel.
+    if config.blinds.len() > usize::from(Channel::MAX_INDIVIDUAL) {
+        bail!(
+            "at most {} [[blinds]] entries are supported, one per channel L1-L{}",
+            Channel::MAX_INDIVIDUAL,
+            Channel::MAX_INDIVIDUAL
+        );
+    }
+    let mut aids = BTreeSet::new();
+    let mut channels = BTreeSet::new();
+    for blind in &config.blinds {
+        if blind.name.trim().is_empty() {
+            bail!("blinds.name must not be empty (aid {})", blind.aid);
+        }
+        if blind.aid < 2 {
+            bail!(
+                "blinds.aid for `{}` must be 2 or greater; aid 1 is the HomeKit bridge",
+                blind.name
+            );
+        }
+        if !aids.insert(blind.aid) {
+            bail!("blinds.aid {} is used by more than one blind", blind.aid);
+        }
+        if !matches!(blind.channel, Channel::Individual(_)) {
+            bail!(
+                "blinds.channel for `{}` must be an individual channel (L1-L{})",
+                blind.name,
+                Channel::MAX_INDIVIDUAL
+            );
+        }
+        if !channels.insert(blind.channel) {
+            bail!(
+                "blinds.channel {} is used by more than one blind",
+                blind.channel
+            );
+        }
+        if config.driver == DriverKind::Telis && !Channel::TELIS_ROWS.contains(&blind.channel) {
+            bail!(
+                "blinds.channel {} for `{}` is not available with the Telis driver (L1-L4 only)",
+                blind.channel,
+                blind.name
+            );
+        }
+    }
+    validate_groups(config, &mut aids, &channels)?;
+    validate_scenes(config, &mut aids, &channels)?;
+    validate_safety(config, &mut aids, &channels)
+}
+
+fn validate_groups(
+    config: &AppConfig,
+    aids: &mut BTreeSet<u64>,
+    blind_channels: &BTreeSet<Channel>,
+) -> Result<()> {
+    let mut names = BTreeSet::new();
+    let mut channels = BTreeSet::new();
+    for group in &config.groups {
+        if group.name.trim().is_empty() {
+            bail!("groups.name must not be empty ({})", group.channel);
+        }
+        if !names.insert(group.name.as_str()) {
+            bail!(
+                "groups.name `{}` is used by more than one group",
+                group.name
+            );
+        }
+        if !matches!(group.channel, Channel::Group(_)) {
+            bail!(
+                "groups.channel for `{}` must be a group channel (G1-G{})",
+                group.name,
+                Channel::MAX_GROUP
+            );
+        }
+        if !channels.insert(group.channel) {
+            bail!(
+                "groups.channel {} is used by more than one group",
+                group.channel
+            );
+        }
+        if group.blinds.is_empty() {
+            bail!(
+                "groups.blinds for `{}` must list at least one blind",
+                group.name
+            );
+        }
+        let mut members = BTreeSet::new();
+        for member in &group.blinds {
+            if !blind_channels.contains(member) {
+                bail!(
+                    "groups.blinds for `{}` lists {member}, which is not a configured blind",
+                    group.name
+                );
+            }
+            if !members.insert(*member) {
+                bail!("groups.blinds for `{}` lists {member} twice", group.name);
+            }
+        }
+        if let Some(aid) = group.aid {
+            if aid < 2 {
+                bail!(
+                    "groups.aid for `{}` must be 2 or greater; aid 1 is the HomeKit bridge",
+                    group.name
+                );
+            }
+            if !aids.insert(aid) {
+                bail!("groups.aid {aid} is already used by another blind or group");
+            }
+        }
+        if group.paired && config.driver == DriverKind::Telis {
+            bail!(
+                "groups.paired for `{}` needs an RTS virtual remote; the Telis driver cannot transmit group channels",
+                group.name
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_scenes(
+    config: &AppConfig,
+    aids: &mut BTreeSet<u64>,
+    blind_channels: &BTreeSet<Channel>,
+) -> Result<()> {
+    let mut names = BTreeSet::new();
+    for scene in &config.scenes {
+        if scene.name.trim().is_empty() {
+            bail!("scenes.name must not be empty");
+        }
+        if !names.insert(scene.name.as_str()) {
+            bail!(
+                "scenes.name `{}` is used by more than one scene",
+                scene.name
+            );
+        }
+        if scene.positions.is_empty() {
+            bail!(
+                "scenes.positions for `{}` must list at least one blind",
+                scene.name
+            );
+        }
+        for (channel, position) in &scene.positions {
+            if !blind_channels.contains(channel) {
+                bail!(
+                    "scenes.positions for `{}` lists {channel}, which is not a configured blind",
+                    scene.name
+                );
+            }
+            if *position > 100 {
+                bail!(
+                    "scenes.positions.{channel} for `{}` must be between 0 and 100",
+                    scene.name
+                );
+            }
+        }
+        if let Some(aid) = scene.aid {
+            if aid < 2 {
+                bail!(
+                    "scenes.aid for `{}` must be 2 or greater; aid 1 is the HomeKit bridge",
+                    scene.name
+                );
+            }
+            if !aids.insert(aid) {
+                bail!("scenes.aid {aid} is already used by another blind, group, or scene");
+            }
+        }
     }
     Ok(())
 }
 
+fn validate_safety(
+    config: &AppConfig,
+    aids: &mut BTreeSet<u64>,
+    blind_channels: &BTreeSet<Channel>,
+) -> Result<()> {
+    let mut names = BTreeSet::new();
+    let mut pins: Vec<(String, u8)> = match config.driver {
+        DriverKind::Fake => Vec::new(),
+        DriverKind::Telis => [
+            ("telis.gpio.up", config.telis.gpio.up),
+            ("telis.gpio.stop", config.telis.gpio.stop),
+            ("telis.gpio.down", config.telis.gpio.down),
+            ("telis.gpio.select", config.telis.gpio.select),
+            ("telis.gpio.led1", config.telis.gpio.led1),
+            ("telis.gpio.led2", config.telis.gpio.led2),
+            ("telis.gpio.led3", config.telis.gpio.led3),
+            ("telis.gpio.led4", config.telis.gpio.led4),
+        ]
+        .into_iter()
+        .map(|(name, gpio)| (name.to_string(), gpio))
+        .collect(),
+        DriverKind::Rts => std::iter::once(("rts.gpio.gdo0", config.rts.gpio.gdo0))
+            .chain(config.rts.gpio.gdo2.map(|gdo2| ("rts.gpio.gdo2", gdo2)))
+            .map(|(name, gpio)| (name.to_string(), gpio))
+            .collect(),
+    };
+    for input in &config.safety.inputs {
+        if input.name.trim().is_empty() {
+            bail!("safety.inputs.name must not be empty");
+        }
+        if !names.insert(input.name.as_str()) {
+            bail!(
+                "safety.inputs.name `{}` is used by more than one input",
+                input.name
+            );
+        }
+        pins.push((
+            format!("safety.inputs.gpio for `{}`", input.name),
+            input.gpio,
+        ));
+        for channel in &input.channels {
+            let configured = blind_channels.contains(channel)
+                || config.groups.iter().any(|group| group.channel == *channel);
+            if !configured {
+                bail!(
+                    "safety.inputs.channels for `{}` lists {channel}, which is not a configured blind or group",
+                    input.name
+                );
+            }
+        }
+        if let Some(aid) = input.aid {
+            if aid < 2 {
+                bail!(
+                    "safety.inputs.aid for `{}` must be 2 or greater; aid 1 is the HomeKit bridge",
+                    input.name
+                );
+            }
+            if !aids.insert(aid) {
+                bail!("safety.inputs.aid {aid} is already used by another accessory");
+            }
+        }
+    }
+    let pins: Vec<(&str, u8)> = pins
+        .iter()
+        .map(|(name, gpio)| (name.as_str(), *gpio))
+        .collect();
+    validate_gpio_pins(&pins)
+}
+
 fn validate_gpio_pins(pins: &[(&str, u8)]) -> Result<()> {
     for (name, gpio) in pins {
         if *gpio > MAX_BCM_GPIO {
@@ -348,12 +1030,12 @@ close_ms = 42000
         )
         .unwrap();
 
-        assert_eq!(config.positioning.l1.open_ms, 11_000);
-        assert_eq!(config.positioning.l1.close_ms, 12_000);
-        assert_eq!(config.positioning.l2.open_ms, 10_000);
-        assert_eq!(config.positioning.l2.slack_ms, 0);
-        assert_eq!(config.positioning.l4.open_ms, 41_000);
-        assert_eq!(config.positioning.l4.close_ms, 42_000);
+        assert_eq!(config.positioning.timing(Channel::L1).open_ms, 11_000);
+        assert_eq!(config.positioning.timing(Channel::L1).close_ms, 12_000);
+        assert_eq!(config.positioning.timing(Channel::L2).open_ms, 10_000);
+        assert_eq!(config.positioning.timing(Channel::L2).slack_ms, 0);
+        assert_eq!(config.positioning.timing(Channel::L4).open_ms, 41_000);
+        assert_eq!(config.positioning.timing(Channel::L4).close_ms, 42_000);
     }
 
     #[test]
@@ -370,7 +1052,194 @@ slack_ms = 2700
         )
         .unwrap();
 
-        assert_eq!(config.positioning.l1.slack_ms, 2_700);
+        assert_eq!(config.positioning.timing(Channel::L1).slack_ms, 2_700);
+    }
+
+    #[test]
+    fn parses_and_validates_my_position() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[positioning.l1]
+my_position = 40
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.positioning.timing(Channel::L1).my_position, Some(40));
+        assert_eq!(config.positioning.timing(Channel::L2).my_position, None);
+        let text = to_toml(&config).unwrap();
+        assert!(text.contains("my_position = 40"), "{text}");
+
+        let config: AppConfig =
+            toml::from_str("driver = \"fake\"\n\n[positioning.l1]\nmy_position = 101\n").unwrap();
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("positioning.l1.my_position"));
+    }
+
+    #[test]
+    fn parses_venetian_blinds_and_tilt_timing() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[[blinds]]
+name = "Office"
+channel = "L1"
+aid = 2
+kind = "venetian"
+
+[[blinds]]
+name = "Kitchen"
+channel = "L2"
+aid = 3
+
+[positioning.l1]
+tilt_ms = 1200
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(config.blinds[0].kind, BlindKind::Venetian);
+        assert_eq!(config.blinds[1].kind, BlindKind::Roller);
+        assert_eq!(config.positioning.timing(Channel::L1).tilt_ms, Some(1_200));
+        let text = to_toml(&config).unwrap();
+        assert!(text.contains("kind = \"venetian\""), "{text}");
+        assert_eq!(text.matches("kind =").count(), 1, "{text}");
+
+        let config: AppConfig =
+            toml::from_str("driver = \"fake\"\n\n[positioning.l1]\ntilt_ms = 0\n").unwrap();
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("positioning.l1.tilt_ms"));
+    }
+
+    #[test]
+    fn parses_and_validates_travel_curves() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[positioning.l1]
+open_ms = 30000
+close_ms = 20000
+open_curve = [[25, 12000], [50, 20000]]
+close_curve = [[50, 8000]]
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        let timing = config.positioning.timing(Channel::L1);
+        assert_eq!(timing.open_curve, vec![(25, 12_000), (50, 20_000)]);
+        assert_eq!(timing.close_curve, vec![(50, 8_000)]);
+        let text = to_toml(&config).unwrap();
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+
+        for (curve, field) in [
+            ("open_curve = [[50, 20000], [25, 12000]]", "open_curve"),
+            ("open_curve = [[100, 12000]]", "open_curve"),
+            ("close_curve = [[50, 20000]]", "close_curve"),
+            ("close_curve = [[25, 5000], [50, 8000]]", "close_curve"),
+        ] {
+            let config: AppConfig = toml::from_str(&format!(
+                "driver = \"fake\"\n\n[positioning.l1]\nopen_ms = 30000\nclose_ms = 20000\n{curve}\n"
+            ))
+            .unwrap();
+            let err = validate(&config).unwrap_err();
+            assert!(err.to_string().contains(field), "{curve}: {err}");
+        }
+    }
+
+    #[test]
+    fn parses_position_update_interval_next_to_channel_tables() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[positioning]
+update_interval_ms = 500
+
+[positioning.l1]
+open_ms = 11000
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.positioning.update_interval_ms, 500);
+        assert_eq!(config.positioning.timing(Channel::L1).open_ms, 11_000);
+        let text = to_toml(&config).unwrap();
+        assert!(text.contains("update_interval_ms = 500"), "{text}");
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+
+        let default: AppConfig = toml::from_str("driver = \"fake\"\n").unwrap();
+        assert_eq!(default.positioning.update_interval_ms, 1_000);
+        assert!(!to_toml(&default).unwrap().contains("update_interval_ms"));
+
+        let config: AppConfig =
+            toml::from_str("driver = \"fake\"\n\n[positioning]\nupdate_interval_ms = 50\n")
+                .unwrap();
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("positioning.update_interval_ms"));
+    }
+
+    #[test]
+    fn parses_duty_cycle_budget_next_to_channel_tables() {
+        let config: AppConfig = toml::from_str(
+            "driver = \"fake\"\n\n[positioning]\nrun_budget_secs = 120\nrun_window_secs = 600\n",
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(config.positioning.run_budget_secs, 120);
+        assert_eq!(config.positioning.run_window_secs, 600);
+        assert_eq!(
+            toml::from_str::<AppConfig>(&to_toml(&config).unwrap()).unwrap(),
+            config
+        );
+
+        let default = AppConfig::default();
+        assert_eq!(default.positioning.run_budget_secs, 240);
+        assert!(!to_toml(&default).unwrap().contains("run_budget_secs"));
+
+        let config: AppConfig = toml::from_str(
+            "driver = \"fake\"\n\n[positioning]\nrun_budget_secs = 600\nrun_window_secs = 300\n",
+        )
+        .unwrap();
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("run_window_secs"), "{err}");
+    }
+
+    #[test]
+    fn parses_and_validates_rehoming_policy() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[positioning.l1]
+rehome_after_moves = 8
+rehome_after_hours = 24
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        let timing = config.positioning.timing(Channel::L1);
+        assert_eq!(timing.rehome_after_moves, Some(8));
+        assert_eq!(timing.rehome_after_hours, Some(24));
+        assert_eq!(
+            config.positioning.timing(Channel::L2).rehome_after_moves,
+            None
+        );
+
+        let config: AppConfig =
+            toml::from_str("driver = \"fake\"\n\n[positioning.l1]\nrehome_after_moves = 0\n")
+                .unwrap();
+        let err = validate(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("positioning.l1.rehome_after_moves"));
     }
 
     #[test]
@@ -407,6 +1276,439 @@ open_ms = 0
         assert!(err.to_string().contains("positioning.l2.open_ms"));
     }
 
+    #[test]
+    fn rejects_unknown_positioning_channel() {
+        for key in ["all", "l17", "L1", "l01"] {
+            let err = toml::from_str::<AppConfig>(&format!(
+                "driver = \"fake\"\n\n[positioning.{key}]\nopen_ms = 1000\n"
+            ))
+            .unwrap_err();
+            assert!(
+                err.to_string().contains("unknown positioning channel"),
+                "{key}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn positioning_round_trips_through_lowercase_channel_keys() {
+        let mut config = AppConfig {
+            driver: DriverKind::Fake,
+            ..AppConfig::default()
+        };
+        config.positioning.set_timing(
+            Channel::Individual(9),
+            BlindTimingOptions {
+                open_ms: 9_000,
+                ..BlindTimingOptions::default()
+            },
+        );
+
+        let text = to_toml(&config).unwrap();
+        assert!(text.contains("[positioning.l9]"), "{text}");
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn missing_blinds_default_to_four_telis_rows() {
+        let config: AppConfig = toml::from_str("driver = \"fake\"\n").unwrap();
+
+        assert_eq!(
+            config
+                .blinds
+                .iter()
+                .map(|blind| (blind.name.as_str(), blind.channel, blind.aid))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Blind 1", Channel::L1, 2),
+                ("Blind 2", Channel::L2, 3),
+                ("Blind 3", Channel::L3, 4),
+                ("Blind 4", Channel::L4, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_blind_inventory_beyond_four_channels() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "rts"
+
+[[blinds]]
+name = "Kitchen"
+channel = "L1"
+aid = 2
+
+[[blinds]]
+name = "Attic"
+channel = "L12"
+aid = 14
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(config.blinds.len(), 2);
+        assert_eq!(config.blinds[1].channel, Channel::Individual(12));
+        assert_eq!(config.blinds[1].aid, 14);
+    }
+
+    #[test]
+    fn rejects_invalid_blind_inventory() {
+        for (blinds, expected) in [
+            (
+                "[[blinds]]\nname = \"A\"\nchannel = \"L1\"\naid = 1\n",
+                "aid 1 is the HomeKit bridge",
+            ),
+            (
+                "[[blinds]]\nname = \"A\"\nchannel = \"L1\"\naid = 2\n[[blinds]]\nname = \"B\"\nchannel = \"L2\"\naid = 2\n",
+                "blinds.aid 2 is used by more than one blind",
+            ),
+            (
+                "[[blinds]]\nname = \"A\"\nchannel = \"L1\"\naid = 2\n[[blinds]]\nname = \"B\"\nchannel = \"L1\"\naid = 3\n",
+                "blinds.channel L1 is used by more than one blind",
+            ),
+            (
+                "[[blinds]]\nname = \"A\"\nchannel = \"ALL\"\naid = 2\n",
+                "must be an individual channel",
+            ),
+            ("blinds = []\n", "at least one [[blinds]] entry"),
+        ] {
+            let config: AppConfig =
+                toml::from_str(&format!("driver = \"fake\"\n{blinds}")).unwrap();
+            let err = validate(&config).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+
+        let mut config: AppConfig = toml::from_str("driver = \"fake\"\n").unwrap();
+        let template = config.blinds[0].clone();
+        config.blinds.extend((0..13).map(|index| BlindOptions {
+            name: format!("Extra {index}"),
+            ..template.clone()
+        }));
+        assert_eq!(config.blinds.len(), 17);
+        let err = validate(&config).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "at most 16 [[blinds]] entries are supported, one per channel L1-L16"
+        );
+    }
+
+    #[test]
+    fn parses_named_groups() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "rts"
+
+[[groups]]
+name = "living room"
+channel = "G1"
+blinds = ["L1", "L3"]
+aid = 20
+paired = true
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(
+            config.groups,
+            vec![GroupOptions {
+                name: "living room".to_string(),
+                channel: Channel::Group(1),
+                blinds: vec![Channel::L1, Channel::L3],
+                aid: Some(20),
+                paired: true,
+            }]
+        );
+        let text = to_toml(&config).unwrap();
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn rejects_invalid_groups() {
+        for (groups, expected) in [
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"L1\"\nblinds = [\"L1\"]\n",
+                "must be a group channel",
+            ),
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"G1\"\nblinds = [\"L9\"]\n",
+                "L9, which is not a configured blind",
+            ),
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"G1\"\nblinds = []\n",
+                "at least one blind",
+            ),
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"G1\"\nblinds = [\"L1\"]\n[[groups]]\nname = \"a\"\nchannel = \"G2\"\nblinds = [\"L2\"]\n",
+                "groups.name `a` is used by more than one group",
+            ),
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"G1\"\nblinds = [\"L1\"]\n[[groups]]\nname = \"b\"\nchannel = \"G1\"\nblinds = [\"L2\"]\n",
+                "groups.channel G1 is used by more than one group",
+            ),
+            (
+                "[[groups]]\nname = \"a\"\nchannel = \"G1\"\nblinds = [\"L1\"]\naid = 3\n",
+                "groups.aid 3 is already used",
+            ),
+        ] {
+            let config: AppConfig =
+                toml::from_str(&format!("driver = \"fake\"\n{groups}")).unwrap();
+            let err = validate(&config).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_scenes() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[[scenes]]
+name = "movie"
+positions = { L1 = 0, L2 = 30, L4 = 100 }
+aid = 30
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(
+            config.scenes,
+            vec![SceneOptions {
+                name: "movie".to_string(),
+                positions: BTreeMap::from([
+                    (Channel::L1, 0),
+                    (Channel::L2, 30),
+                    (Channel::L4, 100)
+                ]),
+                aid: Some(30),
+            }]
+        );
+        let text = to_toml(&config).unwrap();
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+
+        for (scenes, expected) in [
+            (
+                "[[scenes]]\nname = \"a\"\npositions = {}\n",
+                "at least one blind",
+            ),
+            (
+                "[[scenes]]\nname = \"a\"\npositions = { L9 = 0 }\n",
+                "L9, which is not a configured blind",
+            ),
+            (
+                "[[scenes]]\nname = \"a\"\npositions = { L1 = 101 }\n",
+                "between 0 and 100",
+            ),
+            (
+                "[[scenes]]\nname = \"a\"\npositions = { L1 = 0 }\naid = 2\n",
+                "scenes.aid 2 is already used",
+            ),
+            (
+                "[[scenes]]\nname = \"a\"\npositions = { L1 = 0 }\n[[scenes]]\nname = \"a\"\npositions = { L2 = 0 }\n",
+                "scenes.name `a` is used by more than one scene",
+            ),
+        ] {
+            let config: AppConfig =
+                toml::from_str(&format!("driver = \"fake\"\n{scenes}")).unwrap();
+            let err = validate(&config).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_safety_inputs() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "rts"
+
+[safety]
+hold_off_secs = 900
+
+[[safety.inputs]]
+name = "wind"
+gpio = 20
+channels = ["L1", "L2"]
+aid = 40
+
+[[safety.inputs]]
+name = "rain"
+gpio = 21
+active_low = true
+"#,
+        )
+        .unwrap();
+        validate(&config).unwrap();
+        assert_eq!(config.safety.debounce_ms, 5_000);
+        assert_eq!(config.safety.hold_off_secs, 900);
+        assert_eq!(config.safety.inputs[0].channels, [Channel::L1, Channel::L2]);
+        assert!(config.safety.inputs[1].active_low);
+        assert!(to_toml(&config).unwrap().contains("[[safety.inputs]]"));
+        assert!(!to_toml(&AppConfig::default()).unwrap().contains("safety"));
+
+        for (inputs, expected) in [
+            (
+                "[[safety.inputs]]\nname = \"wind\"\ngpio = 18\n",
+                "rts.gpio.gdo0 and safety.inputs.gpio for `wind` must not both use BCM GPIO 18",
+            ),
+            (
+                "[[safety.inputs]]\nname = \"wind\"\ngpio = 32\n",
+                "must be a BCM GPIO in 0..=31",
+            ),
+            (
+                "[[safety.inputs]]\nname = \"wind\"\ngpio = 20\nchannels = [\"L9\"]\n",
+                "lists L9, which is not a configured blind or group",
+            ),
+            (
+                "[[safety.inputs]]\nname = \"wind\"\ngpio = 20\naid = 3\n",
+                "safety.inputs.aid 3 is already used",
+            ),
+            (
+                "[[safety.inputs]]\nname = \"wind\"\ngpio = 20\n[[safety.inputs]]\nname = \"wind\"\ngpio = 21\n",
+                "safety.inputs.name `wind` is used by more than one input",
+            ),
+        ] {
+            let config: AppConfig =
+                toml::from_str(&format!("driver = \"rts\"\n{inputs}")).unwrap();
+            let err = validate(&config).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_schedules() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "fake"
+
+[[scenes]]
+name = "movie"
+positions = { L1 = 0 }
+
+[[schedule]]
+name = "weekday morning"
+at = "07:30"
+days = ["mon", "tue", "wed", "thu", "fri"]
+timezone = "Europe/Paris"
+command = "up"
+channel = "ALL"
+
+[[schedule]]
+name = "evening"
+cron = "0 21 * * *"
+scene = "movie"
+
+[[schedule]]
+name = "dusk"
+sun = "sunset"
+offset_minutes = 30
+not_after = "22:00"
+command = "down"
+channel = "L2"
+
+[location]
+latitude = 48.8566
+longitude = 2.3522
+"#,
+        )
+        .unwrap();
+
+        validate(&config).unwrap();
+        assert_eq!(config.schedule.len(), 3);
+        assert_eq!(config.schedule[2].sun, Some(SunEvent::Sunset));
+        assert_eq!(config.schedule[0].days.len(), 5);
+        let text = to_toml(&config).unwrap();
+        assert_eq!(toml::from_str::<AppConfig>(&text).unwrap(), config);
+
+        for (schedule, expected) in [
+            (
+                "name = \"a\"\ncommand = \"up\"\n",
+                "set exactly one of `at`, `cron`, or `sun`",
+            ),
+            (
+                "name = \"a\"\nat = \"25:00\"\ncommand = \"up\"\n",
+                "at must be HH:MM",
+            ),
+            (
+                "name = \"a\"\ncron = \"0 7 * *\"\ncommand = \"up\"\n",
+                "five fields",
+            ),
+            (
+                "name = \"a\"\nat = \"07:00\"\ntimezone = \"Mars/Olympus\"\ncommand = \"up\"\n",
+                "unknown time zone",
+            ),
+            (
+                "name = \"a\"\nat = \"07:00\"\ncommand = \"up\"\nscene = \"movie\"\n",
+                "exactly one of `command` or `scene`",
+            ),
+            (
+                "name = \"a\"\nat = \"07:00\"\nscene = \"morning\"\n",
+                "unknown scene `morning`",
+            ),
+            (
+                "name = \"a\"\nat = \"07:00\"\ncommand = \"up\"\nchannel = \"L9\"\n",
+                "L9",
+            ),
+            (
+                "name = \"a\"\nsun = \"sunrise\"\ncommand = \"up\"\n",
+                "`sun` needs [location]",
+            ),
+            (
+                "name = \"a\"\nat = \"07:00\"\noffset_minutes = 5\ncommand = \"up\"\n",
+                "only apply to `sun`",
+            ),
+        ] {
+            let config: AppConfig = toml::from_str(&format!(
+                "driver = \"fake\"\n[[scenes]]\nname = \"movie\"\npositions = {{ L1 = 0 }}\n[[schedule]]\n{schedule}"
+            ))
+            .unwrap();
+            let err = validate(&config).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn telis_driver_rejects_paired_groups() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "telis"
+
+[[groups]]
+name = "living room"
+channel = "G1"
+blinds = ["L1", "L3"]
+paired = true
+"#,
+        )
+        .unwrap();
+
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("cannot transmit group channels"));
+    }
+
+    #[test]
+    fn telis_driver_rejects_channels_beyond_l4() {
+        let config: AppConfig = toml::from_str(
+            r#"
+driver = "telis"
+
+[[blinds]]
+name = "Attic"
+channel = "L5"
+aid = 6
+"#,
+        )
+        .unwrap();
+
+        let err = validate(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("not available with the Telis driver"));
+    }
+
     #[test]
     fn rejects_unknown_telis_fields() {
         let err = toml::from_str::<AppConfig>(
@@ -474,11 +1776,21 @@ chip = "/dev/gpiochip1"
 
 [rts.gpio]
 gdo0 = 24
+gdo2 = 25
 "#,
         )
         .unwrap();
 
         assert_eq!(config.gpio.chip, "/dev/gpiochip1");
         assert_eq!(config.rts.gpio.gdo0, 24);
+        assert_eq!(config.rts.gpio.gdo2, Some(25));
+        validate(&config).unwrap();
+
+        let mut clash = config.clone();
+        clash.rts.gpio.gdo2 = Some(24);
+        assert_eq!(
+            validate(&clash).unwrap_err().to_string(),
+            "rts.gpio.gdo0 and rts.gpio.gdo2 must not both use BCM GPIO 24"
+        );
     }
 }
@@ -1,76 +1,122 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{broadcast, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch, Mutex};
+use tokio::time::Instant;
 
 use crate::config::{DriverConfig, DriverKind, PositioningOptions};
 use crate::core::{Channel, Command};
 use crate::driver::{CommandOutcome, CommandRouter, SelectedChannelRx};
+use crate::history::{CommandSource, History, HistoryEntry};
+use crate::locks::{Lock, Locks};
+use crate::positioning::duty_cycle::DutyCycle;
+use crate::positioning::inventory::{Blind, BlindInventory};
 use crate::positioning::motion::{
-    plan_motion, BlindMovement, DriverStart, MotionPlan, MotionRequest, MotionTimings,
+    plan_motion, plan_movement, plan_my_movement, plan_tilt, BlindMovement, DriverStart,
+    MotionPlan, MotionRequest, MotionTimings,
 };
 use crate::positioning::motion_tasks::MotionTasks;
 use crate::positioning::state::{
-    find_blind, target_positions, BlindPosition, PositionCache, PositionDelta,
+    unix_now, BlindPosition, PositionCache, PositionDelta, Provenance, STATUS_STOPPED, TILT_MAX,
 };
+use crate::remotes::{LearnTimedOut, LearnedRemote, LearnedRemotes, RemotePress};
+use crate::rts::export::ExportedWaveform;
+use crate::rts::frame::{DecodedFrame, RtsCommand};
+use crate::safety::{SafetyHold, SafetyStatus};
 
 /// Driver-agnostic control of channel selection, button presses, and position events.
 pub struct BlindController {
     router: CommandRouter,
     driver_kind: DriverKind,
     operation_lock: Mutex<()>,
+    blinds: Arc<BlindInventory>,
     positions: Arc<PositionCache>,
     timings: MotionTimings,
+    duty_cycle: DutyCycle,
     motion_tasks: MotionTasks,
     position_tx: broadcast::Sender<Arc<[PositionDelta]>>,
+    remote_tx: broadcast::Sender<RemotePress>,
+    learned_remotes: LearnedRemotes,
+    history: History,
+    locks: Locks,
+    safety: watch::Sender<Vec<SafetyStatus>>,
 }
 
 impl fmt::Debug for BlindController {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("BlindController")
             .field("driver_kind", &self.driver_kind)
+            .field("blinds", &self.blinds.len())
             .field("position_subscribers", &self.position_tx.receiver_count())
             .finish_non_exhaustive()
     }
 }
 
 impl BlindController {
+    /// Controller backed by the on-disk state under `persist::state_dir()`.
+    /// Tests use [`Self::with_driver_and_positions_for_test`], which never
+    /// touches disk.
     pub(crate) async fn with_driver(
         config: DriverConfig,
+        blinds: BlindInventory,
         positioning: PositioningOptions,
     ) -> Result<Self> {
         let driver_kind = config.kind();
         let router = CommandRouter::new(config).await?;
         let (position_tx, _) = broadcast::channel(64);
+        let (remote_tx, _) = broadcast::channel(16);
+        let safety = watch::Sender::new(initial_safety(&blinds));
+        let blinds = Arc::new(blinds);
         Ok(Self {
             router,
             driver_kind,
             operation_lock: Mutex::new(()),
-            positions: Arc::new(PositionCache::new()),
+            positions: Arc::new(PositionCache::new(blinds.clone())),
+            blinds,
+            duty_cycle: DutyCycle::new(&positioning),
             timings: positioning.into(),
             motion_tasks: MotionTasks::default(),
             position_tx,
+            remote_tx,
+            learned_remotes: LearnedRemotes::open(),
+            history: History::open(),
+            locks: Locks::open(),
+            safety,
         })
     }
 
     #[cfg(test)]
     pub(crate) async fn with_driver_and_positions_for_test(
         config: DriverConfig,
+        blinds: BlindInventory,
         positioning: PositioningOptions,
         positions: HashMap<u64, u8>,
     ) -> Result<Self> {
         let driver_kind = config.kind();
         let router = CommandRouter::new(config).await?;
         let (position_tx, _) = broadcast::channel(64);
+        let (remote_tx, _) = broadcast::channel(16);
+        let safety = watch::Sender::new(initial_safety(&blinds));
+        let blinds = Arc::new(blinds);
         Ok(Self {
             router,
             driver_kind,
             operation_lock: Mutex::new(()),
-            positions: Arc::new(PositionCache::from_positions(positions)),
+            positions: Arc::new(PositionCache::from_positions(blinds.clone(), positions)),
+            blinds,
+            duty_cycle: DutyCycle::new(&positioning),
             timings: positioning.into(),
             motion_tasks: MotionTasks::default(),
             position_tx,
+            remote_tx,
+            learned_remotes: LearnedRemotes::in_memory(),
+            history: History::in_memory(),
+            locks: Locks::in_memory(),
+            safety,
         })
     }
 
@@ -83,6 +129,176 @@ impl BlindController {
         self.driver_kind
     }
 
+    /// Configured blinds (`[[blinds]]`) in config order.
+    pub fn blinds(&self) -> &BlindInventory {
+        &self.blinds
+    }
+
+    /// Journal of executed commands.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Maintenance locks that refuse moves on their channels.
+    pub fn locks(&self) -> &Locks {
+        &self.locks
+    }
+
+    /// Save `lock` and stop any blind it covers that is still moving. Stop is
+    /// only sent to moving blinds: an idle Somfy motor takes it as My.
+    pub async fn lock_channel(&self, lock: Lock) -> Result<Vec<PositionDelta>> {
+        let deltas = {
+            let _guard = self.operation_lock.lock().await;
+            let channel = lock.channel;
+            self.locks.insert(lock)?;
+            let moving = self.moving_aids(channel).await;
+            let mut deltas = self.snap_interrupted(&moving).await;
+            self.motion_tasks.cancel_many(&moving).await;
+            for aid in &moving {
+                if let Some(blind) = self.blinds.find(*aid) {
+                    self.router.execute_on(blind.channel, Command::Stop).await?;
+                }
+            }
+            deltas.extend(self.positions.stop_aids(&moving).await);
+            deltas
+        };
+        self.emit_position_deltas(&deltas);
+        Ok(deltas)
+    }
+
+    /// Blinds behind `channel` with a move in progress.
+    async fn moving_aids(&self, channel: Channel) -> Vec<u64> {
+        let aids = self.blinds.aids_for_channel(channel);
+        self.positions
+            .snapshot()
+            .await
+            .into_iter()
+            .filter(|position| position.status != STATUS_STOPPED && aids.contains(&position.aid))
+            .map(|position| position.aid)
+            .collect()
+    }
+
+    /// Refuse the move when a maintenance lock covers any of `channels`.
+    fn ensure_unlocked(&self, channels: impl IntoIterator<Item = Channel>) -> Result<()> {
+        self.locks.check(&self.blinds, channels)?;
+        Ok(())
+    }
+
+    /// Current state of every `[[safety.inputs]]` entry, in config order.
+    pub fn safety_status(&self) -> Vec<SafetyStatus> {
+        self.safety.borrow().clone()
+    }
+
+    /// Subscribe to safety input changes (HomeKit bridge, SSE).
+    pub fn subscribe_safety(&self) -> watch::Receiver<Vec<SafetyStatus>> {
+        self.safety.subscribe()
+    }
+
+    /// Mark the `name` safety input active and drive its blinds up. Downward
+    /// moves of those blinds are refused until [`Self::clear_safety`].
+    pub async fn trip_safety(self: &Arc<Self>, name: &str) -> Result<Vec<PositionDelta>> {
+        let Some(sensor) = self.blinds.safety_sensor_named(name) else {
+            bail!("unknown safety input `{name}`");
+        };
+        let since = unix_now();
+        self.safety.send_modify(|statuses| {
+            for status in statuses.iter_mut().filter(|status| status.name == name) {
+                status.active = true;
+                status.since.get_or_insert(since);
+            }
+        });
+        self.retract(name, &sensor.aids).await
+    }
+
+    pub fn clear_safety(&self, name: &str) {
+        self.safety.send_modify(|statuses| {
+            for status in statuses.iter_mut().filter(|status| status.name == name) {
+                status.active = false;
+                status.since = None;
+            }
+        });
+    }
+
+    /// Send `up` to `aids` for the tripped safety input `input`, whatever
+    /// their estimated position. Locked blinds are left alone, and the
+    /// duty-cycle budget is not charged: retracting in a storm is not
+    /// optional. Every channel is tried and journaled; the first failure is
+    /// returned once the rest have been sent.
+    async fn retract(self: &Arc<Self>, input: &str, aids: &[u64]) -> Result<Vec<PositionDelta>> {
+        let source = CommandSource::Safety {
+            input: input.to_string(),
+        };
+        let (deltas, result) = {
+            let _guard = self.operation_lock.lock().await;
+            let mut unlocked = Vec::with_capacity(aids.len());
+            for aid in aids {
+                let Some(blind) = self.blinds.find(*aid) else {
+                    continue;
+                };
+                match self.locks.check(&self.blinds, [blind.channel]) {
+                    Ok(()) => unlocked.push(*aid),
+                    Err(locked) => {
+                        tracing::warn!("not retracting: {locked}");
+                        self.history.record(HistoryEntry::now(
+                            source.clone(),
+                            "up",
+                            Some(blind.channel),
+                            None,
+                            Err::<(), _>(locked),
+                        ));
+                    }
+                }
+            }
+            let channels = match self.blinds.shared_channel(&unlocked) {
+                Some(channel) => vec![channel],
+                None => self.target_channels(unlocked),
+            };
+            let mut deltas = Vec::new();
+            let mut result = Ok(());
+            for channel in channels {
+                let sent = self.transmit(channel, Command::Up).await;
+                self.history.record(HistoryEntry::now(
+                    source.clone(),
+                    "up",
+                    Some(channel),
+                    None,
+                    sent.as_ref().map(|_| ()).map_err(|e| format!("{e:#}")),
+                ));
+                match sent {
+                    Ok(()) => deltas.extend(self.complete_command(channel, Command::Up).await.1),
+                    Err(e) if result.is_ok() => result = Err(e),
+                    Err(_) => {}
+                }
+            }
+            (deltas, result)
+        };
+        self.emit_position_deltas(&deltas);
+        result.map(|()| deltas)
+    }
+
+    /// Refuse a downward move of any of `aids` held up by an active safety input.
+    fn ensure_may_lower(&self, aids: impl IntoIterator<Item = u64>) -> Result<()> {
+        let statuses = self.safety.borrow();
+        for aid in aids {
+            let held = statuses
+                .iter()
+                .filter(|status| status.active)
+                .find(|status| {
+                    self.blinds
+                        .safety_sensor_named(&status.name)
+                        .is_some_and(|sensor| sensor.aids.contains(&aid))
+                });
+            if let (Some(status), Some(blind)) = (held, self.blinds.find(aid)) {
+                return Err(SafetyHold {
+                    channel: blind.channel,
+                    input: status.name.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
     /// Return the latest known channel selector state.
     pub fn current_selection(&self) -> Channel {
         self.router.selected_channel()
@@ -99,6 +315,154 @@ impl BlindController {
         self.position_tx.subscribe()
     }
 
+    /// Subscribe to presses heard from other RTS remotes.
+    pub fn subscribe_remote_presses(&self) -> broadcast::Receiver<RemotePress> {
+        self.remote_tx.subscribe()
+    }
+
+    /// Decoded frames from the driver's radio; `None` without one.
+    pub(crate) fn subscribe_remote_frames(&self) -> Option<broadcast::Receiver<DecodedFrame>> {
+        self.router.subscribe_remote_frames()
+    }
+
+    /// Build the RTS waveform for `command` on `channel` for another
+    /// transmitter, consuming its rolling code. Positions are not inferred:
+    /// the press has not happened yet.
+    pub async fn export_rts(
+        &self,
+        channel: Channel,
+        command: RtsCommand,
+        long: bool,
+    ) -> Result<ExportedWaveform> {
+        self.router.export_rts(channel, command, long).await
+    }
+
+    /// Physical remotes learned with `somfy rts learn`.
+    pub fn learned_remotes(&self) -> &LearnedRemotes {
+        &self.learned_remotes
+    }
+
+    /// Wait up to `timeout` for a press from a remote that is not one of our
+    /// own virtual remotes and record it as `name`.
+    pub async fn learn_remote(&self, name: &str, timeout: Duration) -> Result<LearnedRemote> {
+        let Some(mut frames) = self.router.subscribe_remote_frames() else {
+            bail!("learning remotes requires driver = \"rts\" with rts.gpio.gdo2 wired");
+        };
+        let frame = tokio::time::timeout(timeout, async {
+            loop {
+                match frames.recv().await {
+                    Ok(frame) if self.router.remote_channel(frame.remote_id).await.is_none() => {
+                        return Ok(frame);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => {
+                        bail!("the RTS receiver stopped");
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|_| LearnTimedOut {
+            secs: timeout.as_secs(),
+        })??;
+        let remote = self.learned_remotes.learn(LearnedRemote {
+            name: name.to_string(),
+            remote_id: frame.remote_id,
+            rolling_code: frame.rolling_code,
+            channels: Vec::new(),
+        })?;
+        tracing::info!(name, remote_id = frame.remote_id, "learned RTS remote");
+        Ok(remote)
+    }
+
+    /// Bind the learned remote `name` to `channels`, which must be configured.
+    pub fn bind_remote(&self, name: &str, channels: Vec<Channel>) -> Result<LearnedRemote> {
+        if let Some(channel) = channels
+            .iter()
+            .find(|channel| !self.blinds.contains_channel(**channel))
+        {
+            bail!("channel {channel} is not configured in [[blinds]]");
+        }
+        self.learned_remotes.bind(name, channels)
+    }
+
+    /// Publish a frame heard from another remote. Frames carrying one of our
+    /// own virtual remote addresses, such as an exported frame replayed by
+    /// another transmitter, also move that channel's cached positions; frames
+    /// from a learned remote move the channels it is bound to and are
+    /// recorded in the history under its name.
+    pub async fn observe_remote_frame(self: &Arc<Self>, frame: DecodedFrame) -> Vec<PositionDelta> {
+        let own = self.router.remote_channel(frame.remote_id).await;
+        let learned = match own {
+            Some(_) => None,
+            None => match self
+                .learned_remotes
+                .heard(frame.remote_id, frame.rolling_code)
+            {
+                Ok(learned) => learned,
+                Err(stale) => {
+                    // A motor would ignore it, so positions must not move.
+                    tracing::warn!("ignoring remote press: {stale}");
+                    return Vec::new();
+                }
+            },
+        };
+        let channels = match (own, &learned) {
+            (Some(channel), _) => vec![channel],
+            (None, Some(remote)) => remote.channels.clone(),
+            (None, None) => Vec::new(),
+        };
+        let mut deltas = Vec::new();
+        for channel in &channels {
+            deltas.extend(self.apply_remote_press(*channel, frame.command).await);
+            if let Some(remote) = &learned {
+                self.history.record(HistoryEntry::now(
+                    CommandSource::Remote {
+                        name: remote.name.clone(),
+                    },
+                    Command::from(frame.command).to_string(),
+                    Some(*channel),
+                    None,
+                    Ok::<(), anyhow::Error>(()),
+                ));
+            }
+        }
+        let _ = self.remote_tx.send(RemotePress {
+            remote_id: frame.remote_id,
+            rolling_code: frame.rolling_code,
+            command: frame.command,
+            remote: learned.map(|remote| remote.name),
+            channels,
+            at: unix_now(),
+        });
+        deltas
+    }
+
+    /// Run position inference for a press another remote already sent on
+    /// `channel`, exactly as for our own command. Nothing is transmitted, and
+    /// locks and the duty-cycle budget do not apply to what already happened.
+    pub async fn apply_remote_press(
+        self: &Arc<Self>,
+        channel: Channel,
+        command: RtsCommand,
+    ) -> Vec<PositionDelta> {
+        let deltas = {
+            let _guard = self.operation_lock.lock().await;
+            match command {
+                RtsCommand::Up => self.complete_command(channel, Command::Up).await.1,
+                RtsCommand::Down => self.complete_command(channel, Command::Down).await.1,
+                // The middle button: Stop while moving, My while idle.
+                RtsCommand::Stop => {
+                    let (stopped, movements) = self.plan_my(channel).await;
+                    self.complete_my(stopped, movements).await
+                }
+                RtsCommand::Prog => Vec::new(),
+            }
+        };
+        self.emit_position_deltas(&deltas);
+        deltas
+    }
+
     #[cfg(test)]
     pub(crate) fn emit_position_deltas_for_test(&self, deltas: &[PositionDelta]) {
         self.emit_position_deltas(deltas);
@@ -115,6 +479,11 @@ impl BlindController {
         self.positions.snapshot().await
     }
 
+    /// Drift bookkeeping used by the re-homing policy, keyed by aid.
+    pub async fn provenance_snapshot(&self) -> HashMap<u64, Provenance> {
+        self.positions.provenance_snapshot().await
+    }
+
     #[cfg(test)]
     pub async fn position_for_aid(&self, aid: u64) -> BlindPosition {
         self.positions
@@ -129,41 +498,112 @@ impl BlindController {
         self: &Arc<Self>,
         channel: Option<Channel>,
         position: u8,
+        source: CommandSource,
     ) -> Result<Vec<PositionDelta>> {
         let channel = channel.unwrap_or_else(|| self.current_selection());
-        self.set_target_positions(target_positions(channel, position))
+        self.set_target_positions(self.blinds.target_positions(channel, position), source)
             .await
     }
 
+    /// Move each blind to its target. A re-homing move is checked and
+    /// reserved for both legs here; `source` is journaled for the second leg.
     pub async fn set_target_positions(
         self: &Arc<Self>,
         targets: Vec<(u64, u8)>,
+        source: CommandSource,
     ) -> Result<Vec<PositionDelta>> {
         let deltas = {
             let _guard = self.operation_lock.lock().await;
-            let requests = self.build_motion_requests(targets).await;
-            match plan_motion(&requests) {
+            self.ensure_unlocked(self.target_channels(targets.iter().map(|(aid, _)| *aid)))?;
+            let aids: Vec<u64> = targets.iter().map(|(aid, _)| *aid).collect();
+            // Plan from the elapsed-time estimate, but only record it once
+            // the move is accepted: a refused move leaves the motor running.
+            let interrupted = self.motion_tasks.interpolated(&aids).await;
+            let requests = self.build_motion_requests(targets, &interrupted).await;
+            let plan = plan_motion(&requests, &self.blinds);
+            if let MotionPlan::Travel { movements, .. } = &plan {
+                // Check the legs, not the targets: re-homing can route an
+                // upward move through the closed end stop.
+                let continuations: Vec<BlindMovement> = movements
+                    .iter()
+                    .filter_map(|m| self.continuation(m))
+                    .collect();
+                let legs = || movements.iter().chain(&continuations);
+                self.ensure_may_lower(
+                    legs()
+                        .filter(|m| m.command == Command::Down || m.target == 0)
+                        .map(|m| m.blind.aid),
+                )?;
+                self.reserve_run_time(legs().map(|m| (&m.blind, m.duration)))?;
+            }
+            let mut deltas = self.apply_interrupted(interrupted).await;
+            deltas.extend(match plan {
                 MotionPlan::NoOp => Vec::new(),
                 MotionPlan::CancelAndSnap { requests } => {
                     self.cancel_inflight_and_snap(requests).await?
                 }
                 MotionPlan::Travel { starts, movements } => {
-                    self.execute_travel(starts, movements).await?
+                    self.execute_travel(starts, movements, source).await?
                 }
-            }
+            });
+            deltas
         };
         self.emit_position_deltas(&deltas);
         Ok(deltas)
     }
 
-    async fn build_motion_requests(&self, targets: Vec<(u64, u8)>) -> Vec<MotionRequest> {
+    /// Second leg of a re-homing `movement`, from its end stop to the
+    /// requested target.
+    fn continuation(&self, movement: &BlindMovement) -> Option<BlindMovement> {
+        plan_movement(&MotionRequest {
+            blind: movement.blind.clone(),
+            current: movement.target,
+            target: movement.then?,
+            timing: self.timings.for_channel(movement.blind.channel),
+            rehome: false,
+        })
+    }
+
+    /// Channels of the configured blinds among `aids`.
+    fn target_channels(&self, aids: impl IntoIterator<Item = u64>) -> Vec<Channel> {
+        aids.into_iter()
+            .filter_map(|aid| self.blinds.find(aid).map(|blind| blind.channel))
+            .collect()
+    }
+
+    /// Re-estimate `current` for blinds whose timed travel is about to be
+    /// interrupted, from the time elapsed since the move started. Callers hold
+    /// the operation lock and cancel the motion afterwards.
+    async fn snap_interrupted(&self, aids: &[u64]) -> Vec<PositionDelta> {
+        let interrupted = self.motion_tasks.interpolated(aids).await;
+        self.apply_interrupted(interrupted).await
+    }
+
+    /// Record estimates taken with [`MotionTasks::interpolated`].
+    async fn apply_interrupted(&self, interrupted: Vec<(Blind, u8)>) -> Vec<PositionDelta> {
+        let mut deltas = Vec::new();
+        for (blind, position) in interrupted {
+            deltas.extend(self.positions.apply_progress(&blind, position).await);
+        }
+        deltas
+    }
+
+    /// Motion requests for `targets`, starting interrupted blinds from their
+    /// estimate in `interrupted` rather than the cached position.
+    async fn build_motion_requests(
+        &self,
+        targets: Vec<(u64, u8)>,
+        interrupted: &[(Blind, u8)],
+    ) -> Vec<MotionRequest> {
         let snapshot = self.positions.snapshot().await;
         let positions: HashMap<u64, BlindPosition> =
             snapshot.into_iter().map(|p| (p.aid, p)).collect();
+        let provenance = self.positions.provenance_snapshot().await;
+        let now = unix_now();
 
         let mut requests = Vec::with_capacity(targets.len());
         for (aid, target) in targets {
-            let Some(blind) = find_blind(aid) else {
+            let Some(blind) = self.blinds.find(aid) else {
                 tracing::warn!(aid, "ignoring position target for unknown accessory");
                 continue;
             };
@@ -175,16 +615,67 @@ impl BlindController {
             if position.target == target {
                 continue;
             }
+            let current = interrupted
+                .iter()
+                .find(|(blind, _)| blind.aid == aid)
+                .map_or(position.current, |(_, estimate)| *estimate);
+            let rehome = self
+                .timings
+                .rehome_policy(blind.channel)
+                .is_due(provenance.get(&aid).copied().unwrap_or_default(), now);
             requests.push(MotionRequest {
-                blind,
-                current: position.current,
+                blind: blind.clone(),
+                current,
                 target,
                 timing: self.timings.for_channel(blind.channel),
+                rehome,
             });
         }
         requests
     }
 
+    /// Charge planned motor run time against each blind's duty-cycle budget.
+    /// Fails with [`MotorResting`](crate::positioning::duty_cycle::MotorResting)
+    /// before anything is transmitted when a motor needs to cool down.
+    fn reserve_run_time<'a>(
+        &self,
+        runs: impl IntoIterator<Item = (&'a Blind, Duration)>,
+    ) -> Result<()> {
+        self.duty_cycle.reserve(runs, std::time::Instant::now())?;
+        Ok(())
+    }
+
+    /// Run time of each blind behind `channel` travelling to the end stop
+    /// that `command` drives it to. Empty for commands that do not travel.
+    async fn end_stop_runs(&self, channel: Channel, command: Command) -> Vec<(Blind, Duration)> {
+        let Some(target) = infer_position(command) else {
+            return Vec::new();
+        };
+        let positions: HashMap<u64, BlindPosition> = self
+            .positions
+            .snapshot()
+            .await
+            .into_iter()
+            .map(|p| (p.aid, p))
+            .collect();
+        self.blinds
+            .aids_for_channel(channel)
+            .into_iter()
+            .filter_map(|aid| {
+                let blind = self.blinds.find(aid)?;
+                let current = positions.get(&aid).map_or(0, |position| position.current);
+                let movement = plan_movement(&MotionRequest {
+                    blind: blind.clone(),
+                    current,
+                    target,
+                    timing: self.timings.for_channel(blind.channel),
+                    rehome: false,
+                })?;
+                Some((blind.clone(), movement.duration))
+            })
+            .collect()
+    }
+
     async fn cancel_inflight_and_snap(
         &self,
         requests: Vec<MotionRequest>,
@@ -198,7 +689,7 @@ impl BlindController {
             }
             deltas.extend(
                 self.positions
-                    .apply_blind_current(request.blind, request.target)
+                    .apply_blind_current(&request.blind, request.target)
                     .await,
             );
         }
@@ -209,6 +700,7 @@ impl BlindController {
         self: &Arc<Self>,
         starts: Vec<DriverStart>,
         movements: Vec<BlindMovement>,
+        source: CommandSource,
     ) -> Result<Vec<PositionDelta>> {
         for movement in &movements {
             self.motion_tasks.cancel(movement.blind.aid).await;
@@ -220,12 +712,16 @@ impl BlindController {
 
         let mut deltas = Vec::new();
         for movement in movements {
+            let target = movement.then.unwrap_or(movement.target);
             deltas.extend(
                 self.positions
-                    .apply_target(movement.blind, movement.target, movement.status)
+                    .apply_target(&movement.blind, target, movement.status)
                     .await,
             );
-            self.schedule_completion(movement).await;
+            let continuation = self
+                .continuation(&movement)
+                .map(|leg| (leg, source.clone()));
+            self.schedule_completion(movement, continuation).await;
         }
         Ok(deltas)
     }
@@ -235,18 +731,49 @@ impl BlindController {
     /// channel directly without changing logical selection when the driver
     /// supports that distinction.
     pub async fn execute(
-        &self,
+        self: &Arc<Self>,
         command: Command,
         channel: Option<Channel>,
     ) -> Result<CommandOutcome> {
         let (outcome, deltas) = {
             let _guard = self.operation_lock.lock().await;
+            if command != Command::Select {
+                let target = channel.unwrap_or_else(|| self.current_selection());
+                if command == Command::Stop {
+                    // Stop stays available so a locked blind can always be
+                    // halted, but an idle motor takes it as My and moves.
+                    let moving = self.moving_aids(target).await;
+                    let idle = self
+                        .blinds
+                        .aids_for_channel(target)
+                        .into_iter()
+                        .filter(|aid| !moving.contains(aid));
+                    self.ensure_unlocked(self.target_channels(idle))?;
+                } else {
+                    self.ensure_unlocked([target])?;
+                }
+                // Stop is My on an idle blind, and must not halt a retraction.
+                if matches!(command, Command::Down | Command::My | Command::Stop) {
+                    self.ensure_may_lower(self.blinds.aids_for_channel(target))?;
+                }
+                let runs = if command == Command::Stop {
+                    let (_, movements) = self.plan_my(target).await;
+                    movements
+                        .into_iter()
+                        .map(|movement| (movement.blind, movement.duration))
+                        .collect()
+                } else {
+                    self.end_stop_runs(target, command).await
+                };
+                self.reserve_run_time(runs.iter().map(|(blind, run)| (blind, *run)))?;
+            }
             if command == Command::Select {
+                let channel = channel.or_else(|| self.next_selection());
                 self.router.execute(command, channel).await?;
                 let target = self.current_selection();
                 self.complete_command(target, command).await
             } else if let Some(channel) = channel {
-                self.router.execute_on(channel, command).await?;
+                self.transmit(channel, command).await?;
                 self.complete_command(channel, command).await
             } else {
                 self.router.execute(command, None).await?;
@@ -258,17 +785,198 @@ impl BlindController {
         Ok(outcome)
     }
 
+    /// Tilt the slats of every venetian blind behind `channel` (or the current
+    /// selection) to `angle` degrees.
+    pub async fn set_tilt_for_channel(
+        self: &Arc<Self>,
+        channel: Option<Channel>,
+        angle: i8,
+    ) -> Result<Vec<PositionDelta>> {
+        let channel = channel.unwrap_or_else(|| self.current_selection());
+        let targets: Vec<(u64, i8)> = self
+            .blinds
+            .aids_for_channel(channel)
+            .into_iter()
+            .filter(|aid| self.blinds.find(*aid).is_some_and(Blind::is_venetian))
+            .map(|aid| (aid, angle))
+            .collect();
+        if targets.is_empty() {
+            bail!("channel {channel} has no venetian blinds to tilt");
+        }
+        self.set_tilt_angles(targets).await
+    }
+
+    /// Pulse each venetian blind towards its requested slat angle and schedule
+    /// the stop. Tilting interrupts any travel in progress on the same blind.
+    pub async fn set_tilt_angles(
+        self: &Arc<Self>,
+        targets: Vec<(u64, i8)>,
+    ) -> Result<Vec<PositionDelta>> {
+        let deltas = {
+            let _guard = self.operation_lock.lock().await;
+            self.ensure_unlocked(self.target_channels(targets.iter().map(|(aid, _)| *aid)))?;
+            let positions: HashMap<u64, BlindPosition> = self
+                .positions
+                .snapshot()
+                .await
+                .into_iter()
+                .map(|p| (p.aid, p))
+                .collect();
+            self.ensure_may_lower(targets.iter().filter_map(|(aid, angle)| {
+                let current = positions
+                    .get(aid)
+                    .map_or(0, |position| position.current_tilt);
+                (*angle < current).then_some(*aid)
+            }))?;
+            let runs: Vec<(&Blind, Duration)> = targets
+                .iter()
+                .filter_map(|(aid, angle)| {
+                    let blind = self.blinds.find(*aid).filter(|blind| blind.is_venetian())?;
+                    let current = positions
+                        .get(aid)
+                        .map_or(0, |position| position.current_tilt);
+                    let timing = self.timings.for_channel(blind.channel);
+                    let tilt =
+                        plan_tilt(blind, current, (*angle).clamp(-TILT_MAX, TILT_MAX), &timing)?;
+                    Some((blind, tilt.duration))
+                })
+                .collect();
+            self.reserve_run_time(runs)?;
+            let mut deltas = Vec::new();
+            for (aid, angle) in targets {
+                let Some(blind) = self.blinds.find(aid).filter(|blind| blind.is_venetian()) else {
+                    tracing::warn!(aid, "ignoring tilt target for non-venetian accessory");
+                    continue;
+                };
+                let angle = angle.clamp(-TILT_MAX, TILT_MAX);
+                let position = positions
+                    .get(&aid)
+                    .copied()
+                    .unwrap_or_else(|| BlindPosition::default_for_aid(aid));
+                if position.target_tilt == angle && position.status == STATUS_STOPPED {
+                    continue;
+                }
+                let interrupted = self.motion_tasks.interpolated(&[aid]).await;
+                if self.motion_tasks.cancel(aid).await {
+                    self.router.execute_on(blind.channel, Command::Stop).await?;
+                    deltas.extend(self.apply_interrupted(interrupted).await);
+                    deltas.extend(self.positions.stop_aids(&[aid]).await);
+                }
+                let timing = self.timings.for_channel(blind.channel);
+                let Some(tilt) = plan_tilt(blind, position.current_tilt, angle, &timing) else {
+                    deltas.extend(self.positions.apply_tilt_current(blind, angle).await);
+                    continue;
+                };
+                self.router.execute_on(blind.channel, tilt.command).await?;
+                deltas.extend(self.positions.apply_tilt_target(blind, angle).await);
+                self.schedule_settle(tilt.blind, tilt.duration, true, Settle::Tilt(angle))
+                    .await;
+            }
+            deltas
+        };
+        self.emit_position_deltas(&deltas);
+        Ok(deltas)
+    }
+
+    /// Press My on `channel` (or the current selection). Idle blinds travel to
+    /// their configured `my_position`; moving blinds treat the press as Stop.
+    /// Idle blinds without a `my_position` keep their cached position.
+    pub async fn execute_my(
+        self: &Arc<Self>,
+        channel: Option<Channel>,
+    ) -> Result<Vec<PositionDelta>> {
+        let deltas = {
+            let _guard = self.operation_lock.lock().await;
+            let target = channel.unwrap_or_else(|| self.current_selection());
+            self.ensure_unlocked([target])?;
+            self.ensure_may_lower(self.blinds.aids_for_channel(target))?;
+            let (stopped, movements) = self.plan_my(target).await;
+            self.reserve_run_time(movements.iter().map(|m| (&m.blind, m.duration)))?;
+            match channel {
+                Some(channel) => self.transmit(channel, Command::My).await?,
+                None => self.router.execute(Command::My, None).await?,
+            }
+            self.complete_my(stopped, movements).await
+        };
+        self.emit_position_deltas(&deltas);
+        Ok(deltas)
+    }
+
+    /// Split the blinds behind `channel` into moving ones, which My stops,
+    /// and idle ones with a `my_position` to travel to.
+    async fn plan_my(&self, channel: Channel) -> (Vec<u64>, Vec<BlindMovement>) {
+        let positions: HashMap<u64, BlindPosition> = self
+            .positions
+            .snapshot()
+            .await
+            .into_iter()
+            .map(|p| (p.aid, p))
+            .collect();
+
+        let mut stopped = Vec::new();
+        let mut movements = Vec::new();
+        for aid in self.blinds.aids_for_channel(channel) {
+            let Some(blind) = self.blinds.find(aid) else {
+                continue;
+            };
+            let position = positions
+                .get(&aid)
+                .copied()
+                .unwrap_or_else(|| BlindPosition::default_for_aid(aid));
+            if position.status != STATUS_STOPPED {
+                stopped.push(aid);
+                continue;
+            }
+            let Some(target) = self.timings.my_position(blind.channel) else {
+                tracing::debug!(aid, "my pressed without my_position; position unchanged");
+                continue;
+            };
+            movements.extend(plan_my_movement(&MotionRequest {
+                blind: blind.clone(),
+                current: position.current,
+                target,
+                timing: self.timings.for_channel(blind.channel),
+                rehome: false,
+            }));
+        }
+        (stopped, movements)
+    }
+
+    async fn complete_my(
+        self: &Arc<Self>,
+        stopped: Vec<u64>,
+        movements: Vec<BlindMovement>,
+    ) -> Vec<PositionDelta> {
+        let mut deltas = self.snap_interrupted(&stopped).await;
+        self.motion_tasks.cancel_many(&stopped).await;
+        deltas.extend(self.positions.stop_aids(&stopped).await);
+        for movement in movements {
+            self.motion_tasks.cancel(movement.blind.aid).await;
+            deltas.extend(
+                self.positions
+                    .apply_target(&movement.blind, movement.target, movement.status)
+                    .await,
+            );
+            self.schedule_completion(movement, None).await;
+        }
+        deltas
+    }
+
     /// Run an action command directly on `channel`. RTS can do this without
     /// changing public selection state; Telis may update selection because
     /// targeting a channel requires moving the physical selector.
     #[cfg(test)]
-    pub async fn execute_on(&self, channel: Channel, command: Command) -> Result<CommandOutcome> {
+    pub async fn execute_on(
+        self: &Arc<Self>,
+        channel: Channel,
+        command: Command,
+    ) -> Result<CommandOutcome> {
         if command == Command::Select {
             anyhow::bail!("select is not a direct targeted command");
         }
         let (outcome, deltas) = {
             let _guard = self.operation_lock.lock().await;
-            self.router.execute_on(channel, command).await?;
+            self.transmit(channel, command).await?;
             self.complete_command(channel, command).await
         };
         self.emit_position_deltas(&deltas);
@@ -280,84 +988,255 @@ impl BlindController {
         self.router.operations()
     }
 
+    /// Send `command` on `channel`. A group without a paired virtual remote has
+    /// nothing to transmit on, so its commands go to each member blind instead;
+    /// pairing commands always target the group's own remote.
+    async fn transmit(&self, channel: Channel, command: Command) -> Result<()> {
+        match self.blinds.group(channel) {
+            Some(group)
+                if !group.paired && !matches!(command, Command::Prog | Command::ProgLong) =>
+            {
+                for member in &group.members {
+                    self.router.execute_on(*member, command).await?;
+                }
+                Ok(())
+            }
+            _ => self.router.execute_on(channel, command).await,
+        }
+    }
+
+    /// Channel an unqualified `select` moves to. The Telis remote cycles its own
+    /// LED rows; the other drivers step through the configured blinds, then ALL.
+    fn next_selection(&self) -> Option<Channel> {
+        if self.driver_kind == DriverKind::Telis {
+            return None;
+        }
+        Some(self.current_selection().next_in(&self.blinds.channels()))
+    }
+
     async fn complete_command(
-        &self,
+        self: &Arc<Self>,
         channel: Channel,
         command: Command,
     ) -> (CommandOutcome, Vec<PositionDelta>) {
         let inferred_position = infer_position(command);
         let deltas = match (command, inferred_position) {
             (_, Some(position)) => {
-                self.motion_tasks.cancel_channel(channel).await;
+                self.motion_tasks
+                    .cancel_many(&self.blinds.aids_for_channel(channel))
+                    .await;
                 self.positions.apply_for_channel(channel, position).await
             }
+            // The middle button: Stop while moving, My while idle.
+            (Command::Stop, None) if self.moving_aids(channel).await.is_empty() => {
+                let (stopped, movements) = self.plan_my(channel).await;
+                self.complete_my(stopped, movements).await
+            }
             (Command::Stop, None) => {
-                self.motion_tasks.cancel_channel(channel).await;
-                self.positions.stop_channel(channel).await
+                let aids = self.blinds.aids_for_channel(channel);
+                let mut deltas = self.snap_interrupted(&aids).await;
+                self.motion_tasks.cancel_many(&aids).await;
+                deltas.extend(self.positions.stop_channel(channel).await);
+                deltas
             }
             _ => Vec::new(),
         };
         (CommandOutcome { inferred_position }, deltas)
     }
 
-    async fn schedule_completion(self: &Arc<Self>, movement: BlindMovement) {
+    /// Settle `movement` once its travel time is up, then start `continuation`
+    /// when it is the first leg of a re-homing move.
+    async fn schedule_completion(
+        self: &Arc<Self>,
+        movement: BlindMovement,
+        continuation: Option<(BlindMovement, CommandSource)>,
+    ) {
+        self.schedule_settle(
+            movement.blind.clone(),
+            movement.duration,
+            movement.stop_at_end,
+            Settle::Travel(movement, continuation.map(Box::new)),
+        )
+        .await;
+    }
+
+    /// Wait out a timed move, publishing its interpolated position every
+    /// `update_interval` along the way.
+    async fn follow_travel(&self, movement: &BlindMovement, generation: u64, started: Instant) {
+        let finish = started + movement.duration;
+        if let Some(interval) = self.timings.update_interval() {
+            let mut tick = started + interval;
+            while tick < finish {
+                tokio::time::sleep_until(tick).await;
+                let deltas = {
+                    let _guard = self.operation_lock.lock().await;
+                    if !self
+                        .motion_tasks
+                        .is_current(movement.blind.aid, generation)
+                        .await
+                    {
+                        return;
+                    }
+                    self.positions
+                        .apply_progress(&movement.blind, movement.position_at(started.elapsed()))
+                        .await
+                };
+                self.emit_position_deltas(&deltas);
+                tick += interval;
+            }
+        }
+        tokio::time::sleep_until(finish).await;
+    }
+
+    /// After `duration`, optionally stop `blind` and record where it settled,
+    /// unless a newer motion for the same blind superseded this one.
+    async fn schedule_settle(
+        self: &Arc<Self>,
+        blind: Blind,
+        duration: Duration,
+        stop_at_end: bool,
+        settle: Settle,
+    ) {
         let controller = self.clone();
-        let generation = self.motion_tasks.replace(movement.blind.aid, None).await;
+        let aid = blind.aid;
+        let started = Instant::now();
+        let travel = match &settle {
+            Settle::Travel(movement, _) => Some((movement.clone(), started)),
+            Settle::Tilt(_) => None,
+        };
+        let generation = self.motion_tasks.begin(aid, travel).await;
         let handle = tokio::spawn(async move {
-            tokio::time::sleep(movement.duration).await;
+            match &settle {
+                Settle::Travel(movement, _) => {
+                    controller
+                        .follow_travel(movement, generation, started)
+                        .await;
+                }
+                Settle::Tilt(_) => tokio::time::sleep(duration).await,
+            }
             let deltas = {
                 let _guard = controller.operation_lock.lock().await;
-                if !controller
-                    .motion_tasks
-                    .is_current(movement.blind.aid, generation)
-                    .await
-                {
+                if !controller.motion_tasks.is_current(aid, generation).await {
                     return;
                 }
-                if movement.stop_at_end {
+                if stop_at_end {
                     if let Err(e) = controller
                         .router
-                        .execute_on(movement.blind.channel, Command::Stop)
+                        .execute_on(blind.channel, Command::Stop)
                         .await
                     {
                         tracing::warn!(
-                            aid = movement.blind.aid,
-                            channel = %movement.blind.channel,
+                            aid,
+                            channel = %blind.channel,
                             "failed to stop timed motion: {e}"
                         );
                         return;
                     }
                 }
-                if !controller
-                    .motion_tasks
-                    .is_current(movement.blind.aid, generation)
-                    .await
-                {
+                if !controller.motion_tasks.is_current(aid, generation).await {
                     return;
                 }
-                let deltas = controller
-                    .positions
-                    .apply_blind_current(movement.blind, movement.target)
-                    .await;
+                let (mut deltas, continuation) = match settle {
+                    Settle::Travel(movement, continuation) => (
+                        controller
+                            .positions
+                            .apply_blind_current(&blind, movement.target)
+                            .await,
+                        continuation,
+                    ),
+                    Settle::Tilt(angle) => (
+                        controller.positions.apply_tilt_current(&blind, angle).await,
+                        None,
+                    ),
+                };
                 controller
                     .motion_tasks
-                    .remove_if_current(movement.blind.aid, generation)
+                    .remove_if_current(aid, generation)
                     .await;
+                if let Some((leg, source)) = continuation.map(|boxed| *boxed) {
+                    deltas.extend(controller.continue_after_rehome(leg, source).await);
+                }
                 deltas
             };
             controller.emit_position_deltas(&deltas);
         });
         self.motion_tasks
-            .attach_handle(movement.blind.aid, generation, handle)
+            .attach_handle(aid, generation, handle)
+            .await;
+    }
+
+    /// Start the second leg of a re-homing move once the first settles at its
+    /// end stop, under the same operation lock. Its run time was reserved with
+    /// the first leg, but a lock or safety input can still have come on since;
+    /// a refused leg leaves the blind at the end stop. Either way the outcome
+    /// is journaled under the original `source`. Boxed because it schedules
+    /// the settle task it runs from.
+    fn continue_after_rehome<'a>(
+        self: &'a Arc<Self>,
+        leg: BlindMovement,
+        source: CommandSource,
+    ) -> Pin<Box<dyn Future<Output = Vec<PositionDelta>> + Send + 'a>> {
+        Box::pin(async move {
+            let channel = leg.blind.channel;
+            let target = leg.target;
+            let started = async {
+                self.ensure_unlocked([channel])?;
+                if leg.command == Command::Down {
+                    self.ensure_may_lower([leg.blind.aid])?;
+                }
+                self.router.execute_on(channel, leg.command).await
+            }
             .await;
+            self.history.record(HistoryEntry::now(
+                source,
+                "rehome",
+                Some(channel),
+                Some(i16::from(target)),
+                started.as_ref().map(|_| ()).map_err(|e| format!("{e:#}")),
+            ));
+            if let Err(e) = started {
+                tracing::warn!(
+                    aid = leg.blind.aid,
+                    "failed to continue re-homing move: {e:#}"
+                );
+                return Vec::new();
+            }
+            let deltas = self
+                .positions
+                .apply_target(&leg.blind, target, leg.status)
+                .await;
+            self.schedule_completion(leg, None).await;
+            deltas
+        })
     }
 }
 
+/// Where a timed motion leaves the blind once it completes.
+#[derive(Clone, Debug)]
+enum Settle {
+    /// Timed travel, and the re-homing leg to start once it settles.
+    Travel(BlindMovement, Option<Box<(BlindMovement, CommandSource)>>),
+    Tilt(i8),
+}
+
+/// Every configured safety input, inactive.
+fn initial_safety(blinds: &BlindInventory) -> Vec<SafetyStatus> {
+    blinds
+        .safety_sensors()
+        .map(|sensor| SafetyStatus {
+            name: sensor.name.clone(),
+            channels: sensor.channels.clone(),
+            active: false,
+            since: None,
+        })
+        .collect()
+}
+
 fn infer_position(command: Command) -> Option<u8> {
     match command {
         Command::Up => Some(100),
         Command::Down => Some(0),
-        Command::Stop | Command::Select | Command::Prog | Command::ProgLong => None,
+        Command::Stop | Command::My | Command::Select | Command::Prog | Command::ProgLong => None,
     }
 }
 
@@ -1,7 +1,7 @@
 use super::*;
-use crate::config::{DriverConfig, PositioningOptions};
+use crate::config::{DriverConfig, GroupOptions, PositioningOptions};
 use crate::driver::ProtocolOperation;
-use crate::testing::fixtures::{fake_controller, uniform_positioning_l1_ms};
+use crate::testing::fixtures::{fake_controller, inventory, uniform_positioning_l1_ms};
 use std::collections::HashMap;
 use tokio::time::{timeout, Duration};
 
@@ -20,9 +20,16 @@ fn position_inference_only_tracks_directional_extremes() {
 
 #[tokio::test]
 async fn client_command_with_channel_targets_without_selection() {
-    let controller = BlindController::with_driver(DriverConfig::fake(), controller_config())
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            BlindInventory::default(),
+            controller_config(),
+            HashMap::new(),
+        )
         .await
-        .unwrap();
+        .unwrap(),
+    );
 
     controller
         .execute(Command::Up, Some(Channel::L3))
@@ -43,9 +50,14 @@ async fn client_command_with_channel_targets_without_selection() {
 #[tokio::test]
 async fn controller_operations_wait_behind_operation_lock() {
     let controller = Arc::new(
-        BlindController::with_driver(DriverConfig::fake(), controller_config())
-            .await
-            .unwrap(),
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            BlindInventory::default(),
+            controller_config(),
+            HashMap::new(),
+        )
+        .await
+        .unwrap(),
     );
     let guard = controller.lock_operations_for_test().await;
     let pending_controller = controller.clone();
@@ -82,8 +94,11 @@ async fn target_position_writes_wait_behind_operation_lock() {
     let guard = controller.lock_operations_for_test().await;
     let pending_controller = controller.clone();
 
-    let operation =
-        tokio::spawn(async move { pending_controller.set_target_positions(vec![(2, 50)]).await });
+    let operation = tokio::spawn(async move {
+        pending_controller
+            .set_target_positions(vec![(2, 50)], CommandSource::Cli)
+            .await
+    });
 
     assert!(timeout(Duration::from_millis(10), async {
         while !operation.is_finished() {
@@ -106,9 +121,16 @@ async fn target_position_writes_wait_behind_operation_lock() {
 
 #[tokio::test]
 async fn execute_on_rejects_select() {
-    let controller = BlindController::with_driver(DriverConfig::fake(), controller_config())
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            BlindInventory::default(),
+            controller_config(),
+            HashMap::new(),
+        )
         .await
-        .unwrap();
+        .unwrap(),
+    );
 
     let err = controller
         .execute_on(Channel::L2, Command::Select)
@@ -125,7 +147,7 @@ async fn target_position_updates_shared_position_model() {
     let controller = fake_controller(uniform_positioning_l1_ms(2), HashMap::from([(2, 100)])).await;
 
     let deltas = controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
 
@@ -141,7 +163,7 @@ async fn target_position_matching_cached_current_is_noop() {
     let mut position_rx = controller.subscribe_positions();
 
     let deltas = controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
 
@@ -157,13 +179,13 @@ async fn target_position_matching_pending_target_is_noop() {
     let controller =
         fake_controller(uniform_positioning_l1_ms(50), HashMap::from([(2, 100)])).await;
     controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
     let mut position_rx = controller.subscribe_positions();
 
     let deltas = controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
 
@@ -187,7 +209,7 @@ async fn manual_stop_cancels_timed_position_completion() {
     let controller =
         fake_controller(uniform_positioning_l1_ms(100), HashMap::from([(2, 100)])).await;
     controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
     let mut position_rx = controller.subscribe_positions();
@@ -226,7 +248,7 @@ async fn position_broadcast_runs_once_per_non_empty_emit() {
     let mut position_rx = controller.subscribe_positions();
 
     controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
         .await
         .unwrap();
 
@@ -249,6 +271,8 @@ async fn position_broadcast_while_operation_lock_held() {
         current: None,
         target: Some(50),
         status: Some(STATUS_INCREASING),
+        current_tilt: None,
+        target_tilt: None,
     }]);
 
     let published = tokio::time::timeout(Duration::from_millis(10), position_rx.recv())
@@ -264,10 +288,1040 @@ async fn position_broadcast_not_sent_when_emit_empty() {
     let mut position_rx = controller.subscribe_positions();
 
     let deltas = controller
-        .set_target_positions(vec![(2, 50)])
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
+        .await
+        .unwrap();
+
+    assert!(deltas.is_empty());
+    assert!(position_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn select_without_channel_cycles_configured_blinds_then_all() {
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            inventory(&[(2, Channel::L1), (7, Channel::Individual(6))]),
+            controller_config(),
+            HashMap::new(),
+        )
+        .await
+        .unwrap(),
+    );
+
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        controller.execute(Command::Select, None).await.unwrap();
+        seen.push(controller.current_selection());
+    }
+
+    assert_eq!(
+        seen,
+        vec![Channel::Individual(6), Channel::All, Channel::L1]
+    );
+}
+
+#[tokio::test]
+async fn all_channel_target_fans_out_to_configured_blinds() {
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            inventory(&[(2, Channel::L1), (7, Channel::Individual(6))]),
+            controller_config(),
+            HashMap::from([(2, 100), (7, 100)]),
+        )
+        .await
+        .unwrap(),
+    );
+
+    let deltas = controller
+        .set_target_for_channel(Some(Channel::All), 0, CommandSource::Cli)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        deltas.iter().map(|delta| delta.aid).collect::<Vec<_>>(),
+        vec![2, 7]
+    );
+    assert_eq!(
+        controller.operations(),
+        vec![ProtocolOperation::FakeCommand {
+            channel: Channel::All,
+            command: Command::Down,
+        }]
+    );
+}
+
+fn living_room(paired: bool) -> BlindInventory {
+    inventory(&[(2, Channel::L1), (3, Channel::L2), (4, Channel::L3)]).with_groups(&[
+        GroupOptions {
+            name: "living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L1, Channel::L3],
+            aid: None,
+            paired,
+        },
+    ])
+}
+
+#[tokio::test]
+async fn unpaired_group_stop_is_sent_to_each_member() {
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            living_room(false),
+            controller_config(),
+            HashMap::new(),
+        )
+        .await
+        .unwrap(),
+    );
+
+    controller
+        .execute(Command::Stop, Some(Channel::Group(1)))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L3,
+                command: Command::Stop,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn paired_group_target_starts_on_group_remote() {
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            living_room(true),
+            controller_config(),
+            HashMap::from([(2, 100), (3, 100), (4, 100)]),
+        )
+        .await
+        .unwrap(),
+    );
+
+    let deltas = controller
+        .set_target_for_channel(Some(Channel::Group(1)), 0, CommandSource::Cli)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        deltas.iter().map(|delta| delta.aid).collect::<Vec<_>>(),
+        vec![2, 4]
+    );
+    assert_eq!(
+        controller.operations(),
+        vec![ProtocolOperation::FakeCommand {
+            channel: Channel::Group(1),
+            command: Command::Down,
+        }]
+    );
+}
+
+fn my_positioning(travel_ms: u64, my_position: Option<u8>) -> PositioningOptions {
+    let mut positioning = uniform_positioning_l1_ms(travel_ms);
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.my_position = my_position;
+    }
+    positioning
+}
+
+#[tokio::test]
+async fn my_on_idle_blind_travels_to_my_position_without_timed_stop() {
+    use crate::positioning::state::{STATUS_DECREASING, STATUS_STOPPED};
+
+    let controller = fake_controller(my_positioning(10, Some(40)), HashMap::from([(2, 100)])).await;
+
+    let deltas = controller.execute_my(Some(Channel::L1)).await.unwrap();
+
+    assert_eq!(deltas[0].target, Some(40));
+    assert_eq!(deltas[0].status, Some(STATUS_DECREASING));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current, 40);
+    assert_eq!(position.status, STATUS_STOPPED);
+    assert_eq!(
+        controller.operations(),
+        vec![ProtocolOperation::FakeCommand {
+            channel: Channel::L1,
+            command: Command::My,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn my_on_moving_blind_acts_as_stop() {
+    use crate::positioning::state::STATUS_STOPPED;
+
+    let controller =
+        fake_controller(my_positioning(100, Some(40)), HashMap::from([(2, 100)])).await;
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
         .await
         .unwrap();
 
+    let deltas = controller.execute_my(Some(Channel::L1)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(120)).await;
+
+    assert_eq!(deltas[0].target, Some(100));
+    assert_eq!(deltas[0].status, Some(STATUS_STOPPED));
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current, 100);
+    assert_eq!(position.target, 100);
+}
+
+#[tokio::test]
+async fn my_without_configured_position_leaves_cache_untouched() {
+    let controller = fake_controller(my_positioning(10, None), HashMap::from([(2, 100)])).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    let deltas = controller.execute_my(Some(Channel::L1)).await.unwrap();
+
     assert!(deltas.is_empty());
     assert!(position_rx.try_recv().is_err());
+    assert_eq!(controller.position_for_aid(2).await.current, 100);
+}
+
+#[tokio::test]
+async fn stop_on_idle_blind_travels_to_my_position() {
+    use crate::positioning::state::{STATUS_DECREASING, STATUS_STOPPED};
+
+    let controller = fake_controller(my_positioning(10, Some(40)), HashMap::from([(2, 100)])).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    controller
+        .execute(Command::Stop, Some(Channel::L1))
+        .await
+        .unwrap();
+
+    let deltas = position_rx.try_recv().unwrap();
+    assert_eq!(deltas[0].target, Some(40));
+    assert_eq!(deltas[0].status, Some(STATUS_DECREASING));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current, 40);
+    assert_eq!(position.status, STATUS_STOPPED);
+    assert_eq!(
+        controller.operations(),
+        vec![ProtocolOperation::FakeCommand {
+            channel: Channel::L1,
+            command: Command::Stop,
+        }]
+    );
+}
+
+fn venetian_l1() -> BlindInventory {
+    BlindInventory::from_options(&[crate::config::BlindOptions {
+        name: "Office".to_string(),
+        channel: Channel::L1,
+        aid: 2,
+        kind: crate::config::BlindKind::Venetian,
+    }])
+}
+
+async fn venetian_controller(travel_ms: u64, tilt_ms: u64) -> Arc<BlindController> {
+    let mut positioning = uniform_positioning_l1_ms(travel_ms);
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.tilt_ms = Some(tilt_ms);
+    }
+    Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            venetian_l1(),
+            positioning,
+            HashMap::from([(2, 50)]),
+        )
+        .await
+        .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn tilt_pulses_towards_angle_then_stops() {
+    use crate::positioning::state::STATUS_STOPPED;
+
+    let controller = venetian_controller(100, 40).await;
+
+    let deltas = controller
+        .set_tilt_for_channel(Some(Channel::L1), -45)
+        .await
+        .unwrap();
+
+    assert_eq!(deltas[0].target_tilt, Some(-45));
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current_tilt, -45);
+    assert_eq!(position.current, 50);
+    assert_eq!(position.status, STATUS_STOPPED);
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn tilt_rejects_channel_without_venetian_blinds() {
+    let controller = fake_controller(controller_config(), HashMap::from([(2, 50)])).await;
+
+    let error = controller
+        .set_tilt_for_channel(Some(Channel::L1), 30)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "channel L1 has no venetian blinds to tilt"
+    );
+    assert!(controller.operations().is_empty());
+}
+
+#[tokio::test]
+async fn full_travel_leaves_venetian_slats_at_limit() {
+    let controller = venetian_controller(10, 40).await;
+
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current, 0);
+    assert_eq!(position.current_tilt, -90);
+    assert_eq!(position.target_tilt, -90);
+}
+
+#[tokio::test]
+async fn timed_move_publishes_interpolated_progress() {
+    let mut positioning = uniform_positioning_l1_ms(300);
+    positioning.update_interval_ms = 100;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    let mut progress = Vec::new();
+    loop {
+        let deltas = timeout(Duration::from_secs(1), position_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let delta = deltas[0];
+        if delta.status == Some(crate::positioning::state::STATUS_STOPPED) {
+            assert_eq!(delta.current, Some(0));
+            break;
+        }
+        if delta.target.is_none() {
+            assert_eq!(delta.status, None);
+            progress.extend(delta.current);
+        }
+    }
+
+    assert!(!progress.is_empty());
+    assert!(
+        progress.windows(2).all(|pair| pair[0] > pair[1]),
+        "{progress:?}"
+    );
+    assert!(
+        progress.iter().all(|position| (1..100).contains(position)),
+        "{progress:?}"
+    );
+}
+
+#[tokio::test]
+async fn zero_update_interval_reports_only_start_and_end() {
+    let mut positioning = uniform_positioning_l1_ms(150);
+    positioning.update_interval_ms = 0;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let first = position_rx.try_recv().unwrap();
+    assert_eq!(first[0].target, Some(0));
+    let last = position_rx.try_recv().unwrap();
+    assert_eq!(last[0].current, Some(0));
+    assert!(position_rx.try_recv().is_err());
+}
+
+async fn interruptible_controller(travel_ms: u64) -> Arc<BlindController> {
+    let mut positioning = uniform_positioning_l1_ms(travel_ms);
+    positioning.update_interval_ms = 0;
+    fake_controller(positioning, HashMap::from([(2, 100)])).await
+}
+
+#[tokio::test]
+async fn stop_mid_travel_snaps_current_to_elapsed_estimate() {
+    use crate::positioning::state::STATUS_STOPPED;
+
+    let controller = interruptible_controller(1_000).await;
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    controller
+        .execute(Command::Stop, Some(Channel::L1))
+        .await
+        .unwrap();
+
+    let deltas = position_rx.try_recv().unwrap();
+    let snapped = deltas[0].current.unwrap();
+    assert!((60..=70).contains(&snapped), "{snapped}");
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.current, snapped);
+    assert_eq!(position.target, snapped);
+    assert_eq!(position.status, STATUS_STOPPED);
+}
+
+#[tokio::test]
+async fn new_target_mid_travel_plans_from_elapsed_estimate() {
+    use crate::positioning::state::STATUS_INCREASING;
+
+    let controller = interruptible_controller(1_000).await;
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    let deltas = controller
+        .set_target_positions(vec![(2, 100)], CommandSource::Cli)
+        .await
+        .unwrap();
+
+    let snapped = deltas[0].current.unwrap();
+    assert!((50..=60).contains(&snapped), "{snapped}");
+    assert_eq!(deltas[1].target, Some(100));
+    assert_eq!(deltas[1].status, Some(STATUS_INCREASING));
+    assert_eq!(controller.position_for_aid(2).await.current, snapped);
+    tokio::time::sleep(Duration::from_millis(550)).await;
+    assert_eq!(controller.position_for_aid(2).await.current, 100);
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Up,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn due_rehoming_passes_through_end_stop_before_target() {
+    let mut positioning = uniform_positioning_l1_ms(300);
+    positioning.update_interval_ms = 0;
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.rehome_after_moves = Some(2);
+    }
+    let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+
+    controller
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    controller
+        .set_target_positions(vec![(2, 40)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(controller.provenance_snapshot().await[&2].interior_moves, 2);
+    let before = controller.operations().len();
+
+    let deltas = controller
+        .set_target_positions(vec![(2, 30)], CommandSource::Cli)
+        .await
+        .unwrap();
+    assert_eq!(deltas[0].target, Some(30));
+    tokio::time::sleep(Duration::from_millis(350)).await;
+
+    assert_eq!(
+        controller.operations()[before..],
+        [
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Up,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+        ]
+    );
+    let position = controller.position_for_aid(2).await;
+    assert_eq!((position.current, position.target), (30, 30));
+    let provenance = controller.provenance_snapshot().await[&2];
+    assert_eq!(provenance.interior_moves, 1);
+    assert!(provenance.homed_at.is_some());
+    let history = controller
+        .history()
+        .query(&crate::history::HistoryFilter::default());
+    assert_eq!(
+        (
+            &history[0].source,
+            history[0].command.as_str(),
+            history[0].target
+        ),
+        (&CommandSource::Cli, "rehome", Some(30))
+    );
+    assert_eq!(history[0].outcome, crate::history::Outcome::Ok);
+}
+
+#[tokio::test]
+async fn rehoming_is_refused_up_front_when_both_legs_exceed_the_budget() {
+    use crate::positioning::duty_cycle::MotorResting;
+
+    let mut positioning = uniform_positioning_l1_ms(1_500);
+    positioning.run_budget_secs = 1;
+    positioning.run_window_secs = 1_000;
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.rehome_after_hours = Some(1);
+    }
+    let controller = fake_controller(positioning, HashMap::from([(2, 60)])).await;
+
+    // 60 -> 100 fits the budget on its own, 100 -> 50 does not.
+    let err = controller
+        .set_target_positions(vec![(2, 50)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+
+    assert!(err.downcast_ref::<MotorResting>().is_some(), "{err}");
+    assert!(controller.operations().is_empty());
+    let position = controller.position_for_aid(2).await;
+    assert_eq!((position.current, position.target), (60, 60));
+}
+
+#[tokio::test]
+async fn rehoming_leg_refused_after_a_lock_stays_at_the_end_stop() {
+    use crate::locks::Lock;
+    use crate::positioning::state::STATUS_STOPPED;
+
+    let mut positioning = uniform_positioning_l1_ms(300);
+    positioning.update_interval_ms = 0;
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.rehome_after_hours = Some(1);
+    }
+    let controller = fake_controller(positioning, HashMap::from([(2, 30)])).await;
+
+    // 30 -> 40 re-homes through 0 first.
+    controller
+        .set_target_positions(vec![(2, 40)], CommandSource::Http)
+        .await
+        .unwrap();
+    controller
+        .locks()
+        .insert(Lock {
+            channel: Channel::L1,
+            reason: None,
+            since: 0,
+            until: None,
+        })
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    assert_eq!(
+        controller.operations(),
+        vec![ProtocolOperation::FakeCommand {
+            channel: Channel::L1,
+            command: Command::Down,
+        }]
+    );
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(
+        (position.current, position.target, position.status),
+        (0, 0, STATUS_STOPPED)
+    );
+    let history = controller
+        .history()
+        .query(&crate::history::HistoryFilter::default());
+    assert_eq!(
+        (
+            &history[0].source,
+            history[0].command.as_str(),
+            history[0].target
+        ),
+        (&CommandSource::Http, "rehome", Some(40))
+    );
+    assert_eq!(history[0].outcome, crate::history::Outcome::Error);
+    assert!(history[0].error.as_deref().unwrap().contains("locked"));
+}
+
+#[tokio::test]
+async fn refused_target_mid_travel_keeps_the_move_running() {
+    use crate::positioning::duty_cycle::MotorResting;
+    use crate::positioning::state::{STATUS_DECREASING, STATUS_STOPPED};
+
+    let mut positioning = uniform_positioning_l1_ms(800);
+    positioning.update_interval_ms = 0;
+    positioning.run_budget_secs = 1;
+    positioning.run_window_secs = 1_000;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let mut position_rx = controller.subscribe_positions();
+
+    let err = controller
+        .set_target_positions(vec![(2, 100)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+
+    assert!(err.downcast_ref::<MotorResting>().is_some(), "{err}");
+    assert!(position_rx.try_recv().is_err());
+    let position = controller.position_for_aid(2).await;
+    assert_eq!((position.current, position.target), (100, 0));
+    assert_eq!(position.status, STATUS_DECREASING);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    let position = controller.position_for_aid(2).await;
+    assert_eq!((position.current, position.status), (0, STATUS_STOPPED));
+}
+
+#[tokio::test]
+async fn moves_over_the_duty_cycle_budget_are_refused_before_transmitting() {
+    use crate::positioning::duty_cycle::MotorResting;
+
+    let mut positioning = uniform_positioning_l1_ms(600);
+    positioning.update_interval_ms = 0;
+    positioning.run_budget_secs = 1;
+    positioning.run_window_secs = 1_000;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    let err = controller
+        .set_target_positions(vec![(2, 100)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+    let resting = err.downcast_ref::<MotorResting>().unwrap();
+    assert_eq!(resting.channel, Channel::L1);
+    // Just under 0.2 s over a 1 s budget that drains over 1000 s.
+    assert_eq!(resting.retry_after_secs(), 200);
+
+    let err = controller
+        .execute(Command::Up, Some(Channel::L1))
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<MotorResting>().is_some(), "{err}");
+
+    // Stop on an idle blind without a `my_position` does not run the motor.
+    controller
+        .execute(Command::Stop, Some(Channel::L1))
+        .await
+        .unwrap();
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn locking_stops_moving_blinds_and_refuses_further_moves() {
+    use crate::locks::{ChannelLocked, Lock};
+
+    let mut positioning = uniform_positioning_l1_ms(600);
+    positioning.update_interval_ms = 0;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100), (3, 100)])).await;
+
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    controller
+        .lock_channel(Lock {
+            channel: Channel::All,
+            reason: Some("window cleaner".to_string()),
+            since: 0,
+            until: None,
+        })
+        .await
+        .unwrap();
+    // Only the moving blind is stopped; an idle motor would take Stop as My.
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+        ]
+    );
+    let position = controller.position_for_aid(2).await;
+    assert_eq!(position.status, STATUS_STOPPED);
+    assert!((1..100).contains(&position.current), "{position:?}");
+
+    let err = controller
+        .set_target_positions(vec![(3, 0)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+    let locked = err.downcast_ref::<ChannelLocked>().unwrap();
+    assert_eq!(
+        (locked.channel, locked.lock.channel),
+        (Channel::L2, Channel::All)
+    );
+    // An idle motor takes Stop as My, so it is refused like My.
+    for command in [Command::Up, Command::My, Command::Stop] {
+        let err = controller
+            .execute(command, Some(Channel::L2))
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ChannelLocked>().is_some(), "{err}");
+    }
+    assert!(controller.execute_my(Some(Channel::L2)).await.is_err());
+    assert_eq!(controller.operations().len(), 2);
+
+    controller.locks().remove(Channel::All).unwrap();
+    controller
+        .set_target_positions(vec![(3, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stop_halts_moving_locked_blinds_but_not_idle_ones() {
+    use crate::locks::{ChannelLocked, Lock};
+
+    let mut positioning = uniform_positioning_l1_ms(600);
+    positioning.update_interval_ms = 0;
+    let controller = fake_controller(positioning, HashMap::from([(2, 100), (3, 100)])).await;
+    controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap();
+    controller
+        .locks()
+        .insert(Lock {
+            channel: Channel::All,
+            reason: None,
+            since: 0,
+            until: None,
+        })
+        .unwrap();
+
+    let err = controller
+        .execute(Command::Stop, Some(Channel::L2))
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<ChannelLocked>().is_some(), "{err}");
+    controller
+        .execute(Command::Stop, Some(Channel::L1))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Down,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Stop,
+            },
+        ]
+    );
+    assert_eq!(controller.position_for_aid(2).await.status, STATUS_STOPPED);
+}
+
+#[tokio::test]
+async fn tripped_safety_input_retracts_and_holds_blinds_up() {
+    use crate::config::SafetyInputOptions;
+    use crate::safety::SafetyHold;
+
+    let blinds = inventory(&[(2, Channel::L1), (3, Channel::L2), (4, Channel::L3)]).with_safety(&[
+        SafetyInputOptions {
+            name: "wind".to_string(),
+            gpio: 20,
+            active_low: false,
+            channels: vec![Channel::L1, Channel::L2],
+            aid: None,
+        },
+    ]);
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            blinds,
+            controller_config(),
+            HashMap::from([(2, 30), (3, 60), (4, 50)]),
+        )
+        .await
+        .unwrap(),
+    );
+
+    controller.trip_safety("wind").await.unwrap();
+    assert_eq!(
+        controller.operations(),
+        vec![
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L1,
+                command: Command::Up,
+            },
+            ProtocolOperation::FakeCommand {
+                channel: Channel::L2,
+                command: Command::Up,
+            },
+        ]
+    );
+    assert_eq!(controller.position_for_aid(2).await.current, 100);
+    let history = controller
+        .history()
+        .query(&crate::history::HistoryFilter::default());
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|entry| entry.command == "up"
+        && entry.outcome == crate::history::Outcome::Ok
+        && entry.source.to_string() == "safety:wind"));
+    let status = &controller.safety_status()[0];
+    assert!(status.active && status.since.is_some(), "{status:?}");
+
+    let err = controller
+        .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+    let hold = err.downcast_ref::<SafetyHold>().unwrap();
+    assert_eq!((hold.channel, hold.input.as_str()), (Channel::L1, "wind"));
+    for command in [Command::Down, Command::My, Command::Stop] {
+        let err = controller
+            .execute(command, Some(Channel::All))
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<SafetyHold>().is_some(), "{err}");
+    }
+    assert!(controller.execute_my(Some(Channel::L2)).await.is_err());
+    controller
+        .execute(Command::Up, Some(Channel::L1))
+        .await
+        .unwrap();
+    controller
+        .execute(Command::Down, Some(Channel::L3))
+        .await
+        .unwrap();
+
+    controller.clear_safety("wind");
+    assert!(!controller.safety_status()[0].active);
+    controller
+        .execute(Command::Down, Some(Channel::L1))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn safety_hold_refuses_rehoming_through_the_closed_end_stop() {
+    use crate::config::SafetyInputOptions;
+    use crate::locks::Lock;
+    use crate::safety::SafetyHold;
+
+    let mut positioning = uniform_positioning_l1_ms(300);
+    if let Some(timing) = positioning.timing_mut(Channel::L1) {
+        timing.rehome_after_hours = Some(1);
+    }
+    let blinds = inventory(&[(2, Channel::L1)]).with_safety(&[SafetyInputOptions {
+        name: "wind".to_string(),
+        gpio: 20,
+        active_low: false,
+        channels: vec![Channel::L1],
+        aid: None,
+    }]);
+    let controller = Arc::new(
+        BlindController::with_driver_and_positions_for_test(
+            DriverConfig::fake(),
+            blinds,
+            positioning,
+            HashMap::from([(2, 30)]),
+        )
+        .await
+        .unwrap(),
+    );
+    // Locked blinds are not retracted, so this one stays at 30.
+    controller
+        .locks()
+        .insert(Lock {
+            channel: Channel::L1,
+            reason: None,
+            since: 0,
+            until: None,
+        })
+        .unwrap();
+    controller.trip_safety("wind").await.unwrap();
+    controller.locks().remove(Channel::L1).unwrap();
+    let history = controller
+        .history()
+        .query(&crate::history::HistoryFilter::default());
+    assert_eq!(history[0].outcome, crate::history::Outcome::Error);
+    assert!(history[0].error.as_deref().unwrap().contains("locked"));
+
+    // 30 -> 40 re-homes through 0 first.
+    let err = controller
+        .set_target_positions(vec![(2, 40)], CommandSource::Cli)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<SafetyHold>().is_some(), "{err}");
+    assert!(controller.operations().is_empty());
+    controller
+        .set_target_positions(vec![(2, 100)], CommandSource::Cli)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn presses_from_other_remotes_move_cached_positions_without_transmitting() {
+    use crate::rts::frame::{DecodedFrame, RtsCommand};
+
+    let controller = fake_controller(
+        uniform_positioning_l1_ms(50),
+        HashMap::from([(2, 50), (3, 50)]),
+    )
+    .await;
+    let mut presses = controller.subscribe_remote_presses();
+
+    let deltas = controller
+        .apply_remote_press(Channel::L1, RtsCommand::Down)
+        .await;
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(controller.position_for_aid(2).await.current, 0);
+    assert_eq!(controller.position_for_aid(3).await.current, 50);
+    controller
+        .apply_remote_press(Channel::All, RtsCommand::Up)
+        .await;
+    assert_eq!(controller.position_for_aid(3).await.current, 100);
+    assert!(controller.operations().is_empty());
+
+    // The fake driver has no virtual remotes, so the press is only published.
+    let frame = DecodedFrame {
+        command: RtsCommand::Down,
+        rolling_code: 12,
+        remote_id: 0xABCDEF,
+    };
+    assert!(controller.observe_remote_frame(frame).await.is_empty());
+    let press = presses.recv().await.unwrap();
+    assert_eq!(
+        (press.remote_id, press.command, press.channels),
+        (0xABCDEF, RtsCommand::Down, Vec::new())
+    );
+    assert_eq!(controller.position_for_aid(3).await.current, 100);
+}
+
+#[tokio::test]
+async fn learned_remote_presses_move_bound_blinds_and_are_recorded() {
+    use crate::history::{CommandSource, HistoryFilter};
+    use crate::remotes::LearnedRemote;
+    use crate::rts::frame::{DecodedFrame, RtsCommand};
+
+    let controller = fake_controller(
+        uniform_positioning_l1_ms(50),
+        HashMap::from([(2, 50), (3, 50)]),
+    )
+    .await;
+    controller
+        .learned_remotes()
+        .learn(LearnedRemote {
+            name: "hallway".to_string(),
+            remote_id: 0xABCDEF,
+            rolling_code: 11,
+            channels: Vec::new(),
+        })
+        .unwrap();
+    assert!(controller
+        .bind_remote("hallway", vec![Channel::Individual(9)])
+        .is_err());
+    controller
+        .bind_remote("hallway", vec![Channel::L1, Channel::L2])
+        .unwrap();
+    let mut presses = controller.subscribe_remote_presses();
+
+    let frame = DecodedFrame {
+        command: RtsCommand::Down,
+        rolling_code: 12,
+        remote_id: 0xABCDEF,
+    };
+    assert_eq!(controller.observe_remote_frame(frame).await.len(), 2);
+    assert_eq!(controller.position_for_aid(2).await.current, 0);
+    assert_eq!(controller.position_for_aid(3).await.current, 0);
+    assert!(controller.operations().is_empty());
+
+    let press = presses.recv().await.unwrap();
+    assert_eq!(press.remote.as_deref(), Some("hallway"));
+    assert_eq!(press.channels, vec![Channel::L1, Channel::L2]);
+    assert_eq!(controller.learned_remotes().list()[0].rolling_code, 12);
+
+    let history = controller.history().query(&HistoryFilter::default());
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|entry| entry.command == "down"
+        && entry.source
+            == CommandSource::Remote {
+                name: "hallway".to_string()
+            }));
+
+    // A replay of an earlier press moves nothing and is not published.
+    controller
+        .observe_remote_frame(DecodedFrame {
+            command: RtsCommand::Up,
+            rolling_code: 13,
+            ..frame
+        })
+        .await;
+    let replayed = DecodedFrame {
+        command: RtsCommand::Down,
+        ..frame
+    };
+    assert!(controller.observe_remote_frame(replayed).await.is_empty());
+    assert_eq!(controller.position_for_aid(2).await.current, 100);
+    assert_eq!(presses.recv().await.unwrap().rolling_code, 13);
+    assert!(presses.try_recv().is_err());
+    assert_eq!(controller.learned_remotes().list()[0].rolling_code, 13);
 }
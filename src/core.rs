@@ -1,59 +1,84 @@
 //! Domain vocabulary shared across drivers, transports, and HomeKit.
 
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::str::FromStr;
 
-const CHANNELS: [(Channel, &str); 5] = [
-    (Channel::L1, "L1"),
-    (Channel::L2, "L2"),
-    (Channel::L3, "L3"),
-    (Channel::L4, "L4"),
-    (Channel::All, "ALL"),
-];
-
-const COMMANDS: [(Command, &str); 6] = [
+const COMMANDS: [(Command, &str); 7] = [
     (Command::Up, "up"),
     (Command::Down, "down"),
     (Command::Stop, "stop"),
+    (Command::My, "my"),
     (Command::Select, "select"),
     (Command::Prog, "prog"),
     (Command::ProgLong, "prog_long"),
 ];
 
-/// Installation target: one LED row (`L1`–`L4`) or the group (`ALL`).
-#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Installation target: one individual channel (`L1`–`L16`), every blind
+/// (`ALL`), or a configured named group (`G1`–`G16`).
+///
+/// The wired Telis remote only has the four LED rows `L1`–`L4`; RTS and fake
+/// drivers accept any individual channel up to [`Channel::MAX_INDIVIDUAL`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Channel {
-    L1,
-    L2,
-    L3,
-    L4,
-    #[serde(rename = "ALL")]
+    /// 1-based individual channel number.
+    Individual(u8),
     All,
+    /// 1-based `[[groups]]` channel number.
+    Group(u8),
 }
 
 impl Channel {
-    pub const INDIVIDUALS: [Channel; 4] = [Channel::L1, Channel::L2, Channel::L3, Channel::L4];
+    pub const MAX_INDIVIDUAL: u8 = 16;
+    pub const MAX_GROUP: u8 = 16;
+
+    pub const L1: Channel = Channel::Individual(1);
+    pub const L2: Channel = Channel::Individual(2);
+    pub const L3: Channel = Channel::Individual(3);
+    pub const L4: Channel = Channel::Individual(4);
+
+    /// The four LED rows on a Telis 4 remote.
+    pub const TELIS_ROWS: [Channel; 4] = [Channel::L1, Channel::L2, Channel::L3, Channel::L4];
+
+    /// Individual channel `L<number>`, or `None` outside `1..=MAX_INDIVIDUAL`.
+    pub fn individual(number: u8) -> Option<Self> {
+        (1..=Self::MAX_INDIVIDUAL)
+            .contains(&number)
+            .then_some(Self::Individual(number))
+    }
+
+    /// Group channel `G<number>`, or `None` outside `1..=MAX_GROUP`.
+    pub fn group(number: u8) -> Option<Self> {
+        (1..=Self::MAX_GROUP)
+            .contains(&number)
+            .then_some(Self::Group(number))
+    }
 
     pub fn individual_index(self) -> Option<usize> {
         match self {
-            Channel::L1 => Some(0),
-            Channel::L2 => Some(1),
-            Channel::L3 => Some(2),
-            Channel::L4 => Some(3),
-            Channel::All => None,
+            Channel::Individual(number) => Some(usize::from(number) - 1),
+            Channel::All | Channel::Group(_) => None,
         }
     }
 
     /// Advance the Telis selector one step (L1 → L2 → … → ALL → L1).
     pub fn next(self) -> Self {
+        self.next_in(&Self::TELIS_ROWS)
+    }
+
+    /// Advance through `individuals` in order, then `ALL`, then back to the first.
+    pub fn next_in(self, individuals: &[Channel]) -> Self {
+        let Some(first) = individuals.first().copied() else {
+            return Channel::All;
+        };
         match self {
-            Channel::L1 => Channel::L2,
-            Channel::L2 => Channel::L3,
-            Channel::L3 => Channel::L4,
-            Channel::L4 => Channel::All,
-            Channel::All => Channel::L1,
+            Channel::All => first,
+            channel => individuals
+                .iter()
+                .position(|candidate| *candidate == channel)
+                .and_then(|index| individuals.get(index + 1).copied())
+                .unwrap_or(Channel::All),
         }
     }
 }
@@ -61,23 +86,43 @@ impl Channel {
 impl FromStr for Channel {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        CHANNELS
-            .iter()
-            .find(|(_, name)| *name == s)
-            .map(|(ch, _)| *ch)
-            .ok_or_else(|| anyhow::anyhow!("Invalid channel value: {s}"))
+        if s == "ALL" {
+            return Ok(Channel::All);
+        }
+        let number = |digits: &str| {
+            Some(digits)
+                .filter(|digits| !digits.starts_with('0'))
+                .and_then(|digits| digits.parse::<u8>().ok())
+        };
+        let channel = match s.split_at_checked(1) {
+            Some(("L", digits)) => number(digits).and_then(Channel::individual),
+            Some(("G", digits)) => number(digits).and_then(Channel::group),
+            _ => None,
+        };
+        channel.ok_or_else(|| anyhow::anyhow!("Invalid channel value: {s}"))
     }
 }
 
 impl fmt::Display for Channel {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Channel::L1 => "L1",
-            Channel::L2 => "L2",
-            Channel::L3 => "L3",
-            Channel::L4 => "L4",
-            Channel::All => "ALL",
-        })
+        match self {
+            Channel::Individual(number) => write!(f, "L{number}"),
+            Channel::All => f.write_str("ALL"),
+            Channel::Group(number) => write!(f, "G{number}"),
+        }
+    }
+}
+
+impl Serialize for Channel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Channel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -87,6 +132,9 @@ pub enum Command {
     Up,
     Down,
     Stop,
+    /// Middle button while idle: travel to the motor's stored favourite
+    /// position. Transmitted exactly like `Stop`.
+    My,
     Select,
     Prog,
     ProgLong,
@@ -98,6 +146,7 @@ impl fmt::Display for Command {
             Command::Up => "up",
             Command::Down => "down",
             Command::Stop => "stop",
+            Command::My => "my",
             Command::Select => "select",
             Command::Prog => "prog",
             Command::ProgLong => "prog_long",
@@ -122,25 +171,50 @@ mod tests {
 
     #[test]
     fn channel_from_str_valid() {
-        for (ch, name) in CHANNELS {
+        for (ch, name) in [
+            (Channel::L1, "L1"),
+            (Channel::L4, "L4"),
+            (Channel::Individual(5), "L5"),
+            (Channel::Individual(16), "L16"),
+            (Channel::All, "ALL"),
+            (Channel::Group(1), "G1"),
+            (Channel::Group(16), "G16"),
+        ] {
             assert_eq!(Channel::from_str(name).unwrap(), ch);
         }
     }
 
     #[test]
     fn channel_from_str_invalid() {
-        assert!(Channel::from_str("L5").is_err());
-        assert!(Channel::from_str("").is_err());
+        for name in [
+            "L0", "L17", "L01", "l1", "L", "all", "", "G0", "G17", "g1", "G",
+        ] {
+            assert!(Channel::from_str(name).is_err(), "{name}");
+        }
     }
 
     #[test]
     fn channel_display_round_trip() {
-        for (ch, _) in CHANNELS {
+        for ch in (1..=Channel::MAX_INDIVIDUAL)
+            .map(Channel::Individual)
+            .chain([Channel::All])
+            .chain((1..=Channel::MAX_GROUP).map(Channel::Group))
+        {
             let s = ch.to_string();
             assert_eq!(Channel::from_str(&s).unwrap(), ch);
         }
     }
 
+    #[test]
+    fn channel_next_cycles_through_given_individuals_then_all() {
+        let channels = [Channel::L1, Channel::L2, Channel::Individual(6)];
+
+        assert_eq!(Channel::L2.next_in(&channels), Channel::Individual(6));
+        assert_eq!(Channel::Individual(6).next_in(&channels), Channel::All);
+        assert_eq!(Channel::All.next_in(&channels), Channel::L1);
+        assert_eq!(Channel::L4.next(), Channel::All);
+    }
+
     #[test]
     fn channel_serde_preserves_all_spelling() {
         assert_eq!(serde_json::to_string(&Channel::All).unwrap(), r#""ALL""#);
@@ -4,7 +4,7 @@ pub(crate) mod rts_prereqs;
 
 use anyhow::{bail, Context, Result};
 
-use crate::config::DriverKind;
+use crate::config::{AppConfig, DriverKind, RtsTransmitterKind};
 
 use std::fs;
 use std::io::Write;
@@ -19,8 +19,9 @@ pub const UNIT_PATH: &str = "/etc/systemd/system/somfy.service";
 pub const STAGED_DOWNLOAD: &str = "/usr/local/bin/.somfy.download";
 
 /// Install/host setup required before the configured driver can run.
-pub(crate) fn prepare_driver_prereqs(driver: DriverKind) -> Result<()> {
-    if driver == DriverKind::Rts {
+/// Only the pigpiod RTS transmitter needs host packages.
+pub(crate) fn prepare_driver_prereqs(config: &AppConfig) -> Result<()> {
+    if config.driver == DriverKind::Rts && config.rts.transmitter == RtsTransmitterKind::Pigpio {
         rts_prereqs::prepare()?;
     }
     Ok(())
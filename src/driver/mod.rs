@@ -1,10 +1,13 @@
 //! Hardware driver abstraction (`fake`, `telis`, `rts`).
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use tokio::sync::broadcast;
 use tokio::sync::watch::Receiver;
 
 use crate::config::DriverConfig;
 use crate::core::{Channel, Command};
+use crate::rts::export::ExportedWaveform;
+use crate::rts::frame::{DecodedFrame, RtsCommand};
 
 mod fake;
 mod rts;
@@ -46,7 +49,9 @@ impl CommandRouter {
             DriverConfig::Telis { gpio, telis } => {
                 Self::Telis(TelisDriver::new(gpio, telis).await?)
             }
-            DriverConfig::Rts { rts } => Self::Rts(Box::new(RtsDriver::new(rts).await?)),
+            DriverConfig::Rts { gpio, rts } => {
+                Self::Rts(Box::new(RtsDriver::new(gpio, rts).await?))
+            }
         })
     }
 
@@ -87,6 +92,37 @@ impl CommandRouter {
         }
     }
 
+    /// Frames received from other RTS remotes; `None` without a receiver.
+    pub fn subscribe_remote_frames(&self) -> Option<broadcast::Receiver<DecodedFrame>> {
+        match self {
+            Self::Rts(driver) => driver.subscribe_frames(),
+            Self::Fake(_) | Self::Telis(_) => None,
+        }
+    }
+
+    /// RTS waveform for `command` on `channel`, built but not transmitted.
+    pub async fn export_rts(
+        &self,
+        channel: Channel,
+        command: RtsCommand,
+        long: bool,
+    ) -> Result<ExportedWaveform> {
+        match self {
+            Self::Rts(driver) => driver.export(channel, command, long).await,
+            Self::Fake(_) | Self::Telis(_) => {
+                bail!("exporting RTS waveforms requires driver = \"rts\"")
+            }
+        }
+    }
+
+    /// Channel of the virtual remote `remote_id` belongs to, if it is ours.
+    pub async fn remote_channel(&self, remote_id: u32) -> Option<Channel> {
+        match self {
+            Self::Rts(driver) => driver.channel_for_remote(remote_id).await,
+            Self::Fake(_) | Self::Telis(_) => None,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn operations(&self) -> Vec<ProtocolOperation> {
         match self {
@@ -3,14 +3,17 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 use tokio::sync::watch::{self, Sender};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
 
-use crate::config::RtsOptions;
+use crate::config::{RtsOptions, RtsTransmitterKind};
 use crate::core::{Channel, Command};
 use crate::driver::SelectedChannelRx;
-use crate::gpio::MAX_BCM_GPIO;
-use crate::rts::cc1101::Cc1101;
-use crate::rts::frame::{RtsCommand, RtsFrame};
+use crate::gpio::{self, GpioOptions, MAX_BCM_GPIO};
+use crate::rts::cc1101::{Cc1101, PACKET_SYMBOL_US};
+use crate::rts::decoder::Demodulator;
+use crate::rts::export::{self, ExportedWaveform};
+use crate::rts::frame::{DecodedFrame, RtsCommand, RtsFrame};
 use crate::rts::pigpio::PigpioClient;
 use crate::rts::state::RtsStateStore;
 
@@ -53,6 +56,8 @@ struct Hardware {
     radio: Cc1101<Spi>,
     pigpio: PigpioClient<TcpStream>,
     gdo0: u8,
+    /// Return the radio to RX after each transmission.
+    receive: bool,
 }
 
 impl Hardware {
@@ -85,28 +90,42 @@ pub(crate) struct RtsDriver {
     options: RtsOptions,
     state: Mutex<RtsStateStore>,
     transmitter: Arc<dyn RtsTransmitter>,
+    frames: broadcast::Sender<DecodedFrame>,
+    receiver: Option<JoinHandle<()>>,
+}
+
+impl Drop for RtsDriver {
+    fn drop(&mut self) {
+        if let Some(receiver) = &self.receiver {
+            receiver.abort();
+        }
+    }
 }
 
 impl RtsDriver {
-    pub(crate) async fn new(options: RtsOptions) -> Result<Self> {
+    pub(crate) async fn new(gpio: GpioOptions, options: RtsOptions) -> Result<Self> {
         if options.gpio.gdo0 > MAX_BCM_GPIO {
             bail!(
                 "RTS GDO0 GPIO {} is out of BCM range (0..={MAX_BCM_GPIO})",
                 options.gpio.gdo0
             );
         }
-        assert_pigpiod_endpoints_are_loopback()?;
+        if options.transmitter == RtsTransmitterKind::Pigpio {
+            assert_pigpiod_endpoints_are_loopback()?;
+        }
         let state = RtsStateStore::load_or_init_default()?;
         let selected_channel = state.selected_channel();
         let (sender, selected_rx) = watch::channel(selected_channel);
         let transmitter = init_transmitter(options.clone()).await?;
-        Ok(Self::from_parts(
-            sender,
-            selected_rx,
-            options,
-            state,
-            transmitter,
-        ))
+        let mut driver = Self::from_parts(sender, selected_rx, options, state, transmitter);
+        if let Some(gdo2) = driver.options.gpio.gdo2 {
+            driver.receiver = Some(tokio::spawn(receive_frames(
+                gpio.chip,
+                gdo2,
+                driver.frames.clone(),
+            )));
+        }
+        Ok(driver)
     }
 
     fn from_parts(
@@ -116,12 +135,15 @@ impl RtsDriver {
         state: RtsStateStore,
         transmitter: Arc<dyn RtsTransmitter>,
     ) -> Self {
+        let (frames, _) = broadcast::channel(16);
         Self {
             sender,
             selected_rx,
             options,
             state: Mutex::new(state),
             transmitter,
+            frames,
+            receiver: None,
         }
     }
 
@@ -151,7 +173,12 @@ impl RtsDriver {
             }
             // Directional commands use persisted logical selection, not `channel`.
             // Call [`Self::execute_on`] to transmit on a specific RTS channel.
-            Command::Up | Command::Down | Command::Stop | Command::Prog | Command::ProgLong => {
+            Command::Up
+            | Command::Down
+            | Command::Stop
+            | Command::My
+            | Command::Prog
+            | Command::ProgLong => {
                 let channel = self.selected_channel();
                 self.execute_on(channel, command).await
             }
@@ -172,6 +199,35 @@ impl RtsDriver {
         self.selected_rx.clone()
     }
 
+    /// Frames decoded from other remotes; `None` unless `rts.gpio.gdo2` is set.
+    pub(crate) fn subscribe_frames(&self) -> Option<broadcast::Receiver<DecodedFrame>> {
+        self.receiver.as_ref().map(|_| self.frames.subscribe())
+    }
+
+    /// Build the waveform for `command` on `channel` without transmitting it,
+    /// consuming its rolling code.
+    pub(crate) async fn export(
+        &self,
+        channel: Channel,
+        command: RtsCommand,
+        long: bool,
+    ) -> Result<ExportedWaveform> {
+        let mut state = self.state.lock().await;
+        let exported = export::reserve(&mut state, channel, command, long)?;
+        tracing::info!(
+            %channel,
+            command = ?command,
+            rolling_code = exported.rolling_code,
+            "rts waveform exported"
+        );
+        Ok(exported)
+    }
+
+    /// Channel whose virtual remote uses `remote_id`, if it is one of ours.
+    pub(crate) async fn channel_for_remote(&self, remote_id: u32) -> Option<Channel> {
+        self.state.lock().await.channel_for_remote(remote_id)
+    }
+
     async fn set_selected_channel(&self, channel: Channel) -> Result<()> {
         {
             let mut state = self.state.lock().await;
@@ -267,6 +323,77 @@ impl RtsTransmitter for PigpioTransmitter {
     }
 }
 
+/// CC1101 in packet mode: the radio clocks the waveform out of its TX FIFO,
+/// so no pigpiod or GDO0 wiring is needed.
+#[derive(Debug)]
+struct PacketHardware {
+    radio: Cc1101<Spi>,
+    /// Return the radio to async RX after each transmission.
+    receive: bool,
+}
+
+#[derive(Debug)]
+struct PacketTransmitter {
+    hardware: Arc<StdMutex<PacketHardware>>,
+}
+
+impl RtsTransmitter for PacketTransmitter {
+    fn transmit(&self, transmission: PreparedTransmission) -> Result<()> {
+        // Encode before taking the radio so the FIFO is fed without stalls.
+        let bytes = waveform::fifo_bytes(&transmission.pulses, PACKET_SYMBOL_US);
+        let mut hw = self
+            .hardware
+            .lock()
+            .map_err(|_| anyhow::anyhow!("RTS hardware mutex poisoned"))?;
+        tracing::debug!(fifo_bytes = bytes.len(), "CC1101 packet transmit started");
+        let tx_result = hw
+            .radio
+            .configure_packet_tx()
+            .and_then(|()| hw.radio.transmit_fifo(&bytes));
+        let rx_result = if hw.receive {
+            hw.radio
+                .configure_async_rx_on_gdo2()
+                .and_then(|()| hw.radio.rx())
+        } else {
+            Ok(())
+        };
+        tx_result?;
+        tracing::debug!("CC1101 packet transmit completed");
+        rx_result
+    }
+}
+
+/// Demodulate GDO2 and publish each decoded press once. Remotes repeat the
+/// same frame for as long as the button is held.
+async fn receive_frames(chip: String, gdo2: u8, frames: broadcast::Sender<DecodedFrame>) {
+    let mut demodulator = Demodulator::new();
+    let mut last = None;
+    tracing::info!(gdo2, "listening for RTS remotes");
+    let result = gpio::watch_pulses(&chip, gdo2, |high, duration_us| {
+        let Some(bytes) = demodulator.push(high, duration_us) else {
+            return;
+        };
+        match RtsFrame::decode(bytes) {
+            Ok(frame) if last == Some(frame) => {}
+            Ok(frame) => {
+                tracing::info!(
+                    remote_id = format_args!("{:06X}", frame.remote_id),
+                    command = ?frame.command,
+                    rolling_code = frame.rolling_code,
+                    "rts frame received"
+                );
+                last = Some(frame);
+                let _ = frames.send(frame);
+            }
+            Err(e) => tracing::debug!(frame = %hex::encode(bytes), "ignoring rts frame: {e:#}"),
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        tracing::error!("RTS receiver stopped: {e:#}");
+    }
+}
+
 async fn init_transmitter(options: RtsOptions) -> Result<Arc<dyn RtsTransmitter>> {
     tokio::task::spawn_blocking(move || -> Result<Arc<dyn RtsTransmitter>> {
         let spi = open_spi(&options.spi_device)?;
@@ -278,6 +405,22 @@ async fn init_transmitter(options: RtsOptions) -> Result<Arc<dyn RtsTransmitter>
             spi_device = %options.spi_device,
             "CC1101 configured for 433.42 MHz async OOK"
         );
+        let receive = options.gpio.gdo2.is_some();
+        if receive {
+            radio
+                .configure_async_rx_on_gdo2()
+                .context("configuring CC1101 async RX on GDO2")?;
+            radio.rx()?;
+        }
+        if options.transmitter == RtsTransmitterKind::Packet {
+            tracing::info!(
+                symbol_us = PACKET_SYMBOL_US,
+                "CC1101 packet transmitter selected; pigpiod is not used"
+            );
+            return Ok(Arc::new(PacketTransmitter {
+                hardware: Arc::new(StdMutex::new(PacketHardware { radio, receive })),
+            }));
+        }
         let pigpio = connect_and_init_pigpio(options.gpio.gdo0)?;
         tracing::info!(
             addresses = %pigpiod_addr_list(),
@@ -289,6 +432,7 @@ async fn init_transmitter(options: RtsOptions) -> Result<Arc<dyn RtsTransmitter>
                 radio,
                 pigpio,
                 gdo0: options.gpio.gdo0,
+                receive,
             })),
         }))
     })
@@ -370,9 +514,12 @@ fn try_transmit(hw: &mut Hardware, pulses: &[waveform::GpioPulse]) -> Result<()>
         }
     }
     let delete_result = hw.pigpio.wave_delete(wave_id);
-    let idle_result = hw.radio.idle();
+    let mut idle_result = hw.radio.idle();
     if idle_result.is_ok() {
         tracing::debug!("CC1101 strobed to IDLE");
+        if hw.receive {
+            idle_result = hw.radio.rx();
+        }
     }
 
     tx_result?;
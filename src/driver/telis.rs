@@ -48,7 +48,7 @@ impl TelisDriver {
         match command {
             Command::Up => self.transport.press(TelisButton::Up).await,
             Command::Down => self.transport.press(TelisButton::Down).await,
-            Command::Stop => self.transport.press(TelisButton::Stop).await,
+            Command::Stop | Command::My => self.transport.press(TelisButton::Stop).await,
             Command::Prog | Command::ProgLong => bail!("{TELIS_PROG_UNAVAILABLE}"),
             Command::Select => {
                 if channel.is_none() {
@@ -67,7 +67,7 @@ impl TelisDriver {
         match command {
             Command::Up => self.transport.press(TelisButton::Up).await,
             Command::Down => self.transport.press(TelisButton::Down).await,
-            Command::Stop => self.transport.press(TelisButton::Stop).await,
+            Command::Stop | Command::My => self.transport.press(TelisButton::Stop).await,
             Command::Prog | Command::ProgLong => bail!("{TELIS_PROG_UNAVAILABLE}"),
             Command::Select => Ok(()),
         }
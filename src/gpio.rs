@@ -28,7 +28,7 @@ pub fn channel_led_gpio(channel: Channel, config: &TelisGpioOptions) -> Option<u
         Channel::L2 => Some(config.led2),
         Channel::L3 => Some(config.led3),
         Channel::L4 => Some(config.led4),
-        Channel::All => None,
+        Channel::Individual(_) | Channel::All | Channel::Group(_) => None,
     }
 }
 
@@ -44,7 +44,7 @@ pub enum TelisButton {
 #[cfg(any(target_os = "linux", test))]
 pub fn channel_from_gpio(offset: u32, config: &TelisGpioOptions) -> Result<Channel> {
     let gpio = offset as u8;
-    for channel in &Channel::INDIVIDUALS {
+    for channel in &Channel::TELIS_ROWS {
         if channel_led_gpio(*channel, config) == Some(gpio) {
             return Ok(*channel);
         }
@@ -66,32 +66,96 @@ mod platform {
     use super::*;
     use anyhow::Context;
     use futures_util::StreamExt;
-    use gpiocdev::line::EdgeDetection;
+    use gpiocdev::line::{Bias, EdgeDetection, EdgeKind};
     use gpiocdev::tokio::AsyncRequest;
     use gpiocdev::{line::Value, Request};
     use std::time::Duration;
+    use tokio::sync::watch;
+
+    /// Request `offsets` as inputs reporting both edges.
+    fn edge_request(
+        chip: &str,
+        offsets: &[u32],
+        configure: impl FnOnce(&mut gpiocdev::request::Builder),
+    ) -> Result<AsyncRequest> {
+        let mut builder = Request::builder();
+        builder
+            .on_chip(chip)
+            .with_lines(offsets)
+            .as_input()
+            .with_edge_detection(EdgeDetection::BothEdges);
+        configure(&mut builder);
+        let req = builder.request().context("Failed to request GPIO lines")?;
+        Ok(AsyncRequest::new(req))
+    }
+
+    /// Publish the level of a dry-contact input on `levels`: once at start,
+    /// then after every edge. The line is biased towards its inactive level.
+    pub async fn watch_level(
+        chip: &str,
+        gpio: u8,
+        active_low: bool,
+        levels: watch::Sender<bool>,
+    ) -> Result<()> {
+        let areq = edge_request(chip, &[u32::from(gpio)], |builder| {
+            if active_low {
+                builder.as_active_low().with_bias(Bias::PullUp);
+            } else {
+                builder.with_bias(Bias::PullDown);
+            }
+        })?;
+        let level = areq
+            .as_ref()
+            .lone_value()
+            .with_context(|| format!("reading GPIO{gpio}"))?;
+        levels.send_replace(level == Value::Active);
+        let mut events = areq.edge_events();
+        while let Some(event) = events.next().await {
+            let event = event.with_context(|| format!("reading GPIO{gpio} edge event"))?;
+            levels.send_replace(event.kind == EdgeKind::Rising);
+        }
+        anyhow::bail!("GPIO{gpio} edge events ended")
+    }
+
+    /// Report every run of a fast digital signal, such as CC1101 RX data,
+    /// as the level it held and its length in microseconds. Lengths come
+    /// from kernel edge timestamps, so scheduling delays do not skew them.
+    pub async fn watch_pulses(
+        chip: &str,
+        gpio: u8,
+        mut on_pulse: impl FnMut(bool, u32),
+    ) -> Result<()> {
+        let areq = edge_request(chip, &[u32::from(gpio)], |_| {})?;
+        let mut events = areq.edge_events();
+        let mut last_edge: Option<u64> = None;
+        while let Some(event) = events.next().await {
+            let event = event.with_context(|| format!("reading GPIO{gpio} edge event"))?;
+            if let Some(last) = last_edge {
+                let micros = event.timestamp_ns.saturating_sub(last) / 1_000;
+                // A rising edge ends a low run and a falling edge a high one.
+                on_pulse(
+                    event.kind == EdgeKind::Falling,
+                    u32::try_from(micros).unwrap_or(u32::MAX),
+                );
+            }
+            last_edge = Some(event.timestamp_ns);
+        }
+        anyhow::bail!("GPIO{gpio} edge events ended")
+    }
 
     /// Monitors GPIO inputs for LED selection changes
     /// Returns the selected LED input or ALL if multiple inputs are detected
     pub async fn watch_inputs(chip: &str, config: &TelisGpioOptions) -> Result<Channel> {
-        let offsets: Vec<u32> = Channel::INDIVIDUALS
+        let offsets: Vec<u32> = Channel::TELIS_ROWS
             .iter()
             .filter_map(|ch| channel_led_gpio(*ch, config))
             .map(u32::from)
             .collect();
-        if offsets.len() != Channel::INDIVIDUALS.len() {
+        if offsets.len() != Channel::TELIS_ROWS.len() {
             anyhow::bail!("missing Telis LED GPIO mapping for one or more channels");
         }
 
-        let req = Request::builder()
-            .on_chip(chip)
-            .with_lines(&offsets)
-            .as_input()
-            .with_edge_detection(EdgeDetection::BothEdges)
-            .request()
-            .context("Failed to request GPIO lines")?;
-
-        let areq = AsyncRequest::new(req);
+        let areq = edge_request(chip, &offsets, |_| {})?;
         let mut events = areq.edge_events();
 
         let timeout_duration = Duration::from_millis(300);
@@ -178,6 +242,26 @@ mod platform {
         Ok(LEDS[idx as usize])
     }
 
+    /// No GPIO off Linux: the input reads inactive forever.
+    pub async fn watch_level(
+        _chip: &str,
+        _gpio: u8,
+        _active_low: bool,
+        levels: tokio::sync::watch::Sender<bool>,
+    ) -> Result<()> {
+        levels.send_replace(false);
+        std::future::pending().await
+    }
+
+    /// No GPIO off Linux: the input never changes.
+    pub async fn watch_pulses(
+        _chip: &str,
+        _gpio: u8,
+        _on_pulse: impl FnMut(bool, u32),
+    ) -> Result<()> {
+        std::future::pending().await
+    }
+
     pub async fn trigger_output(
         _chip: &str,
         output: TelisButton,
@@ -190,7 +274,7 @@ mod platform {
     }
 }
 
-pub use platform::{trigger_output, watch_inputs};
+pub use platform::{trigger_output, watch_inputs, watch_level, watch_pulses};
 
 #[cfg(test)]
 mod tests {
@@ -47,6 +47,7 @@ pub struct CharacteristicWrite {
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum HapStatus {
     Success = 0,
+    ServiceCommunicationFailure = -70402,
     ReadOnly = -70404,
     WriteOnly = -70405,
     NotificationNotSupported = -70406,
@@ -140,10 +141,12 @@ pub trait HapAccessoryApp: Send + Sync + 'static {
         ids: &'a [CharacteristicId],
     ) -> HapFuture<'a, Vec<CharacteristicRead>>;
 
+    /// `controller_id` is the pairing identifier of the verified session.
     fn write_characteristics<'a>(
         &'a self,
         writes: Vec<CharacteristicWrite>,
         subscriptions: &'a mut Subscriptions,
+        controller_id: Option<&'a str>,
     ) -> HapFuture<'a, CharacteristicWriteOutcome>;
 }
 
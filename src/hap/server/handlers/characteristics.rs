@@ -18,6 +18,7 @@ pub(super) async fn handle_put_characteristics(
     app: &impl HapAccessoryApp,
     body: &[u8],
     subs: &mut Subscriptions,
+    controller_id: Option<&str>,
 ) -> Result<CharacteristicWriteOutcome> {
     let parsed: Value = serde_json::from_slice(body)?;
     let chars = parsed
@@ -40,7 +41,8 @@ pub(super) async fn handle_put_characteristics(
     let mut outcome = if writes.is_empty() {
         CharacteristicWriteOutcome::default()
     } else {
-        app.write_characteristics(writes, subs).await?
+        app.write_characteristics(writes, subs, controller_id)
+            .await?
     };
 
     let mut app_statuses = outcome.statuses.into_iter();
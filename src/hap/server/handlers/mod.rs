@@ -146,7 +146,14 @@ where
             if !encrypted {
                 return Ok(RequestOutcome::response(OutboundResponse::unauthorized()));
             }
-            match handle_put_characteristics(ctx.app.as_ref(), &req.body, &mut conn.subs).await {
+            match handle_put_characteristics(
+                ctx.app.as_ref(),
+                &req.body,
+                &mut conn.subs,
+                conn.controller_id.as_deref(),
+            )
+            .await
+            {
                 Ok(write) => {
                     let response = if write.all_success() {
                         OutboundResponse::no_content()
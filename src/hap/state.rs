@@ -30,6 +30,10 @@ pub struct HapState {
     /// subsequent attempts after 100 failures until factory reset.
     #[serde(default)]
     pub setup_failed_attempts: u32,
+    /// Fingerprint of the accessory layout advertised under `config_number`.
+    /// Opaque to the HAP stack; the accessory adapter decides what it covers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory_fingerprint: Option<String>,
 }
 
 /// HAP §5.6.5: cap on consecutive failed pair-setup proofs.
@@ -76,6 +80,25 @@ impl HapState {
             .retain(|c| c.identifier != identifier);
     }
 
+    /// Record the current accessory layout, bumping `config_number` (`c#`) when
+    /// it differs from the last advertised one so controllers refetch
+    /// `/accessories`. State written before fingerprints existed is compared
+    /// against `legacy`. Returns whether the state needs saving.
+    pub fn update_accessory_fingerprint(&mut self, fingerprint: &str, legacy: &str) -> bool {
+        let previous = self.accessory_fingerprint.as_deref().unwrap_or(legacy);
+        let changed = previous != fingerprint;
+        if changed {
+            // HAP §6.4: c# is 1..=65535 and wraps back to 1.
+            self.config_number = match self.config_number {
+                65535.. => 1,
+                n => n + 1,
+            };
+        }
+        let record = self.accessory_fingerprint.as_deref() != Some(fingerprint);
+        self.accessory_fingerprint = Some(fingerprint.to_string());
+        changed || record
+    }
+
     pub fn status_flag(&self) -> &'static str {
         if self.is_paired() {
             "0"
@@ -110,6 +133,7 @@ impl HapState {
             ltsk: signing.to_bytes(),
             paired_controllers: Vec::new(),
             setup_failed_attempts: 0,
+            accessory_fingerprint: None,
         }
     }
 }
@@ -255,6 +279,31 @@ mod tests {
         assert_eq!(s.status_flag(), "1");
     }
 
+    #[test]
+    fn accessory_fingerprint_change_bumps_config_number() {
+        let mut s = HapState::generate();
+
+        assert!(s.update_accessory_fingerprint("legacy", "legacy"));
+        assert_eq!(s.config_number, 1);
+        assert!(!s.update_accessory_fingerprint("legacy", "legacy"));
+
+        assert!(s.update_accessory_fingerprint("six-blinds", "legacy"));
+        assert_eq!(s.config_number, 2);
+        assert_eq!(s.accessory_fingerprint.as_deref(), Some("six-blinds"));
+
+        s.config_number = 65535;
+        assert!(s.update_accessory_fingerprint("four-blinds", "legacy"));
+        assert_eq!(s.config_number, 1);
+    }
+
+    #[test]
+    fn first_fingerprint_bumps_when_layout_differs_from_legacy() {
+        let mut s = HapState::generate();
+
+        assert!(s.update_accessory_fingerprint("six-blinds", "legacy"));
+        assert_eq!(s.config_number, 2);
+    }
+
     #[test]
     fn setup_code_formats_are_distinct_for_srp_and_display() {
         assert_eq!(srp_setup_code(10148005), "101-48-005");
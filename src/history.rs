@@ -0,0 +1,362 @@
+//! Bounded on-disk journal of executed commands (`history.jsonl`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use crate::core::Channel;
+use crate::persist::{self, atomic_save_bytes};
+use crate::positioning::state::unix_now;
+
+const HISTORY_FILE: &str = "history.jsonl";
+/// Entries kept in memory and after compaction.
+const HISTORY_LIMIT: usize = 1_000;
+/// Entries returned by a query without an explicit `limit`.
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Who asked for a command.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CommandSource {
+    Http,
+    /// WebSocket client, by the `name` it connected with.
+    Ws {
+        client: String,
+    },
+    /// `somfy remote` and other CLI commands posting to the service.
+    Cli,
+    /// Apple Home, by the pairing identifier of the controller that wrote.
+    HomeKit {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pairing: Option<String>,
+    },
+    /// A `[[schedule]]` entry, by name.
+    Scheduler {
+        schedule: String,
+    },
+    /// A physical remote learned with `somfy rts learn`, by name.
+    Remote {
+        name: String,
+    },
+    /// A `[[safety.inputs]]` entry retracting its blinds, by name.
+    Safety {
+        input: String,
+    },
+}
+
+impl fmt::Display for CommandSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http => write!(f, "http"),
+            Self::Ws { client } => write!(f, "ws:{client}"),
+            Self::Cli => write!(f, "cli"),
+            Self::HomeKit { pairing: Some(id) } => write!(f, "homekit:{id}"),
+            Self::HomeKit { pairing: None } => write!(f, "homekit"),
+            Self::Scheduler { schedule } => write!(f, "schedule:{schedule}"),
+            Self::Remote { name } => write!(f, "remote:{name}"),
+            Self::Safety { input } => write!(f, "safety:{input}"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Ok,
+    Error,
+}
+
+/// One executed command.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix seconds.
+    pub at: u64,
+    pub source: CommandSource,
+    /// Wire command name, e.g. `up`, `target`, or `tilt`.
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    /// Target position or tilt angle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<i16>,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Entry stamped now, with the outcome taken from `result`.
+    pub fn now<E: fmt::Display>(
+        source: CommandSource,
+        command: impl Into<String>,
+        channel: Option<Channel>,
+        target: Option<i16>,
+        result: Result<(), E>,
+    ) -> Self {
+        let (outcome, error) = match result {
+            Ok(()) => (Outcome::Ok, None),
+            Err(e) => (Outcome::Error, Some(e.to_string())),
+        };
+        Self {
+            at: unix_now(),
+            source,
+            command: command.into(),
+            channel,
+            target,
+            outcome,
+            error,
+        }
+    }
+}
+
+/// `GET /history` filters. Times are Unix seconds and inclusive.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HistoryFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.channel
+            .is_none_or(|channel| entry.channel == Some(channel))
+            && self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at <= until)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Journal {
+    entries: VecDeque<HistoryEntry>,
+    /// Lines in the file, including ones already dropped from `entries`.
+    lines_on_disk: usize,
+}
+
+/// Command journal. Appends one JSON line per command and rewrites the file
+/// with the newest [`HISTORY_LIMIT`] entries once it holds twice that many.
+#[derive(Debug)]
+pub struct History {
+    path: Option<PathBuf>,
+    journal: StdMutex<Journal>,
+}
+
+impl History {
+    pub fn open() -> Self {
+        Self::open_at(persist::state_dir().join(HISTORY_FILE))
+    }
+
+    fn open_at(path: PathBuf) -> Self {
+        let journal = load_journal(&path);
+        Self {
+            path: Some(path),
+            journal: StdMutex::new(journal),
+        }
+    }
+
+    /// Journal that is never written to disk.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            journal: StdMutex::new(Journal::default()),
+        }
+    }
+
+    /// Append `entry`. Failing to persist is logged, never surfaced: the
+    /// command itself already ran.
+    pub fn record(&self, entry: HistoryEntry) {
+        let mut journal = self
+            .journal
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(path) = &self.path {
+            if let Err(e) = append_entry(path, &entry) {
+                tracing::warn!("failed to append command history: {e:#}");
+            }
+            journal.lines_on_disk += 1;
+        }
+        journal.entries.push_back(entry);
+        while journal.entries.len() > HISTORY_LIMIT {
+            journal.entries.pop_front();
+        }
+        if let Some(path) = &self.path {
+            if journal.lines_on_disk > 2 * HISTORY_LIMIT {
+                match save_journal(path, &journal.entries) {
+                    Ok(()) => journal.lines_on_disk = journal.entries.len(),
+                    Err(e) => tracing::warn!("failed to compact command history: {e:#}"),
+                }
+            }
+        }
+    }
+
+    /// Matching entries, newest first.
+    pub fn query(&self, filter: &HistoryFilter) -> Vec<HistoryEntry> {
+        let journal = self
+            .journal
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        journal
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| filter.matches(entry))
+            .take(filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT))
+            .cloned()
+            .collect()
+    }
+}
+
+fn load_journal(path: &Path) -> Journal {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Journal::default();
+    };
+    let mut journal = Journal::default();
+    let mut malformed = 0;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        journal.lines_on_disk += 1;
+        match serde_json::from_str(line) {
+            Ok(entry) => journal.entries.push_back(entry),
+            Err(_) => malformed += 1,
+        }
+        if journal.entries.len() > HISTORY_LIMIT {
+            journal.entries.pop_front();
+        }
+    }
+    if malformed > 0 {
+        tracing::warn!(malformed, "skipped malformed lines in {}", path.display());
+    }
+    journal
+}
+
+fn append_entry(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("creating state directory {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut file| file.write_all(&line))
+        .with_context(|| format!("appending to {}", path.display()))
+}
+
+fn save_journal(path: &Path, entries: &VecDeque<HistoryEntry>) -> Result<()> {
+    let mut bytes = Vec::new();
+    for entry in entries {
+        bytes.extend(serde_json::to_vec(entry)?);
+        bytes.push(b'\n');
+    }
+    atomic_save_bytes(path, &bytes, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(at: u64, channel: Channel, result: Result<(), &str>) -> HistoryEntry {
+        HistoryEntry {
+            at,
+            ..HistoryEntry::now(CommandSource::Http, "up", Some(channel), None, result)
+        }
+    }
+
+    #[test]
+    fn query_filters_by_channel_and_time_newest_first() {
+        let history = History::in_memory();
+        history.record(entry(100, Channel::L1, Ok(())));
+        history.record(entry(200, Channel::L2, Err("driver busy")));
+        history.record(entry(300, Channel::L1, Ok(())));
+
+        let all = history.query(&HistoryFilter::default());
+        assert_eq!(
+            all.iter().map(|entry| entry.at).collect::<Vec<_>>(),
+            [300, 200, 100]
+        );
+        assert_eq!(all[1].outcome, Outcome::Error);
+        assert_eq!(all[1].error.as_deref(), Some("driver busy"));
+
+        let filter = HistoryFilter {
+            channel: Some(Channel::L1),
+            since: Some(150),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(
+            history
+                .query(&filter)
+                .iter()
+                .map(|entry| entry.at)
+                .collect::<Vec<_>>(),
+            [300]
+        );
+    }
+
+    #[test]
+    fn journal_reloads_and_compacts_to_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        let history = History::open_at(path.clone());
+        for at in 0..(2 * HISTORY_LIMIT as u64 + 1) {
+            history.record(entry(at, Channel::L1, Ok(())));
+        }
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), HISTORY_LIMIT);
+
+        let reloaded = History::open_at(path);
+        let newest = reloaded.query(&HistoryFilter {
+            limit: Some(1),
+            ..HistoryFilter::default()
+        });
+        assert_eq!(newest[0].at, 2 * HISTORY_LIMIT as u64);
+    }
+
+    #[test]
+    fn sources_serialize_with_their_identity() {
+        let entry = HistoryEntry {
+            at: 1,
+            ..HistoryEntry::now(
+                CommandSource::HomeKit {
+                    pairing: Some("A1B2".to_string()),
+                },
+                "target",
+                Some(Channel::L2),
+                Some(40),
+                Ok::<(), String>(()),
+            )
+        };
+        assert_eq!(
+            serde_json::to_value(&entry).unwrap(),
+            serde_json::json!({
+                "at": 1,
+                "source": {"kind": "homekit", "pairing": "A1B2"},
+                "command": "target",
+                "channel": "L2",
+                "target": 40,
+                "outcome": "ok",
+            })
+        );
+        assert_eq!(
+            CommandSource::Ws {
+                client: "tablet".to_string()
+            }
+            .to_string(),
+            "ws:tablet"
+        );
+    }
+}
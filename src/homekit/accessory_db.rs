@@ -17,6 +17,12 @@ pub(crate) const IID_WC_SERVICE: u64 = 8;
 pub(crate) const IID_CURRENT_POSITION: u64 = 9;
 pub(crate) const IID_TARGET_POSITION: u64 = 10;
 pub(crate) const IID_POSITION_STATE: u64 = 11;
+pub(crate) const IID_CURRENT_TILT: u64 = 12;
+pub(crate) const IID_TARGET_TILT: u64 = 13;
+pub(crate) const IID_SWITCH_SERVICE: u64 = 8;
+pub(crate) const IID_SWITCH_ON: u64 = 9;
+pub(crate) const IID_SENSOR_SERVICE: u64 = 8;
+pub(crate) const IID_OCCUPANCY_DETECTED: u64 = 9;
 pub(crate) const IID_BRIDGE_PROTO_SERVICE: u64 = 8;
 pub(crate) const IID_BRIDGE_VERSION: u64 = 9;
 
@@ -25,13 +31,40 @@ pub(crate) struct BlindAccessory<'a> {
     pub name: &'a str,
     pub serial: &'a str,
     pub position: u8,
+    /// Slat angle for venetian blinds; `None` omits the tilt characteristics.
+    pub tilt: Option<i8>,
 }
 
-pub(crate) fn build_accessories(blinds: &[BlindAccessory<'_>]) -> Value {
+/// Stateless switch that activates a `[[scenes]]` preset.
+pub(crate) struct SceneAccessory<'a> {
+    pub aid: u64,
+    pub name: &'a str,
+    pub serial: &'a str,
+}
+
+/// Occupancy sensor mirroring a `[[safety.inputs]]` entry.
+pub(crate) struct SensorAccessory<'a> {
+    pub aid: u64,
+    pub name: &'a str,
+    pub serial: &'a str,
+    pub active: bool,
+}
+
+pub(crate) fn build_accessories(
+    blinds: &[BlindAccessory<'_>],
+    scenes: &[SceneAccessory<'_>],
+    sensors: &[SensorAccessory<'_>],
+) -> Value {
     let mut accessories = vec![bridge_accessory()];
     for blind in blinds {
         accessories.push(blind_accessory(blind));
     }
+    for scene in scenes {
+        accessories.push(scene_accessory(scene));
+    }
+    for sensor in sensors {
+        accessories.push(sensor_accessory(sensor));
+    }
     json!({ "accessories": accessories })
 }
 
@@ -63,7 +96,53 @@ fn blind_accessory(blind: &BlindAccessory<'_>) -> Value {
         "aid": blind.aid,
         "services": [
             accessory_info_service(blind.name, "Telis 4", blind.serial, firmware),
-            window_covering_service(blind.position),
+            window_covering_service(blind.position, blind.tilt),
+        ]
+    })
+}
+
+fn scene_accessory(scene: &SceneAccessory<'_>) -> Value {
+    let firmware = env!("CARGO_PKG_VERSION");
+    json!({
+        "aid": scene.aid,
+        "services": [
+            accessory_info_service(scene.name, "Scene", scene.serial, firmware),
+            {
+                "iid": IID_SWITCH_SERVICE,
+                "type": "49",
+                "characteristics": [
+                    {
+                        "iid": IID_SWITCH_ON,
+                        "type": "25",
+                        "perms": ["pr", "pw", "ev"],
+                        "format": "bool",
+                        "value": false,
+                    },
+                ],
+            },
+        ]
+    })
+}
+
+fn sensor_accessory(sensor: &SensorAccessory<'_>) -> Value {
+    let firmware = env!("CARGO_PKG_VERSION");
+    json!({
+        "aid": sensor.aid,
+        "services": [
+            accessory_info_service(sensor.name, "Safety Input", sensor.serial, firmware),
+            {
+                "iid": IID_SENSOR_SERVICE,
+                "type": "86",
+                "characteristics": [
+                    char_uint8(
+                        IID_OCCUPANCY_DETECTED,
+                        "71",
+                        u8::from(sensor.active),
+                        &["pr", "ev"],
+                        1,
+                    ),
+                ],
+            },
         ]
     })
 }
@@ -83,21 +162,26 @@ fn accessory_info_service(name: &str, model: &str, serial: &str, firmware: &str)
     })
 }
 
-fn window_covering_service(position: u8) -> Value {
+fn window_covering_service(position: u8, tilt: Option<i8>) -> Value {
+    let mut characteristics = vec![
+        char_uint8(IID_CURRENT_POSITION, "6D", position, &["pr", "ev"], 100),
+        char_uint8(
+            IID_TARGET_POSITION,
+            "7C",
+            position,
+            &["pr", "pw", "ev"],
+            100,
+        ),
+        char_uint8(IID_POSITION_STATE, "72", STATUS_STOPPED, &["pr", "ev"], 2),
+    ];
+    if let Some(tilt) = tilt {
+        characteristics.push(char_tilt(IID_CURRENT_TILT, "6C", tilt, &["pr", "ev"]));
+        characteristics.push(char_tilt(IID_TARGET_TILT, "7B", tilt, &["pr", "pw", "ev"]));
+    }
     json!({
         "iid": IID_WC_SERVICE,
         "type": "8C",
-        "characteristics": [
-            char_uint8(IID_CURRENT_POSITION, "6D", position, &["pr", "ev"], 100),
-            char_uint8(IID_TARGET_POSITION, "7C", position, &["pr", "pw", "ev"], 100),
-            char_uint8(
-                IID_POSITION_STATE,
-                "72",
-                STATUS_STOPPED,
-                &["pr", "ev"],
-                2,
-            ),
-        ],
+        "characteristics": characteristics,
     })
 }
 
@@ -124,6 +208,20 @@ fn char_uint8(iid: u64, type_: &str, value: u8, perms: &[&str], max_value: u8) -
     })
 }
 
+fn char_tilt(iid: u64, type_: &str, value: i8, perms: &[&str]) -> Value {
+    json!({
+        "iid": iid,
+        "type": type_,
+        "perms": perms,
+        "format": "int",
+        "unit": "arcdegrees",
+        "value": value,
+        "minValue": -90,
+        "maxValue": 90,
+        "minStep": 1,
+    })
+}
+
 fn char_bool_pw(iid: u64, type_: &str) -> Value {
     json!({
         "iid": iid,
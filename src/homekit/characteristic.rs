@@ -2,10 +2,13 @@ use serde_json::{json, Value};
 
 use crate::hap::runtime::{CharacteristicId, HapStatus};
 use crate::homekit::accessory_db::{
-    BRIDGE_AID, IID_BRIDGE_VERSION, IID_CURRENT_POSITION, IID_FIRMWARE, IID_IDENTIFY,
-    IID_MANUFACTURER, IID_MODEL, IID_NAME, IID_POSITION_STATE, IID_SERIAL, IID_TARGET_POSITION,
+    BRIDGE_AID, IID_BRIDGE_VERSION, IID_CURRENT_POSITION, IID_CURRENT_TILT, IID_FIRMWARE,
+    IID_IDENTIFY, IID_MANUFACTURER, IID_MODEL, IID_NAME, IID_OCCUPANCY_DETECTED,
+    IID_POSITION_STATE, IID_SERIAL, IID_SWITCH_ON, IID_TARGET_POSITION, IID_TARGET_TILT,
 };
-use crate::positioning::state::{find_blind, Blind, BlindPosition};
+use crate::positioning::inventory::{Blind, BlindGroup, BlindInventory, BlindScene, SafetySensor};
+use crate::positioning::state::{BlindPosition, STATUS_STOPPED};
+use crate::safety::SafetyStatus;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum BridgeCharacteristic {
@@ -29,85 +32,124 @@ pub(crate) enum BlindCharacteristic {
     CurrentPosition,
     TargetPosition,
     PositionState,
+    CurrentTilt,
+    TargetTilt,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub(crate) enum HomeKitCharacteristic {
+pub(crate) enum SceneCharacteristic {
+    Identify,
+    Manufacturer,
+    Model,
+    Name,
+    Serial,
+    Firmware,
+    On,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SensorCharacteristic {
+    Identify,
+    Manufacturer,
+    Model,
+    Name,
+    Serial,
+    Firmware,
+    OccupancyDetected,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HomeKitCharacteristic<'a> {
     Bridge(BridgeCharacteristic),
     Blind {
-        blind: &'static Blind,
+        blind: &'a Blind,
+        characteristic: BlindCharacteristic,
+    },
+    /// A `[[groups]]` entry with its own `aid`, bridged as one covering.
+    Group {
+        group: &'a BlindGroup,
         characteristic: BlindCharacteristic,
     },
+    /// A `[[scenes]]` entry with its own `aid`, bridged as a stateless switch.
+    Scene {
+        scene: &'a BlindScene,
+        characteristic: SceneCharacteristic,
+    },
+    /// A `[[safety.inputs]]` entry with its own `aid`, bridged as an
+    /// occupancy sensor that reads detected while the input is active.
+    Safety {
+        sensor: &'a SafetySensor,
+        characteristic: SensorCharacteristic,
+    },
 }
 
-impl HomeKitCharacteristic {
-    pub(crate) fn resolve(id: CharacteristicId) -> Option<Self> {
+impl<'a> HomeKitCharacteristic<'a> {
+    pub(crate) fn resolve(blinds: &'a BlindInventory, id: CharacteristicId) -> Option<Self> {
         let iid = id.iid.0;
         if id.aid.0 == BRIDGE_AID {
             return bridge_characteristic(iid).map(Self::Bridge);
         }
+        if let Some(scene) = blinds.find_scene(id.aid.0) {
+            return scene_characteristic(iid).map(|characteristic| Self::Scene {
+                scene,
+                characteristic,
+            });
+        }
+        if let Some(sensor) = blinds.find_safety_sensor(id.aid.0) {
+            return sensor_characteristic(iid).map(|characteristic| Self::Safety {
+                sensor,
+                characteristic,
+            });
+        }
 
-        let blind = find_blind(id.aid.0)?;
-        blind_characteristic(iid).map(|characteristic| Self::Blind {
-            blind,
+        let characteristic = blind_characteristic(iid)?;
+        // Tilt characteristics only exist on venetian blinds, never on groups.
+        let tilt = matches!(
+            characteristic,
+            BlindCharacteristic::CurrentTilt | BlindCharacteristic::TargetTilt
+        );
+        if let Some(blind) = blinds.find(id.aid.0) {
+            return (!tilt || blind.is_venetian()).then_some(Self::Blind {
+                blind,
+                characteristic,
+            });
+        }
+        if tilt {
+            return None;
+        }
+        blinds.find_group(id.aid.0).map(|group| Self::Group {
+            group,
             characteristic,
         })
     }
 
-    pub(crate) fn read_value(self, positions: &[BlindPosition]) -> Result<Value, HapStatus> {
+    pub(crate) fn read_value(
+        self,
+        positions: &[BlindPosition],
+        safety: &[SafetyStatus],
+    ) -> Result<Value, HapStatus> {
         match self {
-            Self::Bridge(BridgeCharacteristic::Identify)
-            | Self::Blind {
-                characteristic: BlindCharacteristic::Identify,
-                ..
-            } => Err(HapStatus::WriteOnly),
-            Self::Bridge(BridgeCharacteristic::Manufacturer) => Ok(json!("Somfy")),
-            Self::Bridge(BridgeCharacteristic::Model) => Ok(json!("Telis 4 Bridge")),
-            Self::Bridge(BridgeCharacteristic::Name) => Ok(json!("Somfy Bridge")),
-            Self::Bridge(BridgeCharacteristic::Serial) => Ok(json!("somfy-bridge")),
-            Self::Bridge(BridgeCharacteristic::Firmware) => Ok(json!(env!("CARGO_PKG_VERSION"))),
-            Self::Bridge(BridgeCharacteristic::BridgeVersion) => Ok(json!("1.1.0")),
-            Self::Blind {
-                blind: _,
-                characteristic: BlindCharacteristic::Manufacturer,
-            } => Ok(json!("Somfy")),
-            Self::Blind {
-                blind: _,
-                characteristic: BlindCharacteristic::Model,
-            } => Ok(json!("Telis 4")),
-            Self::Blind {
-                blind,
-                characteristic: BlindCharacteristic::Name,
-            } => Ok(json!(blind.name)),
-            Self::Blind {
-                blind,
-                characteristic: BlindCharacteristic::Serial,
-            } => Ok(json!(blind.serial)),
-            Self::Blind {
-                blind: _,
-                characteristic: BlindCharacteristic::Firmware,
-            } => Ok(json!(env!("CARGO_PKG_VERSION"))),
-            Self::Blind {
-                blind,
-                characteristic: BlindCharacteristic::CurrentPosition,
-            } => {
-                let pos = position_for_aid(positions, blind.aid);
-                Ok(json!(pos.current))
-            }
+            Self::Bridge(characteristic) => bridge_value(characteristic),
             Self::Blind {
                 blind,
-                characteristic: BlindCharacteristic::TargetPosition,
-            } => {
-                let pos = position_for_aid(positions, blind.aid);
-                Ok(json!(pos.target))
-            }
-            Self::Blind {
-                blind,
-                characteristic: BlindCharacteristic::PositionState,
-            } => {
-                let pos = position_for_aid(positions, blind.aid);
-                Ok(json!(pos.status))
-            }
+                characteristic,
+            } => covering_value(characteristic, &blind.name, &blind.serial, || {
+                position_for_aid(positions, blind.aid)
+            }),
+            Self::Group {
+                group,
+                characteristic,
+            } => covering_value(characteristic, &group.name, &group.serial, || {
+                group_position(positions, group)
+            }),
+            Self::Scene {
+                scene,
+                characteristic,
+            } => scene_value(characteristic, scene),
+            Self::Safety {
+                sensor,
+                characteristic,
+            } => sensor_value(characteristic, sensor, safety),
         }
     }
 
@@ -115,16 +157,29 @@ impl HomeKitCharacteristic {
         matches!(
             self,
             Self::Blind {
+                characteristic: BlindCharacteristic::CurrentPosition
+                    | BlindCharacteristic::TargetPosition
+                    | BlindCharacteristic::PositionState
+                    | BlindCharacteristic::CurrentTilt
+                    | BlindCharacteristic::TargetTilt,
+                ..
+            } | Self::Group {
                 characteristic: BlindCharacteristic::CurrentPosition
                     | BlindCharacteristic::TargetPosition
                     | BlindCharacteristic::PositionState,
                 ..
+            } | Self::Scene {
+                characteristic: SceneCharacteristic::On,
+                ..
+            } | Self::Safety {
+                characteristic: SensorCharacteristic::OccupancyDetected,
+                ..
             }
         )
     }
 
-    pub(crate) fn write_error_status(id: CharacteristicId) -> HapStatus {
-        if Self::resolve(id).is_some() {
+    pub(crate) fn write_error_status(blinds: &BlindInventory, id: CharacteristicId) -> HapStatus {
+        if HomeKitCharacteristic::resolve(blinds, id).is_some() {
             HapStatus::ReadOnly
         } else {
             HapStatus::ResourceDoesNotExist
@@ -140,6 +195,111 @@ pub(crate) fn position_for_aid(positions: &[BlindPosition], aid: u64) -> BlindPo
         .unwrap_or_else(|| BlindPosition::default_for_aid(aid))
 }
 
+/// Aggregate position for a group accessory: rounded mean of the member
+/// positions, reporting the first moving member's direction.
+pub(crate) fn group_position(positions: &[BlindPosition], group: &BlindGroup) -> BlindPosition {
+    let aid = group.aid.unwrap_or_default();
+    let members: Vec<BlindPosition> = group
+        .member_aids
+        .iter()
+        .map(|member| position_for_aid(positions, *member))
+        .collect();
+    if members.is_empty() {
+        return BlindPosition::default_for_aid(aid);
+    }
+    let mean = |value: fn(&BlindPosition) -> u8| {
+        let sum: usize = members
+            .iter()
+            .map(|position| usize::from(value(position)))
+            .sum();
+        u8::try_from((sum + members.len() / 2) / members.len()).unwrap_or(100)
+    };
+    BlindPosition {
+        aid,
+        current: mean(|position| position.current),
+        target: mean(|position| position.target),
+        status: members
+            .iter()
+            .map(|position| position.status)
+            .find(|status| *status != STATUS_STOPPED)
+            .unwrap_or(STATUS_STOPPED),
+        current_tilt: 0,
+        target_tilt: 0,
+    }
+}
+
+fn bridge_value(characteristic: BridgeCharacteristic) -> Result<Value, HapStatus> {
+    match characteristic {
+        BridgeCharacteristic::Identify => Err(HapStatus::WriteOnly),
+        BridgeCharacteristic::Manufacturer => Ok(json!("Somfy")),
+        BridgeCharacteristic::Model => Ok(json!("Telis 4 Bridge")),
+        BridgeCharacteristic::Name => Ok(json!("Somfy Bridge")),
+        BridgeCharacteristic::Serial => Ok(json!("somfy-bridge")),
+        BridgeCharacteristic::Firmware => Ok(json!(env!("CARGO_PKG_VERSION"))),
+        BridgeCharacteristic::BridgeVersion => Ok(json!("1.1.0")),
+    }
+}
+
+/// Shared read path for blind and group window coverings.
+fn covering_value(
+    characteristic: BlindCharacteristic,
+    name: &str,
+    serial: &str,
+    position: impl FnOnce() -> BlindPosition,
+) -> Result<Value, HapStatus> {
+    match characteristic {
+        BlindCharacteristic::Identify => Err(HapStatus::WriteOnly),
+        BlindCharacteristic::Manufacturer => Ok(json!("Somfy")),
+        BlindCharacteristic::Model => Ok(json!("Telis 4")),
+        BlindCharacteristic::Name => Ok(json!(name)),
+        BlindCharacteristic::Serial => Ok(json!(serial)),
+        BlindCharacteristic::Firmware => Ok(json!(env!("CARGO_PKG_VERSION"))),
+        BlindCharacteristic::CurrentPosition => Ok(json!(position().current)),
+        BlindCharacteristic::TargetPosition => Ok(json!(position().target)),
+        BlindCharacteristic::PositionState => Ok(json!(position().status)),
+        BlindCharacteristic::CurrentTilt => Ok(json!(position().current_tilt)),
+        BlindCharacteristic::TargetTilt => Ok(json!(position().target_tilt)),
+    }
+}
+
+/// Scenes are stateless: `On` always reads false once activation is sent.
+fn scene_value(
+    characteristic: SceneCharacteristic,
+    scene: &BlindScene,
+) -> Result<Value, HapStatus> {
+    match characteristic {
+        SceneCharacteristic::Identify => Err(HapStatus::WriteOnly),
+        SceneCharacteristic::Manufacturer => Ok(json!("Somfy")),
+        SceneCharacteristic::Model => Ok(json!("Scene")),
+        SceneCharacteristic::Name => Ok(json!(scene.name)),
+        SceneCharacteristic::Serial => Ok(json!(scene.serial)),
+        SceneCharacteristic::Firmware => Ok(json!(env!("CARGO_PKG_VERSION"))),
+        SceneCharacteristic::On => Ok(json!(false)),
+    }
+}
+
+/// Occupancy reads 1 while the input is tripped, including its hold-off.
+fn sensor_value(
+    characteristic: SensorCharacteristic,
+    sensor: &SafetySensor,
+    safety: &[SafetyStatus],
+) -> Result<Value, HapStatus> {
+    match characteristic {
+        SensorCharacteristic::Identify => Err(HapStatus::WriteOnly),
+        SensorCharacteristic::Manufacturer => Ok(json!("Somfy")),
+        SensorCharacteristic::Model => Ok(json!("Safety Input")),
+        SensorCharacteristic::Name => Ok(json!(sensor.name)),
+        SensorCharacteristic::Serial => Ok(json!(sensor.serial)),
+        SensorCharacteristic::Firmware => Ok(json!(env!("CARGO_PKG_VERSION"))),
+        SensorCharacteristic::OccupancyDetected => {
+            let active = safety
+                .iter()
+                .any(|status| status.name == sensor.name && status.active);
+            Ok(json!(u8::from(active)))
+        }
+    }
+}
+
 fn bridge_characteristic(iid: u64) -> Option<BridgeCharacteristic> {
     match iid {
         IID_IDENTIFY => Some(BridgeCharacteristic::Identify),
@@ -164,6 +324,34 @@ fn blind_characteristic(iid: u64) -> Option<BlindCharacteristic> {
         IID_CURRENT_POSITION => Some(BlindCharacteristic::CurrentPosition),
         IID_TARGET_POSITION => Some(BlindCharacteristic::TargetPosition),
         IID_POSITION_STATE => Some(BlindCharacteristic::PositionState),
+        IID_CURRENT_TILT => Some(BlindCharacteristic::CurrentTilt),
+        IID_TARGET_TILT => Some(BlindCharacteristic::TargetTilt),
+        _ => None,
+    }
+}
+
+fn scene_characteristic(iid: u64) -> Option<SceneCharacteristic> {
+    match iid {
+        IID_IDENTIFY => Some(SceneCharacteristic::Identify),
+        IID_MANUFACTURER => Some(SceneCharacteristic::Manufacturer),
+        IID_MODEL => Some(SceneCharacteristic::Model),
+        IID_NAME => Some(SceneCharacteristic::Name),
+        IID_SERIAL => Some(SceneCharacteristic::Serial),
+        IID_FIRMWARE => Some(SceneCharacteristic::Firmware),
+        IID_SWITCH_ON => Some(SceneCharacteristic::On),
+        _ => None,
+    }
+}
+
+fn sensor_characteristic(iid: u64) -> Option<SensorCharacteristic> {
+    match iid {
+        IID_IDENTIFY => Some(SensorCharacteristic::Identify),
+        IID_MANUFACTURER => Some(SensorCharacteristic::Manufacturer),
+        IID_MODEL => Some(SensorCharacteristic::Model),
+        IID_NAME => Some(SensorCharacteristic::Name),
+        IID_SERIAL => Some(SensorCharacteristic::Serial),
+        IID_FIRMWARE => Some(SensorCharacteristic::Firmware),
+        IID_OCCUPANCY_DETECTED => Some(SensorCharacteristic::OccupancyDetected),
         _ => None,
     }
 }
@@ -1,16 +1,21 @@
 //! Project-specific HomeKit accessory adapters.
 
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
+use tokio::time::Instant;
 
 use crate::controller::BlindController;
 use crate::hap::mdns::{self, MdnsConfig};
-use crate::hap::runtime::{CharacteristicEvent, HapRuntime};
+use crate::hap::runtime::{CharacteristicEvent, CharacteristicId, HapRuntime, HapStore};
 use crate::hap::state::{FileHapStore, HapState};
 use crate::hap::{qr, server};
+use crate::homekit::accessory_db::IID_OCCUPANCY_DETECTED;
 use crate::persist;
-use crate::positioning::state::PositionDelta;
+use crate::positioning::inventory::BlindInventory;
+use crate::positioning::state::{snapshot_deltas, PositionDelta};
 
 mod accessory_db;
 mod characteristic;
@@ -36,12 +41,14 @@ pub struct HomekitHandles {
     _announcement: mdns::Announcement,
     hap_server: tokio::task::JoinHandle<()>,
     _position_events: tokio::task::JoinHandle<()>,
+    _safety_events: tokio::task::JoinHandle<()>,
 }
 
 impl HomekitHandles {
     pub fn abort(&self) {
         self.hap_server.abort();
         self._position_events.abort();
+        self._safety_events.abort();
     }
 }
 
@@ -50,7 +57,16 @@ impl HomekitHandles {
 /// port.
 pub async fn start(controller: Arc<BlindController>) -> Result<HomekitHandles> {
     let store = store();
-    let hap_state = store.load_or_init()?;
+    let mut hap_state = store.load_or_init()?;
+    let fingerprint = somfy::accessory_fingerprint(controller.blinds());
+    let legacy = somfy::accessory_fingerprint(&BlindInventory::default());
+    if hap_state.update_accessory_fingerprint(&fingerprint, &legacy) {
+        store.save_state(&hap_state)?;
+        tracing::info!(
+            config_number = hap_state.config_number,
+            "recorded HomeKit accessory layout"
+        );
+    }
     let setup_uri = setup_uri(&hap_state)?;
     mdns::log_setup_payload(&hap_state, HAP_PORT, &setup_uri);
     let announcement = mdns::announce(
@@ -67,7 +83,12 @@ pub async fn start(controller: Arc<BlindController>) -> Result<HomekitHandles> {
     let app = Arc::new(somfy::SomfyHapApp::new(controller.clone()));
     let runtime = Arc::new(HapRuntime::new(hap_state, store, app, events));
 
-    let position_events = spawn_position_events(controller, runtime.event_sender());
+    let position_events = spawn_position_events(
+        controller.clone(),
+        runtime.event_sender(),
+        PROGRESS_EVENT_INTERVAL,
+    );
+    let safety_events = spawn_safety_events(controller, runtime.event_sender());
 
     let hap_server = tokio::spawn(async move {
         if let Err(e) = server::serve(runtime, HAP_PORT).await {
@@ -78,36 +99,90 @@ pub async fn start(controller: Arc<BlindController>) -> Result<HomekitHandles> {
         _announcement: announcement,
         hap_server,
         _position_events: position_events,
+        _safety_events: safety_events,
     })
 }
 
+/// Minimum spacing between HAP pushes of mid-move `CurrentPosition` updates.
+/// Controllers are notified of every start and stop immediately; progress in
+/// between is coalesced per accessory so a long move does not flood them.
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_secs(2);
+
 fn spawn_position_events(
     controller: Arc<BlindController>,
     event_tx: broadcast::Sender<Vec<CharacteristicEvent>>,
+    progress_interval: Duration,
 ) -> tokio::task::JoinHandle<()> {
     let mut position_rx = controller.subscribe_positions();
     tokio::spawn(async move {
+        let mut progress: BTreeMap<u64, PositionDelta> = BTreeMap::new();
+        let mut flush_at: Option<Instant> = None;
         loop {
-            let events = match position_rx.recv().await {
-                Ok(deltas) => somfy::position_characteristic_events(deltas.as_ref()),
-                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+            let flush = async {
+                match flush_at {
+                    Some(at) => tokio::time::sleep_until(at).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let received = tokio::select! {
+                received = position_rx.recv() => Some(received),
+                () = flush => None,
+            };
+            let events = match received {
+                None => {
+                    flush_at = None;
+                    let deltas: Vec<PositionDelta> =
+                        std::mem::take(&mut progress).into_values().collect();
+                    position_events(&controller, &deltas).await
+                }
+                Some(Ok(deltas)) => {
+                    // Progress sent together with a start or stop for the same
+                    // blind is where an interrupted move ended; publish it now.
+                    let (moving, settled): (Vec<PositionDelta>, Vec<PositionDelta>) =
+                        deltas.iter().partition(|delta| {
+                            delta.is_progress()
+                                && !deltas
+                                    .iter()
+                                    .any(|other| other.aid == delta.aid && !other.is_progress())
+                        });
+                    let mut publish = Vec::with_capacity(settled.len());
+                    for delta in settled {
+                        if let Some(held) = progress.remove(&delta.aid) {
+                            if delta.current.is_none() {
+                                publish.push(held);
+                            }
+                        }
+                        publish.push(delta);
+                    }
+                    for delta in moving {
+                        progress.insert(delta.aid, delta);
+                    }
+                    if progress.is_empty() {
+                        flush_at = None;
+                    } else if flush_at.is_none() {
+                        flush_at = Some(Instant::now() + progress_interval);
+                    }
+                    position_events(&controller, &publish).await
+                }
+                Some(Err(broadcast::error::RecvError::Lagged(skipped))) => {
                     tracing::warn!(
                         skipped,
                         "position broadcast lagged; resyncing HAP position events from snapshot"
                     );
+                    progress.clear();
+                    flush_at = None;
                     let positions = controller.position_snapshot().await;
-                    let deltas: Vec<PositionDelta> = positions
-                        .iter()
-                        .map(|pos| PositionDelta {
-                            aid: pos.aid,
-                            current: Some(pos.current),
-                            target: Some(pos.target),
-                            status: Some(pos.status),
-                        })
-                        .collect();
+                    let mut deltas = snapshot_deltas(controller.blinds(), &positions);
+                    let every: Vec<u64> =
+                        controller.blinds().iter().map(|blind| blind.aid).collect();
+                    deltas.extend(somfy::group_position_deltas(
+                        controller.blinds(),
+                        &positions,
+                        &every,
+                    ));
                     somfy::position_characteristic_events(&deltas)
                 }
-                Err(broadcast::error::RecvError::Closed) => break,
+                Some(Err(broadcast::error::RecvError::Closed)) => break,
             };
 
             if !events.is_empty() {
@@ -118,20 +193,72 @@ fn spawn_position_events(
     })
 }
 
+/// Push `OccupancyDetected` for bridged safety inputs whenever one trips or clears.
+fn spawn_safety_events(
+    controller: Arc<BlindController>,
+    event_tx: broadcast::Sender<Vec<CharacteristicEvent>>,
+) -> tokio::task::JoinHandle<()> {
+    let mut safety_rx = controller.subscribe_safety();
+    tokio::spawn(async move {
+        while safety_rx.changed().await.is_ok() {
+            let statuses = safety_rx.borrow_and_update().clone();
+            let events: Vec<CharacteristicEvent> = controller
+                .blinds()
+                .safety_sensors()
+                .filter_map(|sensor| {
+                    let aid = sensor.aid?;
+                    let active = statuses
+                        .iter()
+                        .any(|status| status.name == sensor.name && status.active);
+                    Some(CharacteristicEvent {
+                        id: CharacteristicId::new(aid, IID_OCCUPANCY_DETECTED),
+                        value: serde_json::json!(u8::from(active)),
+                    })
+                })
+                .collect();
+            if !events.is_empty() {
+                tracing::debug!(count = events.len(), "hap safety events published");
+                let _ = event_tx.send(events);
+            }
+        }
+    })
+}
+
+/// HAP events for blind deltas plus any bridged group whose members moved.
+async fn position_events(
+    controller: &BlindController,
+    deltas: &[PositionDelta],
+) -> Vec<CharacteristicEvent> {
+    let mut events = somfy::position_characteristic_events(deltas);
+    let touched: Vec<u64> = deltas.iter().map(|delta| delta.aid).collect();
+    if controller
+        .blinds()
+        .groups_containing(&touched)
+        .any(|group| group.aid.is_some())
+    {
+        let positions = controller.position_snapshot().await;
+        let groups = somfy::group_position_deltas(controller.blinds(), &positions, &touched);
+        events.extend(somfy::position_characteristic_events(&groups));
+    }
+    events
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::homekit::accessory_db::IID_TARGET_POSITION;
-    use crate::testing::fixtures::fake_four_blinds;
+    use crate::history::CommandSource;
+    use crate::homekit::accessory_db::{IID_CURRENT_POSITION, IID_TARGET_POSITION};
+    use crate::testing::fixtures::{fake_controller, fake_four_blinds, uniform_positioning_l1_ms};
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn position_bridge_maps_deltas_to_hap_events() {
         let controller = fake_four_blinds(10).await;
         let (hap_tx, mut hap_rx) = broadcast::channel(8);
-        let _bridge = spawn_position_events(controller.clone(), hap_tx);
+        let _bridge = spawn_position_events(controller.clone(), hap_tx, PROGRESS_EVENT_INTERVAL);
 
         controller
-            .set_target_positions(vec![(2, 50)])
+            .set_target_positions(vec![(2, 50)], CommandSource::Cli)
             .await
             .unwrap();
 
@@ -141,4 +268,41 @@ mod tests {
             .iter()
             .any(|event| event.id.aid.0 == 2 && event.id.iid.0 == IID_TARGET_POSITION));
     }
+
+    #[tokio::test]
+    async fn position_bridge_coalesces_progress_events() {
+        let mut positioning = uniform_positioning_l1_ms(400);
+        positioning.update_interval_ms = 40;
+        let controller = fake_controller(positioning, HashMap::from([(2, 100)])).await;
+        let mut progress_rx = controller.subscribe_positions();
+        let (hap_tx, mut hap_rx) = broadcast::channel(64);
+        let _bridge = spawn_position_events(controller.clone(), hap_tx, Duration::from_millis(150));
+
+        controller
+            .set_target_positions(vec![(2, 0)], CommandSource::Cli)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let mut published = 0;
+        while let Ok(deltas) = progress_rx.try_recv() {
+            published += deltas.iter().filter(|delta| delta.is_progress()).count();
+        }
+        let mut currents = Vec::new();
+        while let Ok(events) = hap_rx.try_recv() {
+            currents.extend(
+                events
+                    .iter()
+                    .filter(|event| event.id.aid.0 == 2 && event.id.iid.0 == IID_CURRENT_POSITION)
+                    .map(|event| event.value.as_u64().unwrap()),
+            );
+        }
+        assert_eq!(currents.last(), Some(&0));
+        let interim = currents.len() - 1;
+        assert!(interim >= 1, "{currents:?}");
+        assert!(
+            interim < published,
+            "{currents:?} vs {published} progress deltas"
+        );
+    }
 }
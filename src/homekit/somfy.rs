@@ -3,6 +3,7 @@
 //! This module maps HomeKit characteristics onto the shared blind controller.
 
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 use crate::controller::BlindController;
@@ -11,12 +12,17 @@ use crate::hap::runtime::{
     CharacteristicWriteOutcome, CharacteristicWriteStatus, HapAccessoryApp, HapFuture, HapStatus,
     Subscriptions,
 };
+use crate::history::{CommandSource, HistoryEntry};
 use crate::homekit::accessory_db::{
-    self, BlindAccessory, IID_CURRENT_POSITION, IID_POSITION_STATE, IID_TARGET_POSITION,
+    self, BlindAccessory, SceneAccessory, SensorAccessory, IID_CURRENT_POSITION, IID_CURRENT_TILT,
+    IID_POSITION_STATE, IID_TARGET_POSITION, IID_TARGET_TILT,
 };
-use crate::homekit::characteristic::{position_for_aid, HomeKitCharacteristic};
+use crate::homekit::characteristic::{group_position, position_for_aid, HomeKitCharacteristic};
 use crate::homekit::target_writes::{plan_target_writes, PendingTargetWrite};
-use crate::positioning::state::{BlindPosition, PositionDelta, BLINDS};
+use crate::positioning::inventory::BlindInventory;
+use crate::positioning::state::{BlindPosition, PositionDelta};
+use crate::safety::SafetyStatus;
+use crate::service::dispatch_scene;
 
 pub struct SomfyHapApp {
     controller: Arc<BlindController>,
@@ -27,17 +33,51 @@ impl SomfyHapApp {
         Self { controller }
     }
 
-    async fn execute_targets(&self, targets: &[PendingTargetWrite]) -> anyhow::Result<()> {
+    async fn execute_targets(
+        &self,
+        targets: &[PendingTargetWrite],
+        controller_id: Option<&str>,
+    ) -> anyhow::Result<()> {
         self.controller
             .set_target_positions(
                 targets
                     .iter()
-                    .map(|target| (target.blind.aid, target.target))
+                    .flat_map(|target| target.aids.iter().map(|aid| (*aid, target.target)))
                     .collect(),
+                CommandSource::HomeKit {
+                    pairing: controller_id.map(str::to_string),
+                },
             )
             .await
             .map(|_| ())
     }
+
+    /// Record one history entry per written blind.
+    fn record_writes<T>(
+        &self,
+        controller_id: Option<&str>,
+        command: &str,
+        writes: impl Iterator<Item = (u64, i16)>,
+        result: &anyhow::Result<T>,
+    ) {
+        let result = result.as_ref().map(|_| ()).map_err(|e| format!("{e:#}"));
+        for (aid, target) in writes {
+            let channel = self
+                .controller
+                .blinds()
+                .find(aid)
+                .map(|blind| blind.channel);
+            self.controller.history().record(HistoryEntry::now(
+                CommandSource::HomeKit {
+                    pairing: controller_id.map(str::to_string),
+                },
+                command,
+                channel,
+                Some(target),
+                result.clone(),
+            ));
+        }
+    }
 }
 
 /// Map controller position deltas to HAP characteristic events for EVENT push.
@@ -62,15 +102,55 @@ pub(crate) fn position_characteristic_events(deltas: &[PositionDelta]) -> Vec<Ch
                 value: serde_json::json!(status),
             });
         }
+        if let Some(tilt) = delta.current_tilt {
+            events.push(CharacteristicEvent {
+                id: CharacteristicId::new(delta.aid, IID_CURRENT_TILT),
+                value: serde_json::json!(tilt),
+            });
+        }
+        if let Some(tilt) = delta.target_tilt {
+            events.push(CharacteristicEvent {
+                id: CharacteristicId::new(delta.aid, IID_TARGET_TILT),
+                value: serde_json::json!(tilt),
+            });
+        }
     }
     events
 }
 
+/// Full position deltas for group accessories whose members appear in
+/// `touched`, so HomeKit sees the aggregate move alongside its members.
+pub(crate) fn group_position_deltas(
+    blinds: &BlindInventory,
+    positions: &[BlindPosition],
+    touched: &[u64],
+) -> Vec<PositionDelta> {
+    blinds
+        .groups_containing(touched)
+        .filter(|group| group.aid.is_some())
+        .map(|group| {
+            let position = group_position(positions, group);
+            PositionDelta {
+                aid: position.aid,
+                current: Some(position.current),
+                target: Some(position.target),
+                status: Some(position.status),
+                current_tilt: None,
+                target_tilt: None,
+            }
+        })
+        .collect()
+}
+
 impl HapAccessoryApp for SomfyHapApp {
     fn accessories(&self) -> HapFuture<'_, Value> {
         Box::pin(async move {
             let positions = self.controller.position_snapshot().await;
-            Ok(build_accessories(&positions))
+            Ok(build_accessories(
+                self.controller.blinds(),
+                &positions,
+                &self.controller.safety_status(),
+            ))
         })
     }
 
@@ -80,9 +160,10 @@ impl HapAccessoryApp for SomfyHapApp {
     ) -> HapFuture<'a, Vec<CharacteristicRead>> {
         Box::pin(async move {
             let positions = self.controller.position_snapshot().await;
+            let safety = self.controller.safety_status();
             let values = ids
                 .iter()
-                .map(|id| read_characteristic(&positions, *id))
+                .map(|id| read_characteristic(self.controller.blinds(), &positions, &safety, *id))
                 .collect();
             Ok(values)
         })
@@ -92,16 +173,78 @@ impl HapAccessoryApp for SomfyHapApp {
         &'a self,
         writes: Vec<CharacteristicWrite>,
         subscriptions: &'a mut Subscriptions,
+        controller_id: Option<&'a str>,
     ) -> HapFuture<'a, CharacteristicWriteOutcome> {
         Box::pin(async move {
-            let plan = plan_target_writes(writes, subscriptions);
+            let plan = plan_target_writes(self.controller.blinds(), writes, subscriptions);
             let mut outcome = CharacteristicWriteOutcome::default();
             let mut statuses = plan.statuses;
 
             // Position EVENT push is via the position bridge (see `homekit::start`).
-            self.execute_targets(&plan.targets).await?;
+            let result = self.execute_targets(&plan.targets, controller_id).await;
+            self.record_writes(
+                controller_id,
+                "target",
+                plan.targets.iter().flat_map(|target| {
+                    target
+                        .aids
+                        .iter()
+                        .map(|aid| (*aid, i16::from(target.target)))
+                }),
+                &result,
+            );
+            // A refused move (locked channel, resting motor) fails only its
+            // own characteristics, so Home shows the error on those tiles.
+            let status = write_status(&result, "target position write failed");
             for target in plan.targets {
-                statuses[target.index] = Some(CharacteristicWriteStatus::success(target.id));
+                statuses[target.index] = Some(status(target.id));
+            }
+            let mut tilt_result = Ok(());
+            if !plan.tilts.is_empty() {
+                let result = self
+                    .controller
+                    .set_tilt_angles(
+                        plan.tilts
+                            .iter()
+                            .map(|tilt| (tilt.aid, tilt.angle))
+                            .collect(),
+                    )
+                    .await;
+                self.record_writes(
+                    controller_id,
+                    "tilt",
+                    plan.tilts
+                        .iter()
+                        .map(|tilt| (tilt.aid, i16::from(tilt.angle))),
+                    &result,
+                );
+                tilt_result = result.map(|_| ());
+            }
+            let status = write_status(&tilt_result, "tilt write failed");
+            for tilt in plan.tilts {
+                statuses[tilt.index] = Some(status(tilt.id));
+            }
+            for scene in plan.scenes {
+                let source = CommandSource::HomeKit {
+                    pairing: controller_id.map(str::to_string),
+                };
+                statuses[scene.index] = Some(
+                    match dispatch_scene(&self.controller, &scene.name, source).await {
+                        Ok(_) => CharacteristicWriteStatus::success(scene.id),
+                        Err(e) => {
+                            tracing::error!(scene = %scene.name, error = %e, "scene activation failed");
+                            CharacteristicWriteStatus::error(
+                                scene.id,
+                                HapStatus::ServiceCommunicationFailure,
+                            )
+                        }
+                    },
+                );
+                // Scene switches are stateless: flip the tile back off.
+                outcome.events.push(CharacteristicEvent {
+                    id: scene.id,
+                    value: serde_json::json!(false),
+                });
             }
             outcome.statuses = statuses.into_iter().flatten().collect();
             Ok(outcome)
@@ -109,37 +252,146 @@ impl HapAccessoryApp for SomfyHapApp {
     }
 }
 
-fn read_characteristic(positions: &[BlindPosition], id: CharacteristicId) -> CharacteristicRead {
-    let Some(characteristic) = HomeKitCharacteristic::resolve(id) else {
+/// Per-characteristic status for a batch that succeeded or failed as a whole.
+fn write_status<T>(
+    result: &anyhow::Result<T>,
+    context: &'static str,
+) -> impl Fn(CharacteristicId) -> CharacteristicWriteStatus {
+    if let Err(e) = result {
+        tracing::error!(error = %format!("{e:#}"), "{context}");
+    }
+    let failed = result.is_err();
+    move |id| {
+        if failed {
+            CharacteristicWriteStatus::error(id, HapStatus::ServiceCommunicationFailure)
+        } else {
+            CharacteristicWriteStatus::success(id)
+        }
+    }
+}
+
+fn read_characteristic(
+    blinds: &BlindInventory,
+    positions: &[BlindPosition],
+    safety: &[SafetyStatus],
+    id: CharacteristicId,
+) -> CharacteristicRead {
+    let Some(characteristic) = HomeKitCharacteristic::resolve(blinds, id) else {
         return CharacteristicRead::error(id, HapStatus::ResourceDoesNotExist);
     };
-    match characteristic.read_value(positions) {
+    match characteristic.read_value(positions, safety) {
         Ok(value) => CharacteristicRead::success(id, value),
         Err(status) => CharacteristicRead::error(id, status),
     }
 }
 
-fn build_accessories(positions: &[BlindPosition]) -> Value {
-    let blinds: Vec<BlindAccessory<'_>> = BLINDS
+fn build_accessories(
+    blinds: &BlindInventory,
+    positions: &[BlindPosition],
+    safety: &[SafetyStatus],
+) -> Value {
+    let groups = blinds.groups().filter_map(|group| {
+        group.aid.map(|aid| BlindAccessory {
+            aid,
+            name: &group.name,
+            serial: &group.serial,
+            position: group_position(positions, group).current,
+            tilt: None,
+        })
+    });
+    let accessories: Vec<BlindAccessory<'_>> = blinds
         .iter()
         .map(|blind| BlindAccessory {
             aid: blind.aid,
-            name: blind.name,
-            serial: blind.serial,
+            name: &blind.name,
+            serial: &blind.serial,
             position: position_for_aid(positions, blind.aid).current,
+            tilt: blind
+                .is_venetian()
+                .then(|| position_for_aid(positions, blind.aid).current_tilt),
         })
+        .chain(groups)
         .collect();
-    accessory_db::build_accessories(&blinds)
+    let scenes: Vec<SceneAccessory<'_>> = blinds
+        .scenes()
+        .filter_map(|scene| {
+            scene.aid.map(|aid| SceneAccessory {
+                aid,
+                name: &scene.name,
+                serial: &scene.serial,
+            })
+        })
+        .collect();
+    let sensors: Vec<SensorAccessory<'_>> = blinds
+        .safety_sensors()
+        .filter_map(|sensor| {
+            sensor.aid.map(|aid| SensorAccessory {
+                aid,
+                name: &sensor.name,
+                serial: &sensor.serial,
+                active: safety
+                    .iter()
+                    .any(|status| status.name == sensor.name && status.active),
+            })
+        })
+        .collect();
+    accessory_db::build_accessories(&accessories, &scenes, &sensors)
+}
+
+/// Stable digest of the bridged accessory layout (AIDs, names, serials),
+/// including groups, scenes, and safety inputs that carry their own `aid`.
+/// `homekit::start` bumps the HAP `config_number` when it changes.
+pub(crate) fn accessory_fingerprint(blinds: &BlindInventory) -> String {
+    let mut hasher = Sha256::new();
+    for blind in blinds.iter() {
+        // Venetian blinds add tilt characteristics, so their kind is part of
+        // the layout; roller lines keep the original format.
+        let kind = if blind.is_venetian() {
+            "\tvenetian"
+        } else {
+            ""
+        };
+        hasher.update(format!(
+            "{}\t{}\t{}{kind}\n",
+            blind.aid, blind.name, blind.serial
+        ));
+    }
+    for group in blinds.groups() {
+        if let Some(aid) = group.aid {
+            hasher.update(format!("{}\t{}\t{}\n", aid, group.name, group.serial));
+        }
+    }
+    for scene in blinds.scenes() {
+        if let Some(aid) = scene.aid {
+            hasher.update(format!(
+                "{}\t{}\t{}\tscene\n",
+                aid, scene.name, scene.serial
+            ));
+        }
+    }
+    for sensor in blinds.safety_sensors() {
+        if let Some(aid) = sensor.aid {
+            hasher.update(format!(
+                "{}\t{}\t{}\tsafety\n",
+                aid, sensor.name, sensor.serial
+            ));
+        }
+    }
+    hex::encode(hasher.finalize())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{DriverConfig, GroupOptions, PositioningOptions, SceneOptions};
     use crate::core::{Channel, Command};
     use crate::driver::ProtocolOperation;
-    use crate::positioning::state::STATUS_STOPPED;
-    use crate::testing::fixtures::fake_four_blinds;
+    use crate::history::HistoryFilter;
+    use crate::homekit::accessory_db::IID_SWITCH_ON;
+    use crate::positioning::state::{STATUS_DECREASING, STATUS_STOPPED};
+    use crate::testing::fixtures::{fake_four_blinds, inventory};
     use serde_json::json;
+    use std::collections::HashMap;
     use tokio::time::Duration;
 
     async fn wait_for_current(app: &SomfyHapApp, aid: u64, expected: u8) {
@@ -159,9 +411,16 @@ mod tests {
             current: 0,
             target: 0,
             status: STATUS_STOPPED,
+            current_tilt: 0,
+            target_tilt: 0,
         }];
 
-        let read = read_characteristic(&positions, CharacteristicId::new(2, IID_CURRENT_POSITION));
+        let read = read_characteristic(
+            &BlindInventory::default(),
+            &positions,
+            &[],
+            CharacteristicId::new(2, IID_CURRENT_POSITION),
+        );
 
         assert_eq!(read.status, HapStatus::Success);
         assert_eq!(read.value, Some(json!(0)));
@@ -169,40 +428,338 @@ mod tests {
 
     #[test]
     fn accessories_expose_four_blinds() {
-        let body = build_accessories(&[
+        let body = build_accessories(
+            &BlindInventory::default(),
+            &[
+                BlindPosition {
+                    aid: 2,
+                    current: 100,
+                    target: 100,
+                    status: STATUS_STOPPED,
+                    current_tilt: 0,
+                    target_tilt: 0,
+                },
+                BlindPosition {
+                    aid: 3,
+                    current: 100,
+                    target: 100,
+                    status: STATUS_STOPPED,
+                    current_tilt: 0,
+                    target_tilt: 0,
+                },
+                BlindPosition {
+                    aid: 4,
+                    current: 100,
+                    target: 100,
+                    status: STATUS_STOPPED,
+                    current_tilt: 0,
+                    target_tilt: 0,
+                },
+                BlindPosition {
+                    aid: 5,
+                    current: 100,
+                    target: 100,
+                    status: STATUS_STOPPED,
+                    current_tilt: 0,
+                    target_tilt: 0,
+                },
+            ],
+            &[],
+        );
+        let aids = body["accessories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|accessory| accessory["aid"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(aids, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn accessories_follow_configured_inventory() {
+        let blinds = inventory(&[(2, Channel::L1), (12, Channel::Individual(11))]);
+
+        let body = build_accessories(&blinds, &[], &[]);
+        let accessories = body["accessories"].as_array().unwrap();
+
+        assert_eq!(
+            accessories
+                .iter()
+                .map(|accessory| accessory["aid"].as_u64().unwrap())
+                .collect::<Vec<_>>(),
+            vec![1, 2, 12]
+        );
+        let read = read_characteristic(
+            &blinds,
+            &[],
+            &[],
+            CharacteristicId::new(12, IID_TARGET_POSITION),
+        );
+        assert_eq!(read.value, Some(json!(100)));
+        let missing = read_characteristic(
+            &blinds,
+            &[],
+            &[],
+            CharacteristicId::new(3, IID_TARGET_POSITION),
+        );
+        assert_eq!(missing.status, HapStatus::ResourceDoesNotExist);
+    }
+
+    #[test]
+    fn accessory_fingerprint_tracks_inventory_changes() {
+        let legacy = accessory_fingerprint(&BlindInventory::default());
+        let two = [(2, Channel::L1), (3, Channel::L2)];
+
+        assert_eq!(accessory_fingerprint(&BlindInventory::default()), legacy);
+        assert_eq!(
+            accessory_fingerprint(&inventory(&two)),
+            accessory_fingerprint(&inventory(&two))
+        );
+        assert_ne!(accessory_fingerprint(&inventory(&two)), legacy);
+        assert_ne!(
+            accessory_fingerprint(&inventory(&two)),
+            accessory_fingerprint(&inventory(&[(2, Channel::L1), (4, Channel::L2)]))
+        );
+    }
+
+    #[test]
+    fn safety_inputs_with_an_aid_are_bridged_as_occupancy_sensors() {
+        use crate::config::SafetyInputOptions;
+        use crate::homekit::accessory_db::IID_OCCUPANCY_DETECTED;
+
+        let blinds = BlindInventory::default().with_safety(&[SafetyInputOptions {
+            name: "wind".to_string(),
+            gpio: 20,
+            active_low: false,
+            channels: Vec::new(),
+            aid: Some(30),
+        }]);
+        let active = [SafetyStatus {
+            name: "wind".to_string(),
+            channels: vec![Channel::All],
+            active: true,
+            since: Some(1),
+        }];
+        let occupancy = CharacteristicId::new(30, IID_OCCUPANCY_DETECTED);
+
+        let body = build_accessories(&blinds, &[], &active);
+        let sensor = body["accessories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|accessory| accessory["aid"] == 30)
+            .unwrap();
+        assert_eq!(sensor["services"][1]["type"], "86");
+        assert_eq!(sensor["services"][1]["characteristics"][0]["value"], 1);
+        assert_eq!(
+            read_characteristic(&blinds, &[], &active, occupancy).value,
+            Some(json!(1))
+        );
+        assert_eq!(
+            read_characteristic(&blinds, &[], &[], occupancy).value,
+            Some(json!(0))
+        );
+        assert_ne!(
+            accessory_fingerprint(&blinds),
+            accessory_fingerprint(&BlindInventory::default())
+        );
+    }
+
+    #[test]
+    fn venetian_blinds_expose_tilt_characteristics() {
+        let mut options = crate::config::default_blinds();
+        options[0].kind = crate::config::BlindKind::Venetian;
+        let blinds = BlindInventory::from_options(&options);
+
+        let body = build_accessories(&blinds, &[], &[]);
+        let iids = |aid: u64| {
+            body["accessories"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|accessory| accessory["aid"] == aid)
+                .unwrap()["services"][1]["characteristics"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|characteristic| characteristic["iid"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(iids(2), vec![9, 10, 11, 12, 13]);
+        assert_eq!(iids(3), vec![9, 10, 11]);
+        let read = read_characteristic(
+            &blinds,
+            &[],
+            &[],
+            CharacteristicId::new(2, IID_CURRENT_TILT),
+        );
+        assert_eq!(read.value, Some(json!(0)));
+        let missing = read_characteristic(
+            &blinds,
+            &[],
+            &[],
+            CharacteristicId::new(3, IID_CURRENT_TILT),
+        );
+        assert_eq!(missing.status, HapStatus::ResourceDoesNotExist);
+        assert_ne!(
+            accessory_fingerprint(&blinds),
+            accessory_fingerprint(&BlindInventory::default())
+        );
+    }
+
+    #[test]
+    fn group_accessory_aggregates_member_positions() {
+        let blinds =
+            inventory(&[(2, Channel::L1), (3, Channel::L2)]).with_groups(&[GroupOptions {
+                name: "Living room".to_string(),
+                channel: Channel::Group(1),
+                blinds: vec![Channel::L1, Channel::L2],
+                aid: Some(20),
+                paired: false,
+            }]);
+        let positions = [
             BlindPosition {
                 aid: 2,
                 current: 100,
-                target: 100,
-                status: STATUS_STOPPED,
+                target: 0,
+                status: STATUS_DECREASING,
+                current_tilt: 0,
+                target_tilt: 0,
             },
             BlindPosition {
                 aid: 3,
-                current: 100,
-                target: 100,
+                current: 51,
+                target: 51,
                 status: STATUS_STOPPED,
+                current_tilt: 0,
+                target_tilt: 0,
             },
-            BlindPosition {
-                aid: 4,
-                current: 100,
-                target: 100,
-                status: STATUS_STOPPED,
-            },
-            BlindPosition {
-                aid: 5,
-                current: 100,
-                target: 100,
-                status: STATUS_STOPPED,
-            },
-        ]);
-        let aids = body["accessories"]
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|accessory| accessory["aid"].as_u64().unwrap())
-            .collect::<Vec<_>>();
+        ];
+
+        let body = build_accessories(&blinds, &positions, &[]);
+        assert_eq!(body["accessories"][3]["aid"], json!(20));
+        for (iid, expected) in [
+            (IID_CURRENT_POSITION, json!(76)),
+            (IID_TARGET_POSITION, json!(26)),
+            (IID_POSITION_STATE, json!(STATUS_DECREASING)),
+        ] {
+            let read =
+                read_characteristic(&blinds, &positions, &[], CharacteristicId::new(20, iid));
+            assert_eq!(read.value, Some(expected), "iid {iid}");
+        }
+        assert_ne!(
+            accessory_fingerprint(&blinds),
+            accessory_fingerprint(&inventory(&[(2, Channel::L1), (3, Channel::L2)]))
+        );
+        assert_eq!(
+            group_position_deltas(&blinds, &positions, &[3])
+                .iter()
+                .map(|delta| delta.aid)
+                .collect::<Vec<_>>(),
+            vec![20]
+        );
+        assert!(group_position_deltas(&blinds, &positions, &[9]).is_empty());
+    }
 
-        assert_eq!(aids, vec![1, 2, 3, 4, 5]);
+    #[tokio::test]
+    async fn group_target_write_moves_every_member() {
+        let blinds = BlindInventory::default().with_groups(&[GroupOptions {
+            name: "Living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L1, Channel::L3],
+            aid: Some(20),
+            paired: false,
+        }]);
+        let controller = Arc::new(
+            BlindController::with_driver_and_positions_for_test(
+                DriverConfig::fake(),
+                blinds,
+                PositioningOptions::default(),
+                HashMap::from([(2, 100), (3, 100), (4, 100), (5, 100)]),
+            )
+            .await
+            .unwrap(),
+        );
+        let app = SomfyHapApp::new(controller.clone());
+        let mut subscriptions = Subscriptions::default();
+
+        let outcome = app
+            .write_characteristics(
+                vec![CharacteristicWrite {
+                    id: CharacteristicId::new(20, IID_TARGET_POSITION),
+                    value: Some(json!(0)),
+                    ev: None,
+                }],
+                &mut subscriptions,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(outcome.all_success());
+        assert_eq!(controller.position_for_aid(2).await.target, 0);
+        assert_eq!(controller.position_for_aid(3).await.target, 100);
+        assert_eq!(controller.position_for_aid(4).await.target, 0);
+    }
+
+    #[tokio::test]
+    async fn scene_switch_starts_every_blind_together_and_turns_back_off() {
+        let blinds = BlindInventory::default().with_scenes(&[SceneOptions {
+            name: "Night".to_string(),
+            positions: [Channel::L1, Channel::L2, Channel::L3, Channel::L4]
+                .into_iter()
+                .map(|channel| (channel, 0))
+                .collect(),
+            aid: Some(30),
+        }]);
+        let on = CharacteristicId::new(30, IID_SWITCH_ON);
+        let body = build_accessories(&blinds, &[], &[]);
+        assert_eq!(body["accessories"][5]["aid"], json!(30));
+        assert_eq!(body["accessories"][5]["services"][1]["type"], json!("49"));
+        assert_eq!(
+            read_characteristic(&blinds, &[], &[], on).value,
+            Some(json!(false))
+        );
+
+        let controller = Arc::new(
+            BlindController::with_driver_and_positions_for_test(
+                DriverConfig::fake(),
+                blinds,
+                PositioningOptions::default(),
+                HashMap::from([(2, 100), (3, 100), (4, 100), (5, 100)]),
+            )
+            .await
+            .unwrap(),
+        );
+        let app = SomfyHapApp::new(controller.clone());
+        let mut subscriptions = Subscriptions::default();
+
+        let outcome = app
+            .write_characteristics(
+                vec![CharacteristicWrite {
+                    id: on,
+                    value: Some(json!(true)),
+                    ev: None,
+                }],
+                &mut subscriptions,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(outcome.all_success());
+        assert_eq!(outcome.events.len(), 1);
+        assert_eq!(outcome.events[0].value, json!(false));
+        assert_eq!(
+            controller.operations(),
+            vec![ProtocolOperation::FakeCommand {
+                channel: Channel::All,
+                command: Command::Down,
+            }]
+        );
+        assert_eq!(controller.position_for_aid(5).await.target, 0);
     }
 
     #[tokio::test]
@@ -217,7 +774,7 @@ mod tests {
         let mut subscriptions = Subscriptions::default();
 
         let outcome = app
-            .write_characteristics(writes, &mut subscriptions)
+            .write_characteristics(writes, &mut subscriptions, None)
             .await
             .unwrap();
 
@@ -262,6 +819,7 @@ mod tests {
                     ev: None,
                 }],
                 &mut subscriptions,
+                Some("pairing-a"),
             )
             .await
             .unwrap();
@@ -271,6 +829,61 @@ mod tests {
         let published = position_rx.recv().await.unwrap();
         assert!(!published.is_empty());
         assert_eq!(published[0].target, Some(50));
+
+        let history = app.controller.history().query(&HistoryFilter::default());
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].source,
+            CommandSource::HomeKit {
+                pairing: Some("pairing-a".to_string())
+            }
+        );
+        assert_eq!(history[0].channel, Some(Channel::L1));
+        assert_eq!(history[0].target, Some(50));
+    }
+
+    #[tokio::test]
+    async fn writes_to_locked_blinds_fail_only_their_characteristics() {
+        let controller = fake_four_blinds(10).await;
+        controller
+            .locks()
+            .insert(crate::locks::Lock {
+                channel: Channel::L1,
+                reason: None,
+                since: 0,
+                until: None,
+            })
+            .unwrap();
+        let app = SomfyHapApp::new(controller.clone());
+        let mut subscriptions = Subscriptions::default();
+        let locked = CharacteristicId::new(2, IID_TARGET_POSITION);
+
+        let outcome = app
+            .write_characteristics(
+                vec![CharacteristicWrite {
+                    id: locked,
+                    value: Some(json!(50)),
+                    ev: None,
+                }],
+                &mut subscriptions,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.statuses.len(), 1);
+        assert_eq!(outcome.statuses[0].id, locked);
+        assert_eq!(
+            outcome.statuses[0].status,
+            HapStatus::ServiceCommunicationFailure
+        );
+        assert!(controller.operations().is_empty());
+        assert_eq!(controller.position_for_aid(2).await.target, 100);
+        let history = controller.history().query(&HistoryFilter::default());
+        assert!(history[0]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("L1 is locked")));
     }
 
     #[tokio::test]
@@ -288,7 +901,7 @@ mod tests {
         let mut subscriptions = Subscriptions::default();
 
         let outcome = app
-            .write_characteristics(writes, &mut subscriptions)
+            .write_characteristics(writes, &mut subscriptions, None)
             .await
             .unwrap();
 
@@ -315,6 +928,7 @@ mod tests {
                 ev: None,
             }],
             &mut subscriptions,
+            None,
         )
         .await
         .unwrap();
@@ -343,6 +957,7 @@ mod tests {
                 ev: None,
             }],
             &mut subscriptions,
+            None,
         )
         .await
         .unwrap();
@@ -353,6 +968,7 @@ mod tests {
                 ev: None,
             }],
             &mut subscriptions,
+            None,
         )
         .await
         .unwrap();
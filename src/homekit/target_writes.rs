@@ -4,40 +4,64 @@ use crate::hap::runtime::{
     CharacteristicId, CharacteristicWrite, CharacteristicWriteStatus, HapStatus, Subscriptions,
 };
 use crate::homekit::characteristic::{
-    BlindCharacteristic, BridgeCharacteristic, HomeKitCharacteristic,
+    BlindCharacteristic, BridgeCharacteristic, HomeKitCharacteristic, SceneCharacteristic,
+    SensorCharacteristic,
 };
-use crate::positioning::state::Blind;
+use crate::positioning::inventory::BlindInventory;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct PendingTargetWrite {
     pub index: usize,
     pub id: CharacteristicId,
-    pub blind: &'static Blind,
+    /// Blind AIDs to move: the written blind, or every member of a group.
+    pub aids: Vec<u64>,
     pub target: u8,
 }
 
+/// `TargetHorizontalTiltAngle` write for one venetian blind.
+#[derive(Copy, Clone, Debug)]
+pub struct PendingTiltWrite {
+    pub index: usize,
+    pub id: CharacteristicId,
+    pub aid: u64,
+    pub angle: i8,
+}
+
+/// `On = true` write to a scene switch.
+#[derive(Clone, Debug)]
+pub struct PendingSceneWrite {
+    pub index: usize,
+    pub id: CharacteristicId,
+    pub name: String,
+}
+
 pub struct TargetWritePlan {
     pub statuses: Vec<Option<CharacteristicWriteStatus>>,
     pub targets: Vec<PendingTargetWrite>,
+    pub tilts: Vec<PendingTiltWrite>,
+    pub scenes: Vec<PendingSceneWrite>,
 }
 
 pub fn plan_target_writes(
+    blinds: &BlindInventory,
     writes: Vec<CharacteristicWrite>,
     subscriptions: &mut Subscriptions,
 ) -> TargetWritePlan {
     let mut statuses = Vec::new();
     let mut targets = Vec::new();
+    let mut tilts = Vec::new();
+    let mut scenes = Vec::new();
 
     for write in writes {
         let index = statuses.len();
         statuses.push(None);
 
         if let Some(ev) = write.ev {
-            statuses[index] = Some(handle_subscription(write.id, ev, subscriptions));
+            statuses[index] = Some(handle_subscription(blinds, write.id, ev, subscriptions));
             continue;
         }
 
-        let Some(characteristic) = HomeKitCharacteristic::resolve(write.id) else {
+        let Some(characteristic) = HomeKitCharacteristic::resolve(blinds, write.id) else {
             statuses[index] = Some(CharacteristicWriteStatus::error(
                 write.id,
                 HapStatus::ResourceDoesNotExist,
@@ -52,21 +76,86 @@ pub fn plan_target_writes(
                     characteristic: BlindCharacteristic::Identify,
                     ..
                 }
+                | HomeKitCharacteristic::Group {
+                    characteristic: BlindCharacteristic::Identify,
+                    ..
+                }
+                | HomeKitCharacteristic::Scene {
+                    characteristic: SceneCharacteristic::Identify,
+                    ..
+                }
+                | HomeKitCharacteristic::Safety {
+                    characteristic: SensorCharacteristic::Identify,
+                    ..
+                }
         ) {
             statuses[index] = Some(CharacteristicWriteStatus::success(write.id));
             continue;
         };
 
-        let HomeKitCharacteristic::Blind {
+        if let HomeKitCharacteristic::Scene {
+            scene,
+            characteristic: SceneCharacteristic::On,
+        } = characteristic
+        {
+            // Turning a stateless switch off is a no-op.
+            match write.value.as_ref().and_then(switch_value) {
+                Some(true) => scenes.push(PendingSceneWrite {
+                    index,
+                    id: write.id,
+                    name: scene.name.clone(),
+                }),
+                Some(false) => {
+                    statuses[index] = Some(CharacteristicWriteStatus::success(write.id));
+                }
+                None => {
+                    statuses[index] = Some(CharacteristicWriteStatus::error(
+                        write.id,
+                        HapStatus::InvalidValueInRequest,
+                    ));
+                }
+            }
+            continue;
+        }
+
+        if let HomeKitCharacteristic::Blind {
             blind,
-            characteristic: BlindCharacteristic::TargetPosition,
+            characteristic: BlindCharacteristic::TargetTilt,
         } = characteristic
-        else {
-            statuses[index] = Some(CharacteristicWriteStatus::error(
-                write.id,
-                HomeKitCharacteristic::write_error_status(write.id),
-            ));
+        {
+            match write.value.and_then(|v| v.as_i64()) {
+                Some(v) if (-90..=90).contains(&v) => tilts.push(PendingTiltWrite {
+                    index,
+                    id: write.id,
+                    aid: blind.aid,
+                    angle: v as i8,
+                }),
+                _ => {
+                    statuses[index] = Some(CharacteristicWriteStatus::error(
+                        write.id,
+                        HapStatus::InvalidValueInRequest,
+                    ));
+                }
+            }
             continue;
+        }
+
+        let aids = match characteristic {
+            HomeKitCharacteristic::Blind {
+                blind,
+                characteristic: BlindCharacteristic::TargetPosition,
+            } => vec![blind.aid],
+            HomeKitCharacteristic::Group {
+                group,
+                characteristic: BlindCharacteristic::TargetPosition,
+            } => group.member_aids.clone(),
+            _ => {
+                statuses[index] = Some(CharacteristicWriteStatus::error(
+                    write.id,
+                    HomeKitCharacteristic::write_error_status(blinds, write.id),
+                ));
+                continue;
+            }
         };
 
         let value = match write.value.and_then(|v| v.as_u64()) {
@@ -82,20 +171,39 @@ pub fn plan_target_writes(
         targets.push(PendingTargetWrite {
             index,
             id: write.id,
-            blind,
+            aids,
             target: value,
         });
     }
 
-    TargetWritePlan { statuses, targets }
+    TargetWritePlan {
+        statuses,
+        targets,
+        tilts,
+        scenes,
+    }
+}
+
+/// HAP bools arrive as `true`/`false` or `1`/`0`.
+fn switch_value(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(on) => Some(*on),
+        serde_json::Value::Number(n) => match n.as_u64() {
+            Some(0) => Some(false),
+            Some(1) => Some(true),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 fn handle_subscription(
+    blinds: &BlindInventory,
     id: CharacteristicId,
     enabled: bool,
     subscriptions: &mut Subscriptions,
 ) -> CharacteristicWriteStatus {
-    let Some(characteristic) = HomeKitCharacteristic::resolve(id) else {
+    let Some(characteristic) = HomeKitCharacteristic::resolve(blinds, id) else {
         return CharacteristicWriteStatus::error(id, HapStatus::ResourceDoesNotExist);
     };
     if !characteristic.supports_events() {
@@ -112,7 +220,9 @@ fn handle_subscription(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::homekit::accessory_db::{IID_CURRENT_POSITION, IID_TARGET_POSITION};
+    use crate::homekit::accessory_db::{
+        IID_CURRENT_POSITION, IID_TARGET_POSITION, IID_TARGET_TILT,
+    };
     use serde_json::json;
 
     #[test]
@@ -126,15 +236,15 @@ mod tests {
             })
             .collect::<Vec<_>>();
         let mut subscriptions = Subscriptions::default();
-        let plan = plan_target_writes(writes, &mut subscriptions);
+        let plan = plan_target_writes(&BlindInventory::default(), writes, &mut subscriptions);
 
         assert_eq!(plan.targets.len(), 4);
         assert_eq!(
             plan.targets
                 .iter()
-                .map(|target| target.blind.aid)
+                .map(|target| target.aids.clone())
                 .collect::<Vec<_>>(),
-            vec![2, 3, 4, 5]
+            vec![vec![2], vec![3], vec![4], vec![5]]
         );
         assert!(plan
             .targets
@@ -144,6 +254,36 @@ mod tests {
         assert!(subscriptions.is_empty());
     }
 
+    #[test]
+    fn tilt_writes_plan_only_for_venetian_blinds() {
+        let mut blinds = crate::config::default_blinds();
+        blinds[0].kind = crate::config::BlindKind::Venetian;
+        let blinds = BlindInventory::from_options(&blinds);
+        let writes = [(2, json!(-45)), (2, json!(120)), (3, json!(10))]
+            .into_iter()
+            .map(|(aid, value)| CharacteristicWrite {
+                id: CharacteristicId::new(aid, IID_TARGET_TILT),
+                value: Some(value),
+                ev: None,
+            })
+            .collect::<Vec<_>>();
+        let mut subscriptions = Subscriptions::default();
+
+        let plan = plan_target_writes(&blinds, writes, &mut subscriptions);
+
+        assert!(plan.targets.is_empty());
+        assert_eq!(plan.tilts.len(), 1);
+        assert_eq!((plan.tilts[0].aid, plan.tilts[0].angle), (2, -45));
+        assert_eq!(
+            plan.statuses[1].as_ref().unwrap().status,
+            HapStatus::InvalidValueInRequest
+        );
+        assert_eq!(
+            plan.statuses[2].as_ref().unwrap().status,
+            HapStatus::ResourceDoesNotExist
+        );
+    }
+
     #[test]
     fn subscription_toggle_does_not_plan_motion() {
         let id = CharacteristicId::new(2, IID_CURRENT_POSITION);
@@ -154,7 +294,7 @@ mod tests {
         }];
         let mut subscriptions = Subscriptions::default();
 
-        let plan = plan_target_writes(writes, &mut subscriptions);
+        let plan = plan_target_writes(&BlindInventory::default(), writes, &mut subscriptions);
 
         assert!(plan.targets.is_empty());
         assert!(subscriptions.contains(&id));
@@ -163,14 +303,17 @@ mod tests {
     #[test]
     fn unsupported_write_reports_protocol_status() {
         assert_eq!(
-            HomeKitCharacteristic::write_error_status(CharacteristicId::new(
-                2,
-                IID_CURRENT_POSITION
-            )),
+            HomeKitCharacteristic::write_error_status(
+                &BlindInventory::default(),
+                CharacteristicId::new(2, IID_CURRENT_POSITION)
+            ),
             HapStatus::ReadOnly
         );
         assert_eq!(
-            HomeKitCharacteristic::write_error_status(CharacteristicId::new(99, 99)),
+            HomeKitCharacteristic::write_error_status(
+                &BlindInventory::default(),
+                CharacteristicId::new(99, 99)
+            ),
             HapStatus::ResourceDoesNotExist
         );
     }
@@ -19,12 +19,18 @@ pub(crate) mod driver;
 pub(crate) mod embed;
 pub(crate) mod gpio;
 pub(crate) mod hap;
+pub(crate) mod history;
 pub(crate) mod homekit;
+pub(crate) mod locks;
 pub mod logging;
 pub(crate) mod persist;
 pub(crate) mod positioning;
+pub(crate) mod remotes;
 pub(crate) mod rts;
+pub(crate) mod safety;
+pub(crate) mod scheduler;
 pub(crate) mod server;
 pub(crate) mod service;
+pub(crate) mod solar;
 pub(crate) mod systemd;
 pub(crate) mod version;
@@ -0,0 +1,298 @@
+//! Maintenance locks (`locks.json`): channels nothing may move until unlocked.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use crate::core::Channel;
+use crate::persist::{self, atomic_save_bytes};
+use crate::positioning::inventory::BlindInventory;
+use crate::positioning::state::unix_now;
+
+const LOCKS_FILE: &str = "locks.json";
+
+/// A locked channel. `ALL` is the global lock.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lock {
+    pub channel: Channel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Unix seconds.
+    pub since: u64,
+    /// Unix seconds at which the lock lapses; `None` holds until unlocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+}
+
+impl Lock {
+    fn is_active(&self, now: u64) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+
+    /// Whether this lock covers any blind behind `channel`.
+    fn covers(&self, blinds: &BlindInventory, channel: Channel) -> bool {
+        if self.channel == Channel::All || self.channel == channel {
+            return true;
+        }
+        let locked = blinds.aids_for_channel(self.channel);
+        blinds
+            .aids_for_channel(channel)
+            .iter()
+            .any(|aid| locked.contains(aid))
+    }
+}
+
+/// A move refused because its channel is locked for maintenance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelLocked {
+    /// Channel the refused command addressed.
+    pub channel: Channel,
+    pub lock: Lock,
+}
+
+impl fmt::Display for ChannelLocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.lock.channel == self.channel {
+            write!(f, "{} is locked", self.channel)?;
+        } else {
+            write!(
+                f,
+                "{} is locked by the {} lock",
+                self.channel, self.lock.channel
+            )?;
+        }
+        if let Some(reason) = &self.lock.reason {
+            write!(f, " ({reason})")?;
+        }
+        match self
+            .lock
+            .until
+            .and_then(|until| jiff::Timestamp::from_second(until as i64).ok())
+        {
+            Some(until) => write!(f, " until {until}"),
+            None => write!(f, " until unlocked"),
+        }
+    }
+}
+
+impl std::error::Error for ChannelLocked {}
+
+/// Active maintenance locks, saved to disk on every change. Expired locks
+/// are ignored and dropped the next time the set is listed or changed.
+#[derive(Debug)]
+pub struct Locks {
+    path: Option<PathBuf>,
+    locks: StdMutex<Vec<Lock>>,
+}
+
+impl Locks {
+    pub fn open() -> Self {
+        Self::open_at(persist::state_dir().join(LOCKS_FILE))
+    }
+
+    fn open_at(path: PathBuf) -> Self {
+        let locks = load_locks(&path);
+        Self {
+            path: Some(path),
+            locks: StdMutex::new(locks),
+        }
+    }
+
+    /// Locks that are never written to disk.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            locks: StdMutex::new(Vec::new()),
+        }
+    }
+
+    /// Active locks, oldest first.
+    pub fn list(&self) -> Vec<Lock> {
+        let mut locks = self.lock_set();
+        if self.prune(&mut locks) {
+            if let Err(e) = self.save(&locks) {
+                tracing::warn!("failed to drop expired locks: {e:#}");
+            }
+        }
+        locks.clone()
+    }
+
+    /// Add `lock`, replacing any existing lock on the same channel.
+    pub fn insert(&self, lock: Lock) -> Result<()> {
+        let mut locks = self.lock_set();
+        self.prune(&mut locks);
+        let mut updated = locks.clone();
+        updated.retain(|existing| existing.channel != lock.channel);
+        updated.push(lock);
+        self.save(&updated)?;
+        *locks = updated;
+        Ok(())
+    }
+
+    /// Remove the lock on exactly `channel`, returning it if there was one.
+    pub fn remove(&self, channel: Channel) -> Result<Option<Lock>> {
+        let mut locks = self.lock_set();
+        self.prune(&mut locks);
+        let Some(index) = locks.iter().position(|lock| lock.channel == channel) else {
+            return Ok(None);
+        };
+        let mut updated = locks.clone();
+        let removed = updated.remove(index);
+        self.save(&updated)?;
+        *locks = updated;
+        Ok(Some(removed))
+    }
+
+    /// Fail with the first active lock covering any blind behind `channels`.
+    pub(crate) fn check(
+        &self,
+        blinds: &BlindInventory,
+        channels: impl IntoIterator<Item = Channel>,
+    ) -> Result<(), ChannelLocked> {
+        let locks = self.lock_set();
+        let now = unix_now();
+        for channel in channels {
+            if let Some(lock) = locks
+                .iter()
+                .find(|lock| lock.is_active(now) && lock.covers(blinds, channel))
+            {
+                return Err(ChannelLocked {
+                    channel,
+                    lock: lock.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn lock_set(&self) -> std::sync::MutexGuard<'_, Vec<Lock>> {
+        self.locks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Drop expired locks; `true` when any were removed.
+    fn prune(&self, locks: &mut Vec<Lock>) -> bool {
+        let now = unix_now();
+        let before = locks.len();
+        locks.retain(|lock| lock.is_active(now));
+        locks.len() != before
+    }
+
+    fn save(&self, locks: &[Lock]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("creating state directory {}", dir.display()))?;
+        }
+        let bytes = serde_json::to_vec_pretty(locks)?;
+        atomic_save_bytes(path, &bytes, true)
+            .with_context(|| format!("saving locks to {}", path.display()))
+    }
+}
+
+fn load_locks(path: &Path) -> Vec<Lock> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(locks) => locks,
+        Err(e) => {
+            // Failing open would let blinds move during maintenance; keep a
+            // global lock until someone looks at the file.
+            tracing::error!("unreadable {}: {e}; locking all channels", path.display());
+            vec![Lock {
+                channel: Channel::All,
+                reason: Some(format!("unreadable {LOCKS_FILE}")),
+                since: unix_now(),
+                until: None,
+            }]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GroupOptions;
+
+    fn lock(channel: Channel, until: Option<u64>) -> Lock {
+        Lock {
+            channel,
+            reason: Some("window cleaner".to_string()),
+            since: 1,
+            until,
+        }
+    }
+
+    #[test]
+    fn locks_cover_channels_groups_and_all() {
+        let blinds = BlindInventory::default().with_groups(&[GroupOptions {
+            name: "living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L1, Channel::L3],
+            aid: None,
+            paired: false,
+        }]);
+        let locks = Locks::in_memory();
+        locks.insert(lock(Channel::L1, None)).unwrap();
+
+        assert!(locks.check(&blinds, [Channel::L2]).is_ok());
+        let locked = locks.check(&blinds, [Channel::Group(1)]).unwrap_err();
+        assert_eq!(locked.lock.channel, Channel::L1);
+        assert_eq!(
+            locked.to_string(),
+            "G1 is locked by the L1 lock (window cleaner) until unlocked"
+        );
+        assert!(locks.check(&blinds, [Channel::All]).is_err());
+
+        locks.insert(lock(Channel::All, None)).unwrap();
+        assert!(locks.check(&blinds, [Channel::L2]).is_err());
+        assert_eq!(
+            locks.remove(Channel::All).unwrap().unwrap().channel,
+            Channel::All
+        );
+        assert!(locks.remove(Channel::All).unwrap().is_none());
+        assert!(locks.check(&blinds, [Channel::L2]).is_ok());
+    }
+
+    #[test]
+    fn expired_locks_lapse_and_are_dropped_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCKS_FILE);
+        let blinds = BlindInventory::default();
+        let locks = Locks::open_at(path.clone());
+        locks
+            .insert(lock(Channel::L1, Some(unix_now() - 1)))
+            .unwrap();
+        locks
+            .insert(lock(Channel::L2, Some(unix_now() + 3600)))
+            .unwrap();
+
+        assert!(locks.check(&blinds, [Channel::L1]).is_ok());
+        let reloaded = Locks::open_at(path.clone());
+        assert!(reloaded.check(&blinds, [Channel::L2]).is_err());
+        assert_eq!(reloaded.list().len(), 1);
+        let saved: Vec<Lock> = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].channel, Channel::L2);
+    }
+
+    #[test]
+    fn unreadable_lock_file_locks_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCKS_FILE);
+        fs::write(&path, b"{not json").unwrap();
+
+        let locks = Locks::open_at(path);
+        assert!(locks
+            .check(&BlindInventory::default(), [Channel::L4])
+            .is_err());
+    }
+}
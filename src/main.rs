@@ -33,8 +33,19 @@ async fn main() -> Result<()> {
         Command::Uninstall => commands::uninstall::run().await,
         Command::Restart => commands::restart::run(),
         Command::Remote { command } => commands::remote::run(command, &resolved).await,
+        Command::Rts { command } => commands::rts::run(command, &resolved).await,
         Command::Homekit { command } => commands::homekit::run(command, &resolved),
         Command::Logs(args) => commands::logs::run(args),
+        Command::History(args) => commands::history::run(args).await,
+        Command::Lock(args) => commands::lock::lock(args).await,
+        Command::Unlock { channel } => commands::lock::unlock(channel).await,
+        Command::Locks { json } => commands::lock::list(json).await,
+        Command::Schedule { command } => commands::schedule::run(command, &resolved),
+        Command::Calibrate {
+            channel,
+            points,
+            no_restart,
+        } => commands::calibrate::run(channel, points, no_restart, &resolved).await,
         Command::Config { command } => match command {
             ConfigCommand::Path => {
                 commands::config::path(&resolved);
@@ -0,0 +1,172 @@
+//! Per-blind motor run-time budget that keeps clients from tripping the
+//! motor's thermal cut-out.
+//!
+//! Each blind has a leaky bucket: planned run time is added when a move
+//! starts, and drains at `run_budget / run_window`. A move that would
+//! overflow the budget is refused until enough run time has drained.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
+
+use crate::config::PositioningOptions;
+use crate::core::Channel;
+use crate::positioning::inventory::Blind;
+
+/// A move refused because the blind's motor needs to cool down.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MotorResting {
+    pub name: String,
+    pub channel: Channel,
+    /// Time until the refused move fits in the budget again.
+    pub available_in: Duration,
+}
+
+impl MotorResting {
+    /// Whole seconds until the motor is available, rounded up.
+    pub fn retry_after_secs(&self) -> u64 {
+        self.available_in.as_secs() + u64::from(self.available_in.subsec_nanos() > 0)
+    }
+}
+
+impl fmt::Display for MotorResting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) has used its motor run-time budget; available again in {}s",
+            self.name,
+            self.channel,
+            self.retry_after_secs()
+        )
+    }
+}
+
+impl std::error::Error for MotorResting {}
+
+#[derive(Debug)]
+pub(crate) struct DutyCycle {
+    budget: Duration,
+    window: Duration,
+    /// Run time still counted against each aid, as of the paired instant.
+    used: StdMutex<HashMap<u64, (Duration, Instant)>>,
+}
+
+impl DutyCycle {
+    pub(crate) fn new(options: &PositioningOptions) -> Self {
+        Self {
+            budget: Duration::from_secs(options.run_budget_secs),
+            window: Duration::from_secs(options.run_window_secs),
+            used: StdMutex::new(HashMap::new()),
+        }
+    }
+
+    /// Charge every run against its blind's budget, or charge nothing and
+    /// return the first blind that has to rest.
+    pub(crate) fn reserve<'a>(
+        &self,
+        runs: impl IntoIterator<Item = (&'a Blind, Duration)>,
+        now: Instant,
+    ) -> Result<(), MotorResting> {
+        if self.budget.is_zero() {
+            return Ok(());
+        }
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let runs: Vec<(&Blind, Duration)> = runs.into_iter().collect();
+        let mut charged: HashMap<u64, Duration> = HashMap::new();
+        for (blind, run) in &runs {
+            // A single move longer than the budget is still allowed from cold.
+            let run = (*run).min(self.budget);
+            let level = charged
+                .get(&blind.aid)
+                .copied()
+                .unwrap_or_else(|| self.level(used.get(&blind.aid), now));
+            let after = level + run;
+            if after > self.budget {
+                return Err(MotorResting {
+                    name: blind.name.clone(),
+                    channel: blind.channel,
+                    available_in: self.drain_time(after - self.budget),
+                });
+            }
+            charged.insert(blind.aid, after);
+        }
+        for (aid, level) in charged {
+            used.insert(aid, (level, now));
+        }
+        Ok(())
+    }
+
+    /// Remaining charge of `entry` after draining until `now`.
+    fn level(&self, entry: Option<&(Duration, Instant)>, now: Instant) -> Duration {
+        let Some((level, at)) = entry else {
+            return Duration::ZERO;
+        };
+        let drained = now
+            .saturating_duration_since(*at)
+            .mul_f64(self.budget.as_secs_f64() / self.window.as_secs_f64());
+        level.saturating_sub(drained)
+    }
+
+    /// Time it takes to drain `excess` run time.
+    fn drain_time(&self, excess: Duration) -> Duration {
+        excess.mul_f64(self.window.as_secs_f64() / self.budget.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positioning::inventory::BlindInventory;
+
+    fn duty_cycle(budget_secs: u64, window_secs: u64) -> DutyCycle {
+        let mut options = PositioningOptions::default();
+        options.run_budget_secs = budget_secs;
+        options.run_window_secs = window_secs;
+        DutyCycle::new(&options)
+    }
+
+    #[test]
+    fn refuses_runs_over_budget_until_they_drain() {
+        let blinds = BlindInventory::default();
+        let blind = blinds.find(3).unwrap();
+        let duty = duty_cycle(60, 600);
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+
+        duty.reserve([(blind, secs(40))], start).unwrap();
+        let resting = duty.reserve([(blind, secs(30))], start).unwrap_err();
+        assert_eq!(resting.channel, Channel::L2);
+        // 10 s over budget drains at 60 s per 600 s.
+        assert_eq!(resting.retry_after_secs(), 100);
+        assert!(resting.to_string().contains("available again in 100s"));
+
+        duty.reserve([(blind, secs(30))], start + secs(100))
+            .unwrap();
+        // Other blinds have their own budget.
+        duty.reserve([(blinds.find(2).unwrap(), secs(60))], start)
+            .unwrap();
+    }
+
+    #[test]
+    fn refused_batches_charge_nothing() {
+        let blinds = BlindInventory::default();
+        let (first, second) = (blinds.find(2).unwrap(), blinds.find(3).unwrap());
+        let duty = duty_cycle(60, 600);
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+
+        duty.reserve([(second, secs(50))], start).unwrap();
+        let resting = duty
+            .reserve([(first, secs(20)), (second, secs(20))], start)
+            .unwrap_err();
+        assert_eq!(resting.channel, Channel::L2);
+        duty.reserve([(first, secs(60))], start).unwrap();
+
+        // A zero budget turns protection off.
+        let off = duty_cycle(0, 600);
+        for _ in 0..10 {
+            off.reserve([(first, secs(60))], start).unwrap();
+        }
+    }
+}
@@ -0,0 +1,405 @@
+//! Configured blinds: names, driving channels, and stable HomeKit AIDs.
+
+use crate::config::{
+    default_blinds, AppConfig, BlindKind, BlindOptions, GroupOptions, SafetyInputOptions,
+    SceneOptions,
+};
+use crate::core::Channel;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blind {
+    pub aid: u64,
+    pub name: String,
+    pub channel: Channel,
+    pub serial: String,
+    pub kind: BlindKind,
+}
+
+impl Blind {
+    pub fn is_venetian(&self) -> bool {
+        self.kind == BlindKind::Venetian
+    }
+}
+
+impl From<&BlindOptions> for Blind {
+    fn from(value: &BlindOptions) -> Self {
+        Self {
+            aid: value.aid,
+            name: value.name.clone(),
+            channel: value.channel,
+            serial: format!("somfy-{}", value.channel),
+            kind: value.kind,
+        }
+    }
+}
+
+/// A `[[groups]]` entry addressed by its group channel (`G1`–`G16`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindGroup {
+    pub name: String,
+    pub channel: Channel,
+    pub members: Vec<Channel>,
+    /// Member blind AIDs, in `members` order.
+    pub member_aids: Vec<u64>,
+    pub aid: Option<u64>,
+    pub serial: String,
+    /// The group's own RTS virtual remote is paired with every member motor.
+    pub paired: bool,
+}
+
+/// A `[[scenes]]` preset with its positions resolved to blind AIDs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindScene {
+    pub name: String,
+    /// `(aid, target)` pairs for one `set_target_positions` call.
+    pub targets: Vec<(u64, u8)>,
+    pub aid: Option<u64>,
+    pub serial: String,
+}
+
+/// A `[[safety.inputs]]` entry with its channels resolved to blind AIDs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafetySensor {
+    pub name: String,
+    /// Configured channels; `[ALL]` when the entry lists none.
+    pub channels: Vec<Channel>,
+    /// Blinds the input retracts and holds up.
+    pub aids: Vec<u64>,
+    pub aid: Option<u64>,
+    pub serial: String,
+}
+
+/// Blinds declared by `[[blinds]]`, in config order, plus `[[groups]]`,
+/// `[[scenes]]`, and `[[safety.inputs]]`. Validation in `config::validate`
+/// guarantees unique AIDs, unique channels, and that group members and scene
+/// positions name configured blinds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindInventory {
+    blinds: Vec<Blind>,
+    groups: Vec<BlindGroup>,
+    scenes: Vec<BlindScene>,
+    safety: Vec<SafetySensor>,
+}
+
+impl Default for BlindInventory {
+    fn default() -> Self {
+        Self::from_options(&default_blinds())
+    }
+}
+
+impl BlindInventory {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::from_options(&config.blinds)
+            .with_groups(&config.groups)
+            .with_scenes(&config.scenes)
+            .with_safety(&config.safety.inputs)
+    }
+
+    pub fn from_options(blinds: &[BlindOptions]) -> Self {
+        Self {
+            blinds: blinds.iter().map(Blind::from).collect(),
+            groups: Vec::new(),
+            scenes: Vec::new(),
+            safety: Vec::new(),
+        }
+    }
+
+    pub fn with_groups(mut self, groups: &[GroupOptions]) -> Self {
+        self.groups = groups
+            .iter()
+            .map(|group| BlindGroup {
+                name: group.name.clone(),
+                channel: group.channel,
+                members: group.blinds.clone(),
+                member_aids: group
+                    .blinds
+                    .iter()
+                    .filter_map(|member| self.for_channel(*member))
+                    .map(|blind| blind.aid)
+                    .collect(),
+                aid: group.aid,
+                serial: format!("somfy-{}", group.channel),
+                paired: group.paired,
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_scenes(mut self, scenes: &[SceneOptions]) -> Self {
+        self.scenes = scenes
+            .iter()
+            .map(|scene| BlindScene {
+                name: scene.name.clone(),
+                targets: scene
+                    .positions
+                    .iter()
+                    .filter_map(|(channel, position)| {
+                        self.for_channel(*channel)
+                            .map(|blind| (blind.aid, *position))
+                    })
+                    .collect(),
+                aid: scene.aid,
+                serial: format!("somfy-scene-{}", scene.name.replace(' ', "-")),
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_safety(mut self, inputs: &[SafetyInputOptions]) -> Self {
+        self.safety = inputs
+            .iter()
+            .map(|input| {
+                let channels = if input.channels.is_empty() {
+                    vec![Channel::All]
+                } else {
+                    input.channels.clone()
+                };
+                let mut aids: Vec<u64> = channels
+                    .iter()
+                    .flat_map(|channel| self.aids_for_channel(*channel))
+                    .collect();
+                aids.sort_unstable();
+                aids.dedup();
+                SafetySensor {
+                    name: input.name.clone(),
+                    channels,
+                    aids,
+                    aid: input.aid,
+                    serial: format!("somfy-safety-{}", input.name.replace(' ', "-")),
+                }
+            })
+            .collect();
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Blind> {
+        self.blinds.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blinds.len()
+    }
+
+    pub fn find(&self, aid: u64) -> Option<&Blind> {
+        self.blinds.iter().find(|b| b.aid == aid)
+    }
+
+    pub fn for_channel(&self, channel: Channel) -> Option<&Blind> {
+        self.blinds.iter().find(|b| b.channel == channel)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &BlindGroup> {
+        self.groups.iter()
+    }
+
+    pub fn group(&self, channel: Channel) -> Option<&BlindGroup> {
+        self.groups.iter().find(|group| group.channel == channel)
+    }
+
+    pub fn group_named(&self, name: &str) -> Option<&BlindGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    /// Groups with at least one member among `aids`.
+    pub fn groups_containing<'a>(
+        &'a self,
+        aids: &'a [u64],
+    ) -> impl Iterator<Item = &'a BlindGroup> + 'a {
+        self.groups
+            .iter()
+            .filter(|group| group.member_aids.iter().any(|aid| aids.contains(aid)))
+    }
+
+    pub fn find_group(&self, aid: u64) -> Option<&BlindGroup> {
+        self.groups.iter().find(|group| group.aid == Some(aid))
+    }
+
+    pub fn scenes(&self) -> impl Iterator<Item = &BlindScene> {
+        self.scenes.iter()
+    }
+
+    pub fn scene_named(&self, name: &str) -> Option<&BlindScene> {
+        self.scenes.iter().find(|scene| scene.name == name)
+    }
+
+    pub fn find_scene(&self, aid: u64) -> Option<&BlindScene> {
+        self.scenes.iter().find(|scene| scene.aid == Some(aid))
+    }
+
+    pub fn safety_sensors(&self) -> impl Iterator<Item = &SafetySensor> {
+        self.safety.iter()
+    }
+
+    pub fn safety_sensor_named(&self, name: &str) -> Option<&SafetySensor> {
+        self.safety.iter().find(|sensor| sensor.name == name)
+    }
+
+    pub fn find_safety_sensor(&self, aid: u64) -> Option<&SafetySensor> {
+        self.safety.iter().find(|sensor| sensor.aid == Some(aid))
+    }
+
+    /// Individual channels in config order (the RTS/fake selection cycle).
+    pub fn channels(&self) -> Vec<Channel> {
+        self.blinds.iter().map(|blind| blind.channel).collect()
+    }
+
+    /// Whether `channel` addresses at least one configured blind.
+    pub fn contains_channel(&self, channel: Channel) -> bool {
+        match channel {
+            Channel::All => true,
+            Channel::Group(_) => self.group(channel).is_some(),
+            Channel::Individual(_) => self.for_channel(channel).is_some(),
+        }
+    }
+
+    pub fn aids_for_channel(&self, channel: Channel) -> Vec<u64> {
+        match channel {
+            Channel::All => self.blinds.iter().map(|blind| blind.aid).collect(),
+            Channel::Group(_) => self
+                .group(channel)
+                .map(|group| group.member_aids.clone())
+                .unwrap_or_default(),
+            Channel::Individual(_) => self
+                .for_channel(channel)
+                .map(|blind| vec![blind.aid])
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn target_positions(&self, channel: Channel, position: u8) -> Vec<(u64, u8)> {
+        self.aids_for_channel(channel)
+            .into_iter()
+            .map(|aid| (aid, position))
+            .collect()
+    }
+
+    /// Single channel that reaches exactly `aids`: `ALL` when every blind is
+    /// included, otherwise a group whose virtual remote has been paired with
+    /// exactly those blinds.
+    pub fn shared_channel(&self, aids: &[u64]) -> Option<Channel> {
+        let covers = |members: Vec<u64>| {
+            members.len() == aids.len() && members.iter().all(|aid| aids.contains(aid))
+        };
+        if covers(self.aids_for_channel(Channel::All)) {
+            return Some(Channel::All);
+        }
+        self.groups
+            .iter()
+            .filter(|group| group.paired)
+            .find(|group| covers(self.aids_for_channel(group.channel)))
+            .map(|group| group.channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_inventory_matches_legacy_four_blinds() {
+        let blinds = BlindInventory::default();
+
+        assert_eq!(
+            blinds.iter().map(|blind| blind.aid).collect::<Vec<_>>(),
+            vec![2, 3, 4, 5]
+        );
+        assert_eq!(blinds.find(3).unwrap().name, "Blind 2");
+        assert_eq!(blinds.find(3).unwrap().serial, "somfy-L2");
+    }
+
+    #[test]
+    fn aids_for_channel_maps_channel_and_all() {
+        let blinds = BlindInventory::default();
+
+        assert_eq!(blinds.aids_for_channel(Channel::L2), vec![3]);
+        assert_eq!(blinds.aids_for_channel(Channel::All), vec![2, 3, 4, 5]);
+        assert!(blinds.aids_for_channel(Channel::Individual(9)).is_empty());
+    }
+
+    #[test]
+    fn target_positions_pairs_aids_with_position() {
+        let blinds = BlindInventory::default();
+
+        assert_eq!(blinds.target_positions(Channel::L2, 25), vec![(3, 25)]);
+        assert_eq!(
+            blinds.target_positions(Channel::All, 10),
+            vec![(2, 10), (3, 10), (4, 10), (5, 10)]
+        );
+    }
+
+    fn living_room(paired: bool) -> GroupOptions {
+        GroupOptions {
+            name: "living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L3, Channel::L1],
+            aid: Some(20),
+            paired,
+        }
+    }
+
+    #[test]
+    fn group_channel_expands_to_member_blinds() {
+        let blinds = BlindInventory::default().with_groups(&[living_room(false)]);
+
+        assert_eq!(blinds.aids_for_channel(Channel::Group(1)), vec![4, 2]);
+        assert!(blinds.aids_for_channel(Channel::Group(2)).is_empty());
+        assert!(blinds.contains_channel(Channel::Group(1)));
+        assert!(!blinds.contains_channel(Channel::Group(2)));
+        assert_eq!(
+            blinds.group_named("living room").unwrap().channel,
+            Channel::Group(1)
+        );
+        assert_eq!(blinds.find_group(20).unwrap().name, "living room");
+    }
+
+    #[test]
+    fn scenes_resolve_channels_to_blind_aids() {
+        let blinds = BlindInventory::default().with_scenes(&[SceneOptions {
+            name: "movie".to_string(),
+            positions: [(Channel::L4, 100), (Channel::L1, 0), (Channel::L2, 30)].into(),
+            aid: Some(30),
+        }]);
+
+        let scene = blinds.scene_named("movie").unwrap();
+        assert_eq!(scene.targets, vec![(2, 0), (3, 30), (5, 100)]);
+        assert_eq!(blinds.find_scene(30).unwrap().name, "movie");
+        assert!(blinds.scene_named("morning").is_none());
+    }
+
+    #[test]
+    fn shared_channel_prefers_all_then_paired_groups() {
+        let unpaired = BlindInventory::default().with_groups(&[living_room(false)]);
+        let paired = BlindInventory::default().with_groups(&[living_room(true)]);
+
+        assert_eq!(paired.shared_channel(&[5, 4, 3, 2]), Some(Channel::All));
+        assert_eq!(paired.shared_channel(&[2, 4]), Some(Channel::Group(1)));
+        assert_eq!(unpaired.shared_channel(&[2, 4]), None);
+        assert_eq!(paired.shared_channel(&[2]), None);
+        assert_eq!(paired.shared_channel(&[2, 3, 4]), None);
+    }
+
+    #[test]
+    fn configured_inventory_uses_config_aids_and_channels() {
+        let blinds = BlindInventory::from_options(&[
+            BlindOptions {
+                name: "Kitchen".to_string(),
+                channel: Channel::Individual(7),
+                aid: 20,
+                kind: BlindKind::Venetian,
+            },
+            BlindOptions {
+                name: "Office".to_string(),
+                channel: Channel::L1,
+                aid: 9,
+                kind: BlindKind::Roller,
+            },
+        ]);
+
+        assert_eq!(blinds.for_channel(Channel::Individual(7)).unwrap().aid, 20);
+        assert!(blinds.find(20).unwrap().is_venetian());
+        assert!(!blinds.find(9).unwrap().is_venetian());
+        assert_eq!(blinds.channels(), vec![Channel::Individual(7), Channel::L1]);
+        assert!(blinds.contains_channel(Channel::All));
+        assert!(!blinds.contains_channel(Channel::L2));
+    }
+}
@@ -1,5 +1,7 @@
 //! Shared blind position estimation and timed movement planning.
 
+pub(crate) mod duty_cycle;
+pub(crate) mod inventory;
 pub(crate) mod motion;
 pub(crate) mod motion_tasks;
 pub(crate) mod state;
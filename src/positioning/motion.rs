@@ -1,60 +1,210 @@
 //! Percentage-position motion planning.
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use crate::config::{BlindTimingOptions, PositioningOptions};
 use crate::core::{Channel, Command};
-use crate::positioning::state::{Blind, BLINDS, STATUS_DECREASING, STATUS_INCREASING};
+use crate::positioning::inventory::{Blind, BlindInventory};
+use crate::positioning::state::{Provenance, STATUS_DECREASING, STATUS_INCREASING};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BlindMotionTiming {
     pub open: Duration,
     pub close: Duration,
     pub slack: Duration,
+    /// Venetian slat rotation from -90° to 90°.
+    pub tilt: Duration,
+    /// Measured opening curve; `None` keeps proportional travel.
+    pub open_curve: Option<TravelCurve>,
+    /// Measured closing curve; `None` keeps proportional travel.
+    pub close_curve: Option<TravelCurve>,
 }
 
+const DEFAULT_TILT_MS: u64 = 1_500;
+
 impl From<&BlindTimingOptions> for BlindMotionTiming {
     fn from(value: &BlindTimingOptions) -> Self {
         Self {
             open: Duration::from_millis(value.open_ms),
             close: Duration::from_millis(value.close_ms),
             slack: Duration::from_millis(value.slack_ms),
+            tilt: Duration::from_millis(value.tilt_ms.unwrap_or(DEFAULT_TILT_MS)),
+            open_curve: TravelCurve::opening(&value.open_curve, value.open_ms),
+            close_curve: TravelCurve::closing(&value.close_curve, value.close_ms),
         }
     }
 }
 
+/// Elapsed travel time at measured positions for one direction, counted from
+/// the end stop the move starts at. Both end stops are included, so full
+/// travel (and any closed-end slack) is part of the curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TravelCurve {
+    points: Vec<(u8, u64)>,
+}
+
+impl TravelCurve {
+    /// Curve from fully closed (`0`) to fully open after `open_ms`.
+    fn opening(points: &[(u8, u64)], open_ms: u64) -> Option<Self> {
+        Self::between((0, 0), points, (100, open_ms))
+    }
+
+    /// Curve from fully open (`100`) to fully closed after `close_ms`.
+    fn closing(points: &[(u8, u64)], close_ms: u64) -> Option<Self> {
+        Self::between((100, 0), points, (0, close_ms))
+    }
+
+    fn between(start: (u8, u64), points: &[(u8, u64)], end: (u8, u64)) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let mut all = Vec::with_capacity(points.len() + 2);
+        all.push(start);
+        all.extend_from_slice(points);
+        all.push(end);
+        Some(Self { points: all })
+    }
+
+    /// Milliseconds from the starting end stop to `position`, interpolated
+    /// linearly between the neighbouring points.
+    pub fn elapsed_ms(&self, position: u8) -> u64 {
+        let position = position.min(100);
+        for pair in self.points.windows(2) {
+            let ((from, from_ms), (to, to_ms)) = (pair[0], pair[1]);
+            if !(from.min(to)..=from.max(to)).contains(&position) {
+                continue;
+            }
+            let span = u64::from(from.abs_diff(to)).max(1);
+            let offset = u64::from(from.abs_diff(position));
+            return from_ms + (to_ms.saturating_sub(from_ms) * offset).div_ceil(span);
+        }
+        self.points.last().map_or(0, |(_, ms)| *ms)
+    }
+
+    /// Position reached `ms` after leaving the starting end stop, rounded
+    /// towards the start.
+    pub fn position_at(&self, ms: u64) -> u8 {
+        for pair in self.points.windows(2) {
+            let ((from, from_ms), (to, to_ms)) = (pair[0], pair[1]);
+            if ms > to_ms {
+                continue;
+            }
+            let span = to_ms.saturating_sub(from_ms).max(1);
+            let offset = ms.saturating_sub(from_ms).min(span);
+            let moved = u64::from(from.abs_diff(to)) * offset / span;
+            let moved = u8::try_from(moved).unwrap_or(u8::MAX);
+            return if to > from {
+                from.saturating_add(moved)
+            } else {
+                from.saturating_sub(moved)
+            };
+        }
+        self.points.last().map_or(0, |(position, _)| *position)
+    }
+}
+
+/// When an interior move should first pass through an end stop to
+/// re-anchor a drifting position estimate.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RehomePolicy {
+    pub after_moves: Option<u32>,
+    pub after: Option<Duration>,
+}
+
+impl RehomePolicy {
+    /// Whether `provenance` calls for re-homing at `now` (Unix seconds). A blind
+    /// with no recorded end-stop time is due under a time-based policy.
+    pub fn is_due(&self, provenance: Provenance, now: u64) -> bool {
+        let moves_due = self
+            .after_moves
+            .is_some_and(|limit| provenance.interior_moves >= limit);
+        let time_due = self.after.is_some_and(|after| match provenance.homed_at {
+            Some(homed_at) => now.saturating_sub(homed_at) >= after.as_secs(),
+            None => true,
+        });
+        moves_due || time_due
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MotionTimings {
-    individual: [BlindMotionTiming; 4],
+    individual: BTreeMap<Channel, BlindMotionTiming>,
+    my_positions: BTreeMap<Channel, u8>,
+    rehome: BTreeMap<Channel, RehomePolicy>,
+    update_interval: Option<Duration>,
 }
 
 impl From<PositioningOptions> for MotionTimings {
     fn from(value: PositioningOptions) -> Self {
         Self {
-            individual: value.individual_timings().map(BlindMotionTiming::from),
+            individual: value
+                .configured()
+                .map(|(channel, timing)| (channel, BlindMotionTiming::from(timing)))
+                .collect(),
+            my_positions: value
+                .configured()
+                .filter_map(|(channel, timing)| {
+                    timing.my_position.map(|position| (channel, position))
+                })
+                .collect(),
+            rehome: value
+                .configured()
+                .map(|(channel, timing)| {
+                    let policy = RehomePolicy {
+                        after_moves: timing.rehome_after_moves,
+                        after: timing
+                            .rehome_after_hours
+                            .map(|hours| Duration::from_secs(u64::from(hours) * 3_600)),
+                    };
+                    (channel, policy)
+                })
+                .filter(|(_, policy)| *policy != RehomePolicy::default())
+                .collect(),
+            update_interval: (value.update_interval_ms > 0)
+                .then(|| Duration::from_millis(value.update_interval_ms)),
         }
     }
 }
 
 impl MotionTimings {
     pub fn for_channel(&self, channel: Channel) -> BlindMotionTiming {
-        match channel {
-            Channel::L1 => self.individual[0],
-            Channel::L2 => self.individual[1],
-            Channel::L3 => self.individual[2],
-            Channel::L4 => self.individual[3],
-            // ALL has no single blind; use L1 timing (same as the pre-array default).
-            Channel::All => self.individual[0],
-        }
+        // ALL has no single blind; use L1 timing (same as the pre-inventory default).
+        let channel = match channel {
+            Channel::All => Channel::L1,
+            channel => channel,
+        };
+        self.individual
+            .get(&channel)
+            .cloned()
+            .unwrap_or_else(|| BlindMotionTiming::from(&BlindTimingOptions::default()))
+    }
+
+    /// How often moving blinds publish interpolated positions, if at all.
+    pub fn update_interval(&self) -> Option<Duration> {
+        self.update_interval
+    }
+
+    /// Configured `my_position` for an individual channel.
+    pub fn my_position(&self, channel: Channel) -> Option<u8> {
+        self.my_positions.get(&channel).copied()
+    }
+
+    /// Re-homing policy for an individual channel; the default never re-homes.
+    pub fn rehome_policy(&self, channel: Channel) -> RehomePolicy {
+        self.rehome.get(&channel).copied().unwrap_or_default()
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MotionRequest {
-    pub blind: &'static Blind,
+    pub blind: Blind,
     pub current: u8,
     pub target: u8,
     pub timing: BlindMotionTiming,
+    /// Pass through the nearer end stop first. Ignored for moves that
+    /// already start or finish at one.
+    pub rehome: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -63,15 +213,50 @@ pub struct DriverStart {
     pub command: Command,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BlindMovement {
-    pub blind: &'static Blind,
+    pub blind: Blind,
     pub current: u8,
     pub target: u8,
     pub command: Command,
     pub status: u8,
     pub duration: Duration,
     pub stop_at_end: bool,
+    /// Measured curve for this direction, used to interpolate progress.
+    pub curve: Option<TravelCurve>,
+    /// Closed-end slack spent before the blind visibly moves.
+    pub lead: Duration,
+    /// Requested interior target when this leg re-homes at the `target` end
+    /// stop first; the controller moves on to it once the leg settles.
+    pub then: Option<u8>,
+}
+
+impl BlindMovement {
+    /// Estimated position `elapsed` into the move. Rounds towards `current`,
+    /// so progress never runs ahead of the blind, and stops at `target`.
+    pub fn position_at(&self, elapsed: Duration) -> u8 {
+        if elapsed >= self.duration {
+            return self.target;
+        }
+        let delta = self.current.abs_diff(self.target);
+        let moving_ms = elapsed.saturating_sub(self.lead).as_millis();
+        let travelled = match &self.curve {
+            Some(curve) => {
+                let ms = curve.elapsed_ms(self.current) + moving_ms as u64;
+                curve.position_at(ms).abs_diff(self.current)
+            }
+            None => {
+                let visible_ms = self.duration.saturating_sub(self.lead).as_millis().max(1);
+                u8::try_from(u128::from(delta) * moving_ms / visible_ms).unwrap_or(delta)
+            }
+        }
+        .min(delta);
+        if self.target > self.current {
+            self.current + travelled
+        } else {
+            self.current - travelled
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -87,14 +272,14 @@ pub enum MotionPlan {
     CancelAndSnap { requests: Vec<MotionRequest> },
 }
 
-pub fn plan_motion(requests: &[MotionRequest]) -> MotionPlan {
+pub fn plan_motion(requests: &[MotionRequest], blinds: &BlindInventory) -> MotionPlan {
     if requests.is_empty() {
         return MotionPlan::NoOp;
     }
 
     let movements = requests
         .iter()
-        .filter_map(|request| movement_for(*request))
+        .filter_map(plan_movement)
         .collect::<Vec<_>>();
 
     if movements.is_empty() {
@@ -103,9 +288,9 @@ pub fn plan_motion(requests: &[MotionRequest]) -> MotionPlan {
         };
     }
 
-    let starts = if can_group_start(&movements) {
+    let starts = if let Some(channel) = group_start_channel(&movements, blinds) {
         vec![DriverStart {
-            channel: Channel::All,
+            channel,
             command: movements[0].command,
         }]
     } else {
@@ -121,51 +306,159 @@ pub fn plan_motion(requests: &[MotionRequest]) -> MotionPlan {
     MotionPlan::Travel { starts, movements }
 }
 
-fn movement_for(request: MotionRequest) -> Option<BlindMovement> {
+/// Timed travel for one blind, or `None` when it is already at the target.
+pub fn plan_movement(request: &MotionRequest) -> Option<BlindMovement> {
     let current = request.current.min(100);
-    let target = request.target.min(100);
-    if current == target {
+    let requested = request.target.min(100);
+    if current == requested {
         return None;
     }
+    let interior = |position: u8| !matches!(position, 0 | 100);
+    let then = (request.rehome && interior(current) && interior(requested)).then_some(requested);
+    let target = match then {
+        Some(_) => rehome_end_stop(current, requested),
+        None => requested,
+    };
 
     let (command, status, full_travel) = if target > current {
         (Command::Up, STATUS_INCREASING, request.timing.open)
     } else {
         (Command::Down, STATUS_DECREASING, request.timing.close)
     };
+    let curve = if command == Command::Up {
+        &request.timing.open_curve
+    } else {
+        &request.timing.close_curve
+    };
+    let mut millis = match curve {
+        // Measured curves already fold the closed-end slack into their points.
+        Some(curve) => u128::from(
+            curve
+                .elapsed_ms(target)
+                .saturating_sub(curve.elapsed_ms(current)),
+        ),
+        None => proportional_ms(current, target, full_travel, request.timing.slack),
+    };
+    if then.is_some() && curve.is_none() && target == 0 {
+        // Wait for the slats to close before reversing off the end stop.
+        millis += request.timing.slack.as_millis();
+    }
+
+    let lead = if curve.is_none() && command == Command::Up && current == 0 {
+        request.timing.slack
+    } else {
+        Duration::ZERO
+    };
+
+    Some(BlindMovement {
+        blind: request.blind.clone(),
+        current,
+        target,
+        command,
+        status,
+        duration: Duration::from_millis(millis.max(1) as u64),
+        stop_at_end: !matches!(target, 0 | 100),
+        curve: curve.clone(),
+        lead,
+        then,
+    })
+}
+
+/// End stop that keeps the detour from `current` to `target` shortest.
+fn rehome_end_stop(current: u8, target: u8) -> u8 {
+    if u16::from(current) + u16::from(target) < 100 {
+        0
+    } else {
+        100
+    }
+}
+
+/// Linear travel time between two positions, treating `slack` as closed-end
+/// travel that only counts when opening from fully closed.
+fn proportional_ms(current: u8, target: u8, full_travel: Duration, slack: Duration) -> u128 {
     let delta = current.abs_diff(target) as u128;
-    let slack_ms = request.timing.slack.as_millis();
+    let slack_ms = slack.as_millis();
     let visible_full_ms = full_travel.as_millis().saturating_sub(slack_ms);
     let mut millis = (visible_full_ms * delta).div_ceil(100);
-    let opens_from_fully_closed = command == Command::Up && current == 0;
+    let opens_from_fully_closed = target > current && current == 0;
     if opens_from_fully_closed {
         millis += slack_ms;
     }
+    millis
+}
 
-    Some(BlindMovement {
-        blind: request.blind,
+/// Slat rotation on a venetian blind: a short pulse in the tilt direction,
+/// stopped after the proportional share of the full -90°..90° tilt time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TiltMovement {
+    pub blind: Blind,
+    pub current: i8,
+    pub target: i8,
+    pub command: Command,
+    pub duration: Duration,
+}
+
+/// Plan a tilt from `current` to `target` degrees. Unlike travel, the pulse is
+/// always stopped explicitly: the motor would otherwise carry on moving the
+/// blind once the slats reach their end stop.
+pub fn plan_tilt(
+    blind: &Blind,
+    current: i8,
+    target: i8,
+    timing: &BlindMotionTiming,
+) -> Option<TiltMovement> {
+    if current == target {
+        return None;
+    }
+    let command = if target > current {
+        Command::Up
+    } else {
+        Command::Down
+    };
+    let delta = u128::from(current.abs_diff(target));
+    let millis = (timing.tilt.as_millis() * delta).div_ceil(180);
+    Some(TiltMovement {
+        blind: blind.clone(),
         current,
         target,
         command,
-        status,
         duration: Duration::from_millis(millis.max(1) as u64),
-        stop_at_end: !matches!(target, 0 | 100),
     })
 }
 
-fn can_group_start(movements: &[BlindMovement]) -> bool {
-    let Some(first) = movements.first() else {
-        return false;
-    };
-    movements.iter().all(|m| m.command == first.command)
-        && BLINDS
-            .iter()
-            .all(|blind| movements.iter().any(|m| m.blind.aid == blind.aid))
+/// Timed travel to the favourite position after a `my` press. The motor
+/// stops itself there, so no proportional stop is scheduled.
+pub fn plan_my_movement(request: &MotionRequest) -> Option<BlindMovement> {
+    plan_movement(request).map(|movement| BlindMovement {
+        stop_at_end: false,
+        ..movement
+    })
+}
+
+/// One start command can drive every movement when they share a direction
+/// and cover exactly the blinds behind `ALL` or a paired group remote.
+fn group_start_channel(movements: &[BlindMovement], blinds: &BlindInventory) -> Option<Channel> {
+    let first = movements.first()?;
+    if movements.iter().any(|m| m.command != first.command) {
+        return None;
+    }
+    let aids = movements.iter().map(|m| m.blind.aid).collect::<Vec<_>>();
+    blinds.shared_channel(&aids)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::GroupOptions;
+    use crate::positioning::state::PositionSource;
+
+    fn blind(aid: u64) -> Blind {
+        BlindInventory::default().find(aid).unwrap().clone()
+    }
+
+    fn plan_for(requests: &[MotionRequest]) -> MotionPlan {
+        plan_motion(requests, &BlindInventory::default())
+    }
 
     fn timing(open_ms: u64, close_ms: u64) -> BlindMotionTiming {
         timing_with_slack(open_ms, close_ms, 0)
@@ -176,16 +469,20 @@ mod tests {
             open: Duration::from_millis(open_ms),
             close: Duration::from_millis(close_ms),
             slack: Duration::from_millis(slack_ms),
+            tilt: Duration::from_millis(DEFAULT_TILT_MS),
+            open_curve: None,
+            close_curve: None,
         }
     }
 
     #[test]
     fn partial_open_uses_proportional_blind_timing() {
-        let plan = plan_motion(&[MotionRequest {
-            blind: &BLINDS[0],
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
             current: 10,
             target: 60,
             timing: timing(30_000, 20_000),
+            rehome: false,
         }]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
         let MotionPlan::Travel { starts, movements } = plan else {
@@ -204,13 +501,164 @@ mod tests {
         assert!(movements[0].stop_at_end);
     }
 
+    #[test]
+    fn rehoming_routes_interior_move_through_nearer_end_stop() {
+        let request = |current, target| MotionRequest {
+            blind: blind(2),
+            current,
+            target,
+            timing: BlindMotionTiming {
+                slack: Duration::from_millis(2_000),
+                ..timing(30_000, 20_000)
+            },
+            rehome: true,
+        };
+
+        let plan = plan_for(&[request(40, 30)]);
+        assert!(matches!(plan, MotionPlan::Travel { .. }));
+        let MotionPlan::Travel { movements, .. } = plan else {
+            return;
+        };
+        assert_eq!(movements[0].command, Command::Down);
+        assert_eq!(movements[0].target, 0);
+        assert_eq!(movements[0].then, Some(30));
+        assert!(!movements[0].stop_at_end);
+        // 40% of the visible 18 s close, plus the slack to shut the slats.
+        assert_eq!(movements[0].duration, Duration::from_millis(9_200));
+
+        let plan = plan_for(&[request(60, 55)]);
+        let MotionPlan::Travel { movements, .. } = plan else {
+            return;
+        };
+        assert_eq!((movements[0].target, movements[0].then), (100, Some(55)));
+
+        let plan = plan_for(&[request(40, 100)]);
+        let MotionPlan::Travel { movements, .. } = plan else {
+            return;
+        };
+        assert_eq!((movements[0].target, movements[0].then), (100, None));
+    }
+
+    #[test]
+    fn rehome_policy_is_due_after_moves_or_time() {
+        let policy = RehomePolicy {
+            after_moves: Some(3),
+            after: Some(Duration::from_secs(3_600)),
+        };
+        let provenance = |interior_moves, homed_at| Provenance {
+            source: PositionSource::Timed,
+            interior_moves,
+            homed_at,
+        };
+
+        assert!(!policy.is_due(provenance(2, Some(1_000)), 2_000));
+        assert!(policy.is_due(provenance(3, Some(1_000)), 2_000));
+        assert!(policy.is_due(provenance(0, Some(1_000)), 4_600));
+        assert!(policy.is_due(provenance(0, None), 2_000));
+        assert!(!RehomePolicy::default().is_due(provenance(99, None), 2_000));
+    }
+
+    #[test]
+    fn tilt_uses_proportional_tilt_timing() {
+        let mut timing = timing(30_000, 20_000);
+        timing.tilt = Duration::from_millis(1_800);
+
+        let tilt = plan_tilt(&blind(2), -90, 0, &timing).unwrap();
+        assert_eq!(tilt.command, Command::Up);
+        assert_eq!(tilt.duration, Duration::from_millis(900));
+
+        let tilt = plan_tilt(&blind(2), 45, -45, &timing).unwrap();
+        assert_eq!(tilt.command, Command::Down);
+        assert_eq!(tilt.duration, Duration::from_millis(900));
+
+        assert!(plan_tilt(&blind(2), 30, 30, &timing).is_none());
+    }
+
+    #[test]
+    fn travel_curves_replace_proportional_timing() {
+        let options = BlindTimingOptions {
+            open_ms: 30_000,
+            close_ms: 20_000,
+            slack_ms: 2_000,
+            open_curve: vec![(50, 20_000)],
+            close_curve: vec![(50, 8_000)],
+            ..BlindTimingOptions::default()
+        };
+        let timing = BlindMotionTiming::from(&options);
+
+        for (current, target, expected) in [(0, 50, 20_000), (25, 75, 15_000), (100, 20, 15_200)] {
+            let plan = plan_for(&[MotionRequest {
+                blind: blind(2),
+                current,
+                target,
+                timing: timing.clone(),
+                rehome: false,
+            }]);
+            assert!(matches!(plan, MotionPlan::Travel { .. }));
+            let MotionPlan::Travel { movements, .. } = plan else {
+                return;
+            };
+            assert_eq!(
+                movements[0].duration,
+                Duration::from_millis(expected),
+                "{current}->{target}"
+            );
+        }
+    }
+
+    #[test]
+    fn position_at_interpolates_elapsed_travel() {
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
+            current: 0,
+            target: 50,
+            timing: timing_with_slack(30_000, 20_000, 2_000),
+            rehome: false,
+        }]);
+        assert!(matches!(plan, MotionPlan::Travel { .. }));
+        let MotionPlan::Travel { movements, .. } = plan else {
+            return;
+        };
+        let movement = &movements[0];
+
+        assert_eq!(movement.position_at(Duration::from_millis(1_500)), 0);
+        assert_eq!(movement.position_at(Duration::from_millis(9_000)), 25);
+        assert_eq!(movement.position_at(Duration::from_millis(15_999)), 49);
+        assert_eq!(movement.position_at(Duration::from_secs(60)), 50);
+    }
+
+    #[test]
+    fn position_at_follows_travel_curve() {
+        let options = BlindTimingOptions {
+            open_ms: 30_000,
+            close_ms: 20_000,
+            close_curve: vec![(50, 8_000)],
+            ..BlindTimingOptions::default()
+        };
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
+            current: 100,
+            target: 20,
+            timing: BlindMotionTiming::from(&options),
+            rehome: false,
+        }]);
+        assert!(matches!(plan, MotionPlan::Travel { .. }));
+        let MotionPlan::Travel { movements, .. } = plan else {
+            return;
+        };
+
+        assert_eq!(movements[0].position_at(Duration::from_millis(4_000)), 75);
+        assert_eq!(movements[0].position_at(Duration::from_millis(14_000)), 25);
+    }
+
     #[test]
     fn partial_close_uses_close_timing() {
-        let plan = plan_motion(&[MotionRequest {
-            blind: &BLINDS[1],
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(3),
             current: 80,
             target: 20,
             timing: timing(30_000, 10_000),
+            rehome: false,
         }]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
         let MotionPlan::Travel { movements, .. } = plan else {
@@ -225,11 +673,12 @@ mod tests {
 
     #[test]
     fn opening_from_fully_closed_uses_visible_travel_plus_slack() {
-        let plan = plan_motion(&[MotionRequest {
-            blind: &BLINDS[0],
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
             current: 0,
             target: 50,
             timing: timing_with_slack(30_000, 20_000, 2_000),
+            rehome: false,
         }]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
         let MotionPlan::Travel { movements, .. } = plan else {
@@ -243,11 +692,12 @@ mod tests {
     #[test]
     fn slack_is_removed_from_interior_moves_away_from_closed_end() {
         for (current, target, expected) in [(10, 60, 14_000), (100, 50, 9_000)] {
-            let plan = plan_motion(&[MotionRequest {
-                blind: &BLINDS[0],
+            let plan = plan_for(&[MotionRequest {
+                blind: blind(2),
                 current,
                 target,
                 timing: timing_with_slack(30_000, 20_000, 2_000),
+                rehome: false,
             }]);
             assert!(matches!(plan, MotionPlan::Travel { .. }));
             let MotionPlan::Travel { movements, .. } = plan else {
@@ -260,11 +710,12 @@ mod tests {
 
     #[test]
     fn full_opening_from_closed_uses_full_open_travel_time() {
-        let plan = plan_motion(&[MotionRequest {
-            blind: &BLINDS[0],
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
             current: 0,
             target: 100,
             timing: timing_with_slack(30_000, 20_000, 2_000),
+            rehome: false,
         }]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
         let MotionPlan::Travel { movements, .. } = plan else {
@@ -277,11 +728,12 @@ mod tests {
     #[test]
     fn closing_to_fully_closed_does_not_add_opening_slack() {
         for (target, expected) in [(50, 9_000), (0, 18_000)] {
-            let plan = plan_motion(&[MotionRequest {
-                blind: &BLINDS[0],
+            let plan = plan_for(&[MotionRequest {
+                blind: blind(2),
                 current: 100,
                 target,
                 timing: timing_with_slack(30_000, 20_000, 2_000),
+                rehome: false,
             }]);
             assert!(matches!(plan, MotionPlan::Travel { .. }));
             let MotionPlan::Travel { movements, .. } = plan else {
@@ -294,18 +746,20 @@ mod tests {
 
     #[test]
     fn endpoint_targets_do_not_schedule_stop() {
-        let plan = plan_motion(&[
+        let plan = plan_for(&[
             MotionRequest {
-                blind: &BLINDS[0],
+                blind: blind(2),
                 current: 20,
                 target: 100,
                 timing: timing(30_000, 20_000),
+                rehome: false,
             },
             MotionRequest {
-                blind: &BLINDS[1],
+                blind: blind(3),
                 current: 80,
                 target: 0,
                 timing: timing(30_000, 20_000),
+                rehome: false,
             },
         ]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
@@ -319,17 +773,18 @@ mod tests {
 
     #[test]
     fn full_batch_with_same_direction_starts_as_group() {
-        let requests = BLINDS
+        let requests = BlindInventory::default()
             .iter()
             .map(|blind| MotionRequest {
-                blind,
+                blind: blind.clone(),
                 current: 0,
                 target: 50,
                 timing: timing(20_000, 20_000),
+                rehome: false,
             })
             .collect::<Vec<_>>();
 
-        let plan = plan_motion(&requests);
+        let plan = plan_for(&requests);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
         let MotionPlan::Travel { starts, movements } = plan else {
             return;
@@ -345,20 +800,58 @@ mod tests {
         assert_eq!(movements.len(), 4);
     }
 
+    #[test]
+    fn paired_group_batch_starts_on_group_remote() {
+        let blinds = BlindInventory::default().with_groups(&[GroupOptions {
+            name: "living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L1, Channel::L3],
+            aid: None,
+            paired: true,
+        }]);
+        let requests = [2, 4]
+            .into_iter()
+            .map(|aid| MotionRequest {
+                blind: blinds.find(aid).unwrap().clone(),
+                current: 100,
+                target: 40,
+                timing: timing(20_000, 20_000),
+                rehome: false,
+            })
+            .collect::<Vec<_>>();
+
+        let plan = plan_motion(&requests, &blinds);
+        assert!(matches!(plan, MotionPlan::Travel { .. }));
+        let MotionPlan::Travel { starts, movements } = plan else {
+            return;
+        };
+
+        assert_eq!(
+            starts,
+            vec![DriverStart {
+                channel: Channel::Group(1),
+                command: Command::Down,
+            }]
+        );
+        assert_eq!(movements.len(), 2);
+    }
+
     #[test]
     fn mixed_direction_batch_starts_individually() {
-        let plan = plan_motion(&[
+        let plan = plan_for(&[
             MotionRequest {
-                blind: &BLINDS[0],
+                blind: blind(2),
                 current: 0,
                 target: 50,
                 timing: timing(20_000, 20_000),
+                rehome: false,
             },
             MotionRequest {
-                blind: &BLINDS[1],
+                blind: blind(3),
                 current: 90,
                 target: 50,
                 timing: timing(20_000, 20_000),
+                rehome: false,
             },
         ]);
         assert!(matches!(plan, MotionPlan::Travel { .. }));
@@ -383,11 +876,12 @@ mod tests {
 
     #[test]
     fn matching_current_and_target_plans_cancel_and_snap() {
-        let plan = plan_motion(&[MotionRequest {
-            blind: &BLINDS[0],
+        let plan = plan_for(&[MotionRequest {
+            blind: blind(2),
             current: 50,
             target: 50,
             timing: timing(20_000, 20_000),
+            rehome: false,
         }]);
 
         assert!(matches!(plan, MotionPlan::CancelAndSnap { .. }));
@@ -395,6 +889,6 @@ mod tests {
 
     #[test]
     fn empty_requests_is_noop() {
-        assert!(matches!(plan_motion(&[]), MotionPlan::NoOp));
+        assert!(matches!(plan_for(&[]), MotionPlan::NoOp));
     }
 }
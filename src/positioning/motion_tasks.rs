@@ -3,9 +3,10 @@
 use std::collections::HashMap;
 
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 
-use crate::core::Channel;
-use crate::positioning::state::aids_for_channel;
+use crate::positioning::inventory::Blind;
+use crate::positioning::motion::BlindMovement;
 
 #[derive(Debug, Default)]
 pub(crate) struct MotionTasks {
@@ -16,20 +17,38 @@ pub(crate) struct MotionTasks {
 struct MotionTaskState {
     generation: u64,
     handle: Option<tokio::task::JoinHandle<()>>,
+    /// Timed travel in flight and when it started, for interpolating where an
+    /// interrupted move stopped. `None` for tilt pulses.
+    travel: Option<(BlindMovement, Instant)>,
 }
 
 impl MotionTasks {
-    pub async fn replace(&self, aid: u64, handle: Option<tokio::task::JoinHandle<()>>) -> u64 {
+    /// Start a new generation for `aid`, aborting any previous task.
+    pub async fn begin(&self, aid: u64, travel: Option<(BlindMovement, Instant)>) -> u64 {
         let mut tasks = self.tasks.lock().await;
         let state = tasks.entry(aid).or_default();
         state.generation = state.generation.wrapping_add(1);
         if let Some(old) = state.handle.take() {
             old.abort();
         }
-        state.handle = handle;
+        state.travel = travel;
         state.generation
     }
 
+    /// Interpolated positions of the in-flight travels among `aids`.
+    pub async fn interpolated(&self, aids: &[u64]) -> Vec<(Blind, u8)> {
+        let tasks = self.tasks.lock().await;
+        aids.iter()
+            .filter_map(|aid| tasks.get(aid)?.travel.as_ref())
+            .map(|(movement, started)| {
+                (
+                    movement.blind.clone(),
+                    movement.position_at(started.elapsed()),
+                )
+            })
+            .collect()
+    }
+
     pub async fn attach_handle(
         &self,
         aid: u64,
@@ -50,11 +69,10 @@ impl MotionTasks {
         Self::cancel_state(tasks.get_mut(&aid))
     }
 
-    pub async fn cancel_channel(&self, channel: Channel) {
-        let aids = aids_for_channel(channel);
+    pub async fn cancel_many(&self, aids: &[u64]) {
         let mut tasks = self.tasks.lock().await;
         for aid in aids {
-            Self::cancel_state(tasks.get_mut(&aid));
+            Self::cancel_state(tasks.get_mut(aid));
         }
     }
 
@@ -63,6 +81,7 @@ impl MotionTasks {
             return false;
         };
         state.generation = state.generation.wrapping_add(1);
+        state.travel = None;
         let Some(old) = state.handle.take() else {
             return false;
         };
@@ -83,6 +102,7 @@ impl MotionTasks {
         if let Some(state) = tasks.get_mut(&aid) {
             if state.generation == generation {
                 state.handle = None;
+                state.travel = None;
             }
         }
     }
@@ -3,13 +3,17 @@
 //! Reload is read-only: we never replay a saved position to GPIO.
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
 use crate::core::Channel;
 use crate::persist::{self, atomic_save_bytes};
+use crate::positioning::inventory::{Blind, BlindInventory};
 
 const POSITIONS_FILE: &str = "positions.json";
 
@@ -17,64 +21,8 @@ pub const STATUS_DECREASING: u8 = 0;
 pub const STATUS_INCREASING: u8 = 1;
 pub const STATUS_STOPPED: u8 = 2;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct Blind {
-    pub aid: u64,
-    pub name: &'static str,
-    pub channel: Channel,
-    pub serial: &'static str,
-}
-
-pub const BLINDS: &[Blind] = &[
-    Blind {
-        aid: 2,
-        name: "Blind 1",
-        channel: Channel::L1,
-        serial: "somfy-L1",
-    },
-    Blind {
-        aid: 3,
-        name: "Blind 2",
-        channel: Channel::L2,
-        serial: "somfy-L2",
-    },
-    Blind {
-        aid: 4,
-        name: "Blind 3",
-        channel: Channel::L3,
-        serial: "somfy-L3",
-    },
-    Blind {
-        aid: 5,
-        name: "Blind 4",
-        channel: Channel::L4,
-        serial: "somfy-L4",
-    },
-];
-
-pub fn find_blind(aid: u64) -> Option<&'static Blind> {
-    BLINDS.iter().find(|b| b.aid == aid)
-}
-
-pub fn find_blind_for_channel(channel: Channel) -> Option<&'static Blind> {
-    BLINDS.iter().find(|b| b.channel == channel)
-}
-
-pub fn aids_for_channel(channel: Channel) -> Vec<u64> {
-    match channel {
-        Channel::All => BLINDS.iter().map(|blind| blind.aid).collect(),
-        _ => find_blind_for_channel(channel)
-            .map(|blind| vec![blind.aid])
-            .unwrap_or_default(),
-    }
-}
-
-pub fn target_positions(channel: Channel, position: u8) -> Vec<(u64, u8)> {
-    aids_for_channel(channel)
-        .into_iter()
-        .map(|aid| (aid, position))
-        .collect()
-}
+/// Venetian slat angle after travelling up; travelling down leaves `-TILT_MAX`.
+pub const TILT_MAX: i8 = 90;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct BlindPosition {
@@ -82,6 +30,9 @@ pub struct BlindPosition {
     pub current: u8,
     pub target: u8,
     pub status: u8,
+    /// Estimated slat angle (-90..=90); always `0` for roller blinds.
+    pub current_tilt: i8,
+    pub target_tilt: i8,
 }
 
 impl BlindPosition {
@@ -92,6 +43,8 @@ impl BlindPosition {
             current: 100,
             target: 100,
             status: STATUS_STOPPED,
+            current_tilt: 0,
+            target_tilt: 0,
         }
     }
 }
@@ -102,40 +55,123 @@ pub struct PositionDelta {
     pub current: Option<u8>,
     pub target: Option<u8>,
     pub status: Option<u8>,
+    pub current_tilt: Option<i8>,
+    pub target_tilt: Option<i8>,
+}
+
+impl PositionDelta {
+    /// Mid-move interpolated position: only `current` changed.
+    pub fn is_progress(&self) -> bool {
+        self.current.is_some()
+            && self.target.is_none()
+            && self.status.is_none()
+            && self.current_tilt.is_none()
+            && self.target_tilt.is_none()
+    }
+}
+
+/// Interior moves after which a settled estimate is only approximate.
+const DRIFT_MOVES: u32 = 4;
+
+/// Where a blind's current position estimate came from.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionSource {
+    /// Settled at the fully open or fully closed end stop.
+    EndStop,
+    /// A timed move ran to completion between the end stops.
+    Timed,
+    /// A timed move was cut short; the position is an elapsed-time estimate.
+    Interrupted,
+    /// Nothing recorded; the position is the default.
+    #[default]
+    Unknown,
+}
+
+/// How far a client should trust a reported position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionConfidence {
+    High,
+    Medium,
+    Low,
+    Unknown,
 }
 
+/// Source and drift bookkeeping for one blind, persisted next to its position.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    pub source: PositionSource,
+    /// Moves that settled between the end stops since the blind last reached one.
+    pub interior_moves: u32,
+    /// Unix seconds when the blind last settled at an end stop, if known.
+    pub homed_at: Option<u64>,
+}
+
+impl Provenance {
+    /// End stops are exact. One timed move away from an end stop is still
+    /// trusted; interruptions and every further interior move add drift.
+    pub fn confidence(&self) -> PositionConfidence {
+        match self.source {
+            PositionSource::EndStop => PositionConfidence::High,
+            PositionSource::Timed if self.interior_moves <= 1 => PositionConfidence::High,
+            PositionSource::Timed | PositionSource::Interrupted
+                if self.interior_moves <= DRIFT_MOVES =>
+            {
+                PositionConfidence::Medium
+            }
+            PositionSource::Timed | PositionSource::Interrupted => PositionConfidence::Low,
+            PositionSource::Unknown => PositionConfidence::Unknown,
+        }
+    }
+}
+
+/// Tilt is kept in memory only; it is re-established by the next full travel.
 #[derive(Clone, Debug, Default)]
 pub struct PositionState {
     current: HashMap<u64, u8>,
     target: HashMap<u64, u8>,
     status: HashMap<u64, u8>,
+    tilt: HashMap<u64, i8>,
+    tilt_target: HashMap<u64, i8>,
+    provenance: HashMap<u64, Provenance>,
 }
 
 #[derive(Debug)]
 pub struct PositionCache {
+    blinds: Arc<BlindInventory>,
     state: Mutex<PositionState>,
     persist: bool,
 }
 
 impl PositionCache {
-    pub fn new() -> Self {
+    pub fn new(blinds: Arc<BlindInventory>) -> Self {
+        let saved = load_positions();
         Self {
+            blinds,
             state: Mutex::new(PositionState {
-                current: load_positions(),
+                current: saved.current,
                 target: HashMap::new(),
                 status: HashMap::new(),
+                tilt: HashMap::new(),
+                tilt_target: HashMap::new(),
+                provenance: saved.provenance,
             }),
             persist: true,
         }
     }
 
     #[cfg(test)]
-    pub fn from_positions(positions: HashMap<u64, u8>) -> Self {
+    pub fn from_positions(blinds: Arc<BlindInventory>, positions: HashMap<u64, u8>) -> Self {
         Self {
+            blinds,
             state: Mutex::new(PositionState {
                 current: positions,
                 target: HashMap::new(),
                 status: HashMap::new(),
+                tilt: HashMap::new(),
+                tilt_target: HashMap::new(),
+                provenance: HashMap::new(),
             }),
             persist: false,
         }
@@ -143,25 +179,31 @@ impl PositionCache {
 
     pub async fn snapshot(&self) -> Vec<BlindPosition> {
         let state = self.state.lock().await;
-        BLINDS
+        self.blinds
             .iter()
             .map(|b| BlindPosition {
                 aid: b.aid,
                 current: effective_current_position(&state, b.aid),
                 target: effective_target_position(&state, b.aid),
                 status: effective_status(&state, b.aid),
+                current_tilt: effective_tilt(&state, b.aid),
+                target_tilt: effective_target_tilt(&state, b.aid),
             })
             .collect()
     }
 
+    /// Interior-move count and last end-stop time for every configured blind.
+    pub async fn provenance_snapshot(&self) -> HashMap<u64, Provenance> {
+        let state = self.state.lock().await;
+        self.blinds
+            .iter()
+            .map(|b| (b.aid, effective_provenance(&state, b.aid)))
+            .collect()
+    }
+
     pub async fn apply_for_channel(&self, channel: Channel, pos: u8) -> Vec<PositionDelta> {
-        if matches!(channel, Channel::All) {
-            return self.apply_all_current(pos).await;
-        }
-        let Some(blind) = find_blind_for_channel(channel) else {
-            return Vec::new();
-        };
-        self.apply_blind_current(blind, pos).await
+        let aids = self.blinds.aids_for_channel(channel);
+        self.apply_current_for_aids(&aids, pos).await
     }
 
     pub async fn apply_blind_current(&self, blind: &Blind, position: u8) -> Vec<PositionDelta> {
@@ -169,35 +211,83 @@ impl PositionCache {
         let new_pos = position.min(100);
         if state.current.get(&blind.aid).copied() == Some(new_pos)
             && effective_target_position(&state, blind.aid) == new_pos
+            && effective_status(&state, blind.aid) == STATUS_STOPPED
         {
             return Vec::new();
         }
         state.current.insert(blind.aid, new_pos);
         state.target.insert(blind.aid, new_pos);
         state.status.insert(blind.aid, STATUS_STOPPED);
-        self.finish_current_update(&[(blind.aid, new_pos)], &state.current)
+        record_settled(&mut state, blind.aid, new_pos, PositionSource::Timed);
+        self.finish_current_update(&[(blind.aid, new_pos)], &state)
+    }
+
+    /// Record an interpolated position for a blind that is still moving. The
+    /// target and status are left alone and nothing is persisted; the move's
+    /// completion writes the settled position.
+    pub async fn apply_progress(&self, blind: &Blind, position: u8) -> Vec<PositionDelta> {
+        let mut state = self.state.lock().await;
+        let position = position.min(100);
+        if effective_status(&state, blind.aid) == STATUS_STOPPED
+            || effective_current_position(&state, blind.aid) == position
+        {
+            return Vec::new();
+        }
+        state.current.insert(blind.aid, position);
+        vec![PositionDelta {
+            aid: blind.aid,
+            current: Some(position),
+            target: None,
+            status: None,
+            current_tilt: None,
+            target_tilt: None,
+        }]
     }
 
-    pub async fn apply_all_current(&self, position: u8) -> Vec<PositionDelta> {
+    async fn apply_current_for_aids(&self, aids: &[u64], position: u8) -> Vec<PositionDelta> {
         let mut state = self.state.lock().await;
         let new_pos = position.min(100);
         let mut changes = Vec::new();
-        for blind in BLINDS {
-            if state.current.get(&blind.aid).copied() != Some(new_pos)
-                || effective_target_position(&state, blind.aid) != new_pos
+        let mut tilts = Vec::new();
+        let mut rehomed = false;
+        for aid in aids.iter().copied() {
+            if state.current.get(&aid).copied() != Some(new_pos)
+                || effective_target_position(&state, aid) != new_pos
             {
-                state.current.insert(blind.aid, new_pos);
-                state.target.insert(blind.aid, new_pos);
-                state.status.insert(blind.aid, STATUS_STOPPED);
-                changes.push((blind.aid, new_pos));
+                state.current.insert(aid, new_pos);
+                state.target.insert(aid, new_pos);
+                state.status.insert(aid, STATUS_STOPPED);
+                record_settled(&mut state, aid, new_pos, PositionSource::Timed);
+                changes.push((aid, new_pos));
+            } else if matches!(new_pos, 0 | 100) {
+                // The motor runs into the end stop even when the estimate
+                // already sat there, so the anchor is still fresh.
+                record_settled(&mut state, aid, new_pos, PositionSource::EndStop);
+                rehomed = true;
+            }
+            let direction = match new_pos {
+                100 => STATUS_INCREASING,
+                0 => STATUS_DECREASING,
+                _ => continue,
+            };
+            if let Some(blind) = self.blinds.find(aid) {
+                tilts.extend(settle_travel_tilt(&mut state, blind, direction));
             }
         }
-        if changes.is_empty() {
-            return Vec::new();
-        }
-        self.finish_current_update(&changes, &state.current)
+        let mut deltas = if changes.is_empty() {
+            if rehomed {
+                self.persist_current(&state);
+            }
+            Vec::new()
+        } else {
+            self.finish_current_update(&changes, &state)
+        };
+        deltas.extend(tilts);
+        deltas
     }
 
+    /// Record a travel target. Venetian slats rotate fully in the direction of
+    /// travel before the blind moves, so their tilt settles at `±TILT_MAX`.
     pub async fn apply_target(&self, blind: &Blind, target: u8, status: u8) -> Vec<PositionDelta> {
         let mut state = self.state.lock().await;
         let target = target.min(100);
@@ -206,21 +296,68 @@ impl PositionCache {
         }
         state.target.insert(blind.aid, target);
         state.status.insert(blind.aid, status);
-        target_events(blind.aid, target, status)
+        let mut deltas = target_events(blind.aid, target, status);
+        deltas.extend(settle_travel_tilt(&mut state, blind, status));
+        deltas
+    }
+
+    /// Record a slat tilt in progress towards `angle`.
+    pub async fn apply_tilt_target(&self, blind: &Blind, angle: i8) -> Vec<PositionDelta> {
+        let mut state = self.state.lock().await;
+        let angle = angle.clamp(-TILT_MAX, TILT_MAX);
+        if effective_target_tilt(&state, blind.aid) == angle {
+            return Vec::new();
+        }
+        state.tilt_target.insert(blind.aid, angle);
+        vec![PositionDelta {
+            aid: blind.aid,
+            current: None,
+            target: None,
+            status: None,
+            current_tilt: None,
+            target_tilt: Some(angle),
+        }]
+    }
+
+    /// Settle the slats at `angle` once a timed tilt completes.
+    pub async fn apply_tilt_current(&self, blind: &Blind, angle: i8) -> Vec<PositionDelta> {
+        let mut state = self.state.lock().await;
+        tilt_events(&mut state, blind.aid, angle.clamp(-TILT_MAX, TILT_MAX))
+            .into_iter()
+            .collect()
     }
 
     /// Mark a manually stopped channel as stationary at its last known position.
     ///
-    /// Position estimation only advances when a timed motion completes, so an
-    /// early stop cannot infer a more precise intermediate position. Resetting
-    /// the target to the last known current value keeps the state internally
+    /// The controller first snaps an interrupted timed move to its elapsed-time
+    /// estimate; blinds without one keep their last known current value.
+    /// Resetting the target to that value keeps the state internally
     /// consistent and prevents HomeKit from reporting a movement that is no
     /// longer running.
     pub async fn stop_channel(&self, channel: Channel) -> Vec<PositionDelta> {
+        self.stop_aids(&self.blinds.aids_for_channel(channel)).await
+    }
+
+    /// Settle `aids` at their last known position, which may be an
+    /// interpolated estimate of where an interrupted move stopped.
+    pub async fn stop_aids(&self, aids: &[u64]) -> Vec<PositionDelta> {
         let mut state = self.state.lock().await;
         let mut deltas = Vec::new();
-
-        for aid in aids_for_channel(channel) {
+        let mut stopped = false;
+
+        for aid in aids.iter().copied() {
+            let tilt = effective_tilt(&state, aid);
+            if effective_target_tilt(&state, aid) != tilt {
+                state.tilt_target.insert(aid, tilt);
+                deltas.push(PositionDelta {
+                    aid,
+                    current: None,
+                    target: None,
+                    status: None,
+                    current_tilt: None,
+                    target_tilt: Some(tilt),
+                });
+            }
             let current = effective_current_position(&state, aid);
             if effective_target_position(&state, aid) == current
                 && effective_status(&state, aid) == STATUS_STOPPED
@@ -230,103 +367,228 @@ impl PositionCache {
 
             state.target.insert(aid, current);
             state.status.insert(aid, STATUS_STOPPED);
+            record_settled(&mut state, aid, current, PositionSource::Interrupted);
+            stopped = true;
             deltas.push(PositionDelta {
                 aid,
                 current: None,
                 target: Some(current),
                 status: Some(STATUS_STOPPED),
+                current_tilt: None,
+                target_tilt: None,
             });
         }
 
+        if stopped {
+            self.persist_current(&state);
+        }
         deltas
     }
 
     fn finish_current_update(
         &self,
         changes: &[(u64, u8)],
-        positions: &HashMap<u64, u8>,
+        state: &PositionState,
     ) -> Vec<PositionDelta> {
-        if self.persist {
-            if let Err(e) = save_positions(positions) {
-                tracing::warn!("failed to persist positions: {e}");
-            }
-        }
+        self.persist_current(state);
         changes
             .iter()
             .flat_map(|(aid, pos)| position_events(*aid, *pos))
             .collect()
     }
+
+    fn persist_current(&self, state: &PositionState) {
+        if self.persist {
+            if let Err(e) = save_positions(&state.current, &state.provenance) {
+                tracing::warn!("failed to persist positions: {e}");
+            }
+        }
+    }
+}
+
+/// Settled positions and drift bookkeeping read back from `positions.json`.
+#[derive(Debug, Default, PartialEq)]
+struct SavedPositions {
+    current: HashMap<u64, u8>,
+    provenance: HashMap<u64, Provenance>,
 }
 
-fn load_positions() -> HashMap<u64, u8> {
+/// One `positions.json` entry. Files written before re-homing stored a bare
+/// percentage per aid; those still load, with no drift recorded and a source
+/// inferred from the position.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredPosition {
+    Legacy(u8),
+    Tracked(StoredBlind),
+}
+
+#[derive(Deserialize, Serialize)]
+struct StoredBlind {
+    position: u8,
+    #[serde(default)]
+    source: Option<PositionSource>,
+    #[serde(default)]
+    interior_moves: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    homed_at: Option<u64>,
+}
+
+fn load_positions() -> SavedPositions {
     load_positions_from(&persist::state_dir().join(POSITIONS_FILE))
 }
 
-fn save_positions(positions: &HashMap<u64, u8>) -> Result<()> {
+fn save_positions(
+    positions: &HashMap<u64, u8>,
+    provenance: &HashMap<u64, Provenance>,
+) -> Result<()> {
     let dir = persist::state_dir();
     fs::create_dir_all(&dir)
         .with_context(|| format!("creating state directory {}", dir.display()))?;
-    save_positions_to(&dir.join(POSITIONS_FILE), positions)
+    save_positions_to(&dir.join(POSITIONS_FILE), positions, provenance)
 }
 
-fn load_positions_from(path: &Path) -> HashMap<u64, u8> {
+fn load_positions_from(path: &Path) -> SavedPositions {
     let text = match fs::read_to_string(path) {
         Ok(t) => t,
-        Err(_) => return HashMap::new(),
+        Err(_) => return SavedPositions::default(),
     };
-    let raw: HashMap<String, u8> = match serde_json::from_str(&text) {
+    let raw: HashMap<String, StoredPosition> = match serde_json::from_str(&text) {
         Ok(m) => m,
         Err(e) => {
             tracing::warn!("ignoring malformed {}: {}", path.display(), e);
-            return HashMap::new();
+            return SavedPositions::default();
         }
     };
-    raw.into_iter()
-        .filter_map(|(k, v)| {
-            let aid = k.parse::<u64>().ok()?;
-            if v <= 100 {
-                Some((aid, v))
-            } else {
-                None
-            }
-        })
-        .collect()
+    let mut saved = SavedPositions::default();
+    for (key, entry) in raw {
+        let Ok(aid) = key.parse::<u64>() else {
+            continue;
+        };
+        let stored = match entry {
+            StoredPosition::Legacy(position) => StoredBlind {
+                position,
+                source: None,
+                interior_moves: 0,
+                homed_at: None,
+            },
+            StoredPosition::Tracked(stored) => stored,
+        };
+        if stored.position > 100 {
+            continue;
+        }
+        saved.current.insert(aid, stored.position);
+        let source = stored.source.unwrap_or(match stored.position {
+            0 | 100 => PositionSource::EndStop,
+            _ => PositionSource::Timed,
+        });
+        saved.provenance.insert(
+            aid,
+            Provenance {
+                source,
+                interior_moves: stored.interior_moves,
+                homed_at: stored.homed_at,
+            },
+        );
+    }
+    saved
 }
 
-fn save_positions_to(path: &Path, positions: &HashMap<u64, u8>) -> Result<()> {
-    let stringified: BTreeMap<String, u8> =
-        positions.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+fn save_positions_to(
+    path: &Path,
+    positions: &HashMap<u64, u8>,
+    provenance: &HashMap<u64, Provenance>,
+) -> Result<()> {
+    let stringified: BTreeMap<String, StoredBlind> = positions
+        .iter()
+        .map(|(aid, position)| {
+            let provenance = provenance.get(aid).copied().unwrap_or_default();
+            (
+                aid.to_string(),
+                StoredBlind {
+                    position: *position,
+                    source: Some(provenance.source),
+                    interior_moves: provenance.interior_moves,
+                    homed_at: provenance.homed_at,
+                },
+            )
+        })
+        .collect();
     let bytes = serde_json::to_vec_pretty(&stringified)?;
     atomic_save_bytes(path, &bytes, false)
 }
 
+/// Count a move that settled between the end stops with `source`, or
+/// re-anchor the blind when it settled at one.
+fn record_settled(state: &mut PositionState, aid: u64, position: u8, source: PositionSource) {
+    let provenance = state.provenance.entry(aid).or_default();
+    if matches!(position, 0 | 100) {
+        *provenance = Provenance {
+            source: PositionSource::EndStop,
+            interior_moves: 0,
+            homed_at: Some(unix_now()),
+        };
+    } else {
+        provenance.source = source;
+        provenance.interior_moves = provenance.interior_moves.saturating_add(1);
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+pub fn effective_provenance(state: &PositionState, aid: u64) -> Provenance {
+    state.provenance.get(&aid).copied().unwrap_or_default()
+}
+
 pub fn effective_current_position(state: &PositionState, aid: u64) -> u8 {
-    let Some(blind) = find_blind(aid) else {
-        return 100;
-    };
-    state.current.get(&blind.aid).copied().unwrap_or(100)
+    state.current.get(&aid).copied().unwrap_or(100)
 }
 
 pub fn effective_target_position(state: &PositionState, aid: u64) -> u8 {
-    let Some(blind) = find_blind(aid) else {
-        return 100;
-    };
     state
         .target
-        .get(&blind.aid)
+        .get(&aid)
         .copied()
         .unwrap_or_else(|| effective_current_position(state, aid))
 }
 
 pub fn effective_status(state: &PositionState, aid: u64) -> u8 {
-    let Some(blind) = find_blind(aid) else {
-        return STATUS_STOPPED;
-    };
+    state.status.get(&aid).copied().unwrap_or(STATUS_STOPPED)
+}
+
+pub fn effective_tilt(state: &PositionState, aid: u64) -> i8 {
+    state.tilt.get(&aid).copied().unwrap_or(0)
+}
+
+pub fn effective_target_tilt(state: &PositionState, aid: u64) -> i8 {
     state
-        .status
-        .get(&blind.aid)
+        .tilt_target
+        .get(&aid)
         .copied()
-        .unwrap_or(STATUS_STOPPED)
+        .unwrap_or_else(|| effective_tilt(state, aid))
+}
+
+/// Full-state deltas for `positions`, used to resync an observer that fell
+/// behind the position broadcast. Tilt is only reported for venetian blinds.
+pub fn snapshot_deltas(blinds: &BlindInventory, positions: &[BlindPosition]) -> Vec<PositionDelta> {
+    positions
+        .iter()
+        .map(|pos| {
+            let venetian = blinds.find(pos.aid).is_some_and(Blind::is_venetian);
+            PositionDelta {
+                aid: pos.aid,
+                current: Some(pos.current),
+                target: Some(pos.target),
+                status: Some(pos.status),
+                current_tilt: venetian.then_some(pos.current_tilt),
+                target_tilt: venetian.then_some(pos.target_tilt),
+            }
+        })
+        .collect()
 }
 
 pub fn position_events(aid: u64, position: u8) -> Vec<PositionDelta> {
@@ -335,6 +597,8 @@ pub fn position_events(aid: u64, position: u8) -> Vec<PositionDelta> {
         current: Some(position),
         target: Some(position),
         status: Some(STATUS_STOPPED),
+        current_tilt: None,
+        target_tilt: None,
     }]
 }
 
@@ -344,9 +608,43 @@ pub fn target_events(aid: u64, target: u8, status: u8) -> Vec<PositionDelta> {
         current: None,
         target: Some(target),
         status: Some(status),
+        current_tilt: None,
+        target_tilt: None,
     }]
 }
 
+fn tilt_events(state: &mut PositionState, aid: u64, angle: i8) -> Option<PositionDelta> {
+    if effective_tilt(state, aid) == angle && effective_target_tilt(state, aid) == angle {
+        return None;
+    }
+    state.tilt.insert(aid, angle);
+    state.tilt_target.insert(aid, angle);
+    Some(PositionDelta {
+        aid,
+        current: None,
+        target: None,
+        status: None,
+        current_tilt: Some(angle),
+        target_tilt: Some(angle),
+    })
+}
+
+fn settle_travel_tilt(
+    state: &mut PositionState,
+    blind: &Blind,
+    direction: u8,
+) -> Option<PositionDelta> {
+    if !blind.is_venetian() {
+        return None;
+    }
+    let angle = match direction {
+        STATUS_INCREASING => TILT_MAX,
+        STATUS_DECREASING => -TILT_MAX,
+        _ => return None,
+    };
+    tilt_events(state, blind.aid, angle)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,7 +655,7 @@ mod tests {
         let mut positions = HashMap::new();
         positions.insert(2, 25);
 
-        let cache = PositionCache::from_positions(positions);
+        let cache = PositionCache::from_positions(Arc::default(), positions);
 
         let snapshot = cache.snapshot().await;
         let blind = snapshot.iter().find(|p| p.aid == 2).unwrap();
@@ -368,8 +666,10 @@ mod tests {
 
     #[tokio::test]
     async fn stop_channel_resets_pending_target_to_last_known_position() {
-        let cache = PositionCache::from_positions(HashMap::from([(2, 75)]));
-        cache.apply_target(&BLINDS[0], 25, STATUS_DECREASING).await;
+        let blinds = Arc::new(BlindInventory::default());
+        let cache = PositionCache::from_positions(blinds.clone(), HashMap::from([(2, 75)]));
+        let blind = blinds.find(2).unwrap();
+        cache.apply_target(blind, 25, STATUS_DECREASING).await;
 
         let deltas = cache.stop_channel(Channel::L1).await;
 
@@ -380,6 +680,8 @@ mod tests {
                 current: None,
                 target: Some(75),
                 status: Some(STATUS_STOPPED),
+                current_tilt: None,
+                target_tilt: None,
             }]
         );
         assert_eq!(
@@ -389,6 +691,8 @@ mod tests {
                 current: 75,
                 target: 75,
                 status: STATUS_STOPPED,
+                current_tilt: 0,
+                target_tilt: 0,
             }
         );
     }
@@ -409,48 +713,121 @@ mod tests {
         let path = dir.path().join(POSITIONS_FILE);
         let mut original = HashMap::new();
         original.insert(2u64, 0u8);
-        original.insert(3u64, 100u8);
         original.insert(4u64, 37u8);
-        original.insert(6u64, 0u8);
-        save_positions_to(&path, &original).unwrap();
+        original.insert(6u64, 52u8);
+        let provenance = HashMap::from([
+            (
+                2u64,
+                Provenance {
+                    source: PositionSource::EndStop,
+                    interior_moves: 0,
+                    homed_at: Some(1_760_000_100),
+                },
+            ),
+            (
+                4u64,
+                Provenance {
+                    source: PositionSource::Timed,
+                    interior_moves: 3,
+                    homed_at: Some(1_760_000_000),
+                },
+            ),
+            (
+                6u64,
+                Provenance {
+                    source: PositionSource::Interrupted,
+                    interior_moves: 1,
+                    homed_at: None,
+                },
+            ),
+        ]);
+        save_positions_to(&path, &original, &provenance).unwrap();
         let loaded = load_positions_from(&path);
-        assert_eq!(loaded, original);
+        assert_eq!(loaded.current, original);
+        assert_eq!(loaded.provenance, provenance);
     }
 
     #[test]
-    fn positions_file_saves_in_stable_order() {
+    fn positions_file_loads_legacy_percentages() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join(POSITIONS_FILE);
-        let positions = HashMap::from([(4, 37), (6, 50), (2, 0), (3, 101), (5, 100)]);
+        fs::write(
+            &path,
+            r#"{"2": 0, "3": 101, "4": 37, "5": {"position": 60, "interior_moves": 2}}"#,
+        )
+        .unwrap();
 
-        save_positions_to(&path, &positions).unwrap();
+        let loaded = load_positions_from(&path);
 
-        assert_eq!(
-            fs::read_to_string(&path).unwrap(),
-            "{\n  \"2\": 0,\n  \"3\": 101,\n  \"4\": 37,\n  \"5\": 100,\n  \"6\": 50\n}"
-        );
+        assert_eq!(loaded.current, HashMap::from([(2, 0), (4, 37), (5, 60)]));
+        let source = |aid| loaded.provenance[&aid].source;
+        assert_eq!(source(2), PositionSource::EndStop);
+        assert_eq!(source(4), PositionSource::Timed);
+        assert_eq!(source(5), PositionSource::Timed);
+        assert_eq!(loaded.provenance[&5].interior_moves, 2);
     }
 
     #[test]
-    fn missing_positions_file_returns_empty() {
+    fn positions_file_saves_in_stable_order() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join(POSITIONS_FILE);
-        let loaded = load_positions_from(&path);
-        assert!(loaded.is_empty());
+        let positions = HashMap::from([(4, 37), (2, 0), (3, 101)]);
+        let provenance = HashMap::from([(
+            4,
+            Provenance {
+                source: PositionSource::Timed,
+                interior_moves: 1,
+                homed_at: Some(1_760_000_000),
+            },
+        )]);
+
+        save_positions_to(&path, &positions, &provenance).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\n  \"2\": {\n    \"position\": 0,\n    \"source\": \"unknown\",\n    \"interior_moves\": 0\n  },\n  \"3\": {\n    \"position\": 101,\n    \"source\": \"unknown\",\n    \"interior_moves\": 0\n  },\n  \"4\": {\n    \"position\": 37,\n    \"source\": \"timed\",\n    \"interior_moves\": 1,\n    \"homed_at\": 1760000000\n  }\n}"
+        );
     }
 
-    #[test]
-    fn aids_for_channel_maps_channel_and_all() {
-        assert_eq!(aids_for_channel(Channel::L2), vec![3]);
-        assert_eq!(aids_for_channel(Channel::All), vec![2, 3, 4, 5]);
+    #[tokio::test]
+    async fn settled_moves_record_source_and_lower_confidence() {
+        let cache = PositionCache::from_positions(
+            Arc::new(BlindInventory::default()),
+            HashMap::from([(2, 100)]),
+        );
+        let blind = BlindInventory::default().find(2).unwrap().clone();
+        let provenance = |cache: &PositionCache| {
+            let cache = cache.state.try_lock().unwrap();
+            effective_provenance(&cache, 2)
+        };
+        assert_eq!(provenance(&cache).confidence(), PositionConfidence::Unknown);
+
+        cache.apply_for_channel(Channel::L1, 0).await;
+        assert_eq!(provenance(&cache).source, PositionSource::EndStop);
+        assert_eq!(provenance(&cache).confidence(), PositionConfidence::High);
+
+        cache.apply_blind_current(&blind, 40).await;
+        assert_eq!(provenance(&cache).source, PositionSource::Timed);
+        assert_eq!(provenance(&cache).confidence(), PositionConfidence::High);
+
+        cache.apply_target(&blind, 60, STATUS_INCREASING).await;
+        cache.apply_progress(&blind, 50).await;
+        cache.stop_aids(&[2]).await;
+        assert_eq!(provenance(&cache).source, PositionSource::Interrupted);
+        assert_eq!(provenance(&cache).confidence(), PositionConfidence::Medium);
+
+        for position in [30, 40, 30, 40] {
+            cache.apply_blind_current(&blind, position).await;
+        }
+        assert_eq!(provenance(&cache).interior_moves, 6);
+        assert_eq!(provenance(&cache).confidence(), PositionConfidence::Low);
     }
 
     #[test]
-    fn target_positions_pairs_aids_with_position() {
-        assert_eq!(target_positions(Channel::L2, 25), vec![(3, 25)]);
-        assert_eq!(
-            target_positions(Channel::All, 10),
-            vec![(2, 10), (3, 10), (4, 10), (5, 10)]
-        );
+    fn missing_positions_file_returns_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(POSITIONS_FILE);
+        let loaded = load_positions_from(&path);
+        assert_eq!(loaded, SavedPositions::default());
     }
 }
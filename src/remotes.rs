@@ -0,0 +1,363 @@
+//! Presses from other RTS remotes, heard by the radio when `rts.gpio.gdo2`
+//! is wired, and the physical remotes learned with `somfy rts learn`
+//! (`remotes.json`).
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::controller::BlindController;
+use crate::core::Channel;
+use crate::persist::{self, atomic_save_bytes};
+use crate::rts::frame::RtsCommand;
+
+const REMOTES_FILE: &str = "remotes.json";
+/// How long `POST /remotes/learn` waits for a press by default.
+pub(crate) const DEFAULT_LEARN_TIMEOUT_SECS: u64 = 30;
+/// How far past the last heard code a press may jump, as a motor allows for
+/// presses made out of its range.
+const ROLLING_CODE_WINDOW: u16 = 100;
+
+/// One decoded button press, published to SSE `/events` as `remote`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RemotePress {
+    pub remote_id: u32,
+    pub rolling_code: u16,
+    pub command: RtsCommand,
+    /// Name of the learned remote that sent it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Channels whose cached positions the press moved: the one our own
+    /// virtual remote drives, or those a learned remote is bound to.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<Channel>,
+    /// Unix seconds.
+    pub at: u64,
+}
+
+/// A physical remote recorded by `somfy rts learn`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LearnedRemote {
+    pub name: String,
+    /// 24-bit RTS address.
+    pub remote_id: u32,
+    /// Last rolling code heard from the remote.
+    pub rolling_code: u16,
+    /// Channels its presses move; empty until bound.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<Channel>,
+}
+
+/// A learned remote's press whose rolling code does not advance within
+/// [`ROLLING_CODE_WINDOW`], such as a replayed frame. A motor ignores it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StaleRollingCode {
+    pub name: String,
+    pub rolling_code: u16,
+    pub last: u16,
+}
+
+impl std::fmt::Display for StaleRollingCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rolling code {} from `{}` does not follow {}",
+            self.rolling_code, self.name, self.last
+        )
+    }
+}
+
+impl std::error::Error for StaleRollingCode {}
+
+/// No press was heard while learning a remote.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LearnTimedOut {
+    pub secs: u64,
+}
+
+impl std::fmt::Display for LearnTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no remote press heard within {}s", self.secs)
+    }
+}
+
+impl std::error::Error for LearnTimedOut {}
+
+/// `POST /remotes/learn` body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LearnRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// `PUT /remotes/{name}` body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct BindRequest {
+    pub channels: Vec<Channel>,
+}
+
+/// Learned remotes, saved to disk on every change.
+#[derive(Debug)]
+pub struct LearnedRemotes {
+    path: Option<PathBuf>,
+    remotes: StdMutex<Vec<LearnedRemote>>,
+}
+
+impl LearnedRemotes {
+    pub fn open() -> Self {
+        Self::open_at(persist::state_dir().join(REMOTES_FILE))
+    }
+
+    fn open_at(path: PathBuf) -> Self {
+        let remotes = load_remotes(&path);
+        Self {
+            path: Some(path),
+            remotes: StdMutex::new(remotes),
+        }
+    }
+
+    /// Remotes that are never written to disk.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            remotes: StdMutex::new(Vec::new()),
+        }
+    }
+
+    /// Learned remotes, in the order they were learned.
+    pub fn list(&self) -> Vec<LearnedRemote> {
+        self.remote_set().clone()
+    }
+
+    /// Record `remote`. Learning a name again replaces its address and
+    /// rolling code but keeps the channels it is bound to.
+    pub fn learn(&self, remote: LearnedRemote) -> Result<LearnedRemote> {
+        let mut remotes = self.remote_set();
+        if let Some(existing) = remotes
+            .iter()
+            .find(|existing| existing.remote_id == remote.remote_id && existing.name != remote.name)
+        {
+            bail!(
+                "remote {:06X} is already learned as `{}`",
+                remote.remote_id,
+                existing.name
+            );
+        }
+        let mut updated = remotes.clone();
+        let learned = match updated
+            .iter_mut()
+            .find(|existing| existing.name == remote.name)
+        {
+            Some(existing) => {
+                existing.remote_id = remote.remote_id;
+                existing.rolling_code = remote.rolling_code;
+                existing.clone()
+            }
+            None => {
+                updated.push(remote.clone());
+                remote
+            }
+        };
+        self.save(&updated)?;
+        *remotes = updated;
+        Ok(learned)
+    }
+
+    /// Bind the remote called `name` to `channels`, replacing its bindings.
+    pub fn bind(&self, name: &str, channels: Vec<Channel>) -> Result<LearnedRemote> {
+        let mut remotes = self.remote_set();
+        let mut updated = remotes.clone();
+        let Some(remote) = updated.iter_mut().find(|remote| remote.name == name) else {
+            bail!("no learned remote named `{name}`");
+        };
+        remote.channels = channels;
+        let bound = remote.clone();
+        self.save(&updated)?;
+        *remotes = updated;
+        Ok(bound)
+    }
+
+    /// Remove the remote called `name`, returning it if there was one.
+    pub fn forget(&self, name: &str) -> Result<Option<LearnedRemote>> {
+        let mut remotes = self.remote_set();
+        let Some(index) = remotes.iter().position(|remote| remote.name == name) else {
+            return Ok(None);
+        };
+        let mut updated = remotes.clone();
+        let removed = updated.remove(index);
+        self.save(&updated)?;
+        *remotes = updated;
+        Ok(Some(removed))
+    }
+
+    /// Note a press from `remote_id`, returning the learned remote if it is
+    /// one. The press only counts when its rolling code is 1 to
+    /// [`ROLLING_CODE_WINDOW`] past the last one, wrapping at `u16::MAX`.
+    /// Failing to persist the rolling code is logged, never surfaced: the
+    /// press itself already happened.
+    pub fn heard(
+        &self,
+        remote_id: u32,
+        rolling_code: u16,
+    ) -> Result<Option<LearnedRemote>, StaleRollingCode> {
+        let mut remotes = self.remote_set();
+        let Some(index) = remotes
+            .iter()
+            .position(|remote| remote.remote_id == remote_id)
+        else {
+            return Ok(None);
+        };
+        let last = remotes[index].rolling_code;
+        if !(1..=ROLLING_CODE_WINDOW).contains(&rolling_code.wrapping_sub(last)) {
+            return Err(StaleRollingCode {
+                name: remotes[index].name.clone(),
+                rolling_code,
+                last,
+            });
+        }
+        let mut updated = remotes.clone();
+        updated[index].rolling_code = rolling_code;
+        if let Err(e) = self.save(&updated) {
+            tracing::warn!("failed to save remote rolling code: {e:#}");
+        }
+        *remotes = updated;
+        Ok(Some(remotes[index].clone()))
+    }
+
+    fn remote_set(&self) -> std::sync::MutexGuard<'_, Vec<LearnedRemote>> {
+        self.remotes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn save(&self, remotes: &[LearnedRemote]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("creating state directory {}", dir.display()))?;
+        }
+        let bytes = serde_json::to_vec_pretty(remotes)?;
+        atomic_save_bytes(path, &bytes, true)
+            .with_context(|| format!("saving learned remotes to {}", path.display()))
+    }
+}
+
+fn load_remotes(path: &Path) -> Vec<LearnedRemote> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+        tracing::error!("ignoring unreadable {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Forward decoded frames to the controller. Returns `None` when the driver
+/// has no radio.
+pub(crate) fn start(controller: Arc<BlindController>) -> Option<JoinHandle<()>> {
+    let mut frames = controller.subscribe_remote_frames()?;
+    Some(tokio::spawn(async move {
+        loop {
+            match frames.recv().await {
+                Ok(frame) => {
+                    controller.observe_remote_frame(frame).await;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "dropped received RTS frames");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(name: &str, remote_id: u32) -> LearnedRemote {
+        LearnedRemote {
+            name: name.to_string(),
+            remote_id,
+            rolling_code: 10,
+            channels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn learned_remotes_persist_bindings_and_rolling_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(REMOTES_FILE);
+        let remotes = LearnedRemotes::open_at(path.clone());
+        remotes.learn(remote("hallway", 0x123456)).unwrap();
+        remotes
+            .bind("hallway", vec![Channel::L1, Channel::L2])
+            .unwrap();
+        assert_eq!(
+            remotes.heard(0x123456, 11).unwrap().unwrap().name,
+            "hallway"
+        );
+        assert_eq!(remotes.heard(0x654321, 1), Ok(None));
+
+        let reopened = LearnedRemotes::open_at(path).list();
+        assert_eq!(
+            reopened,
+            vec![LearnedRemote {
+                rolling_code: 11,
+                channels: vec![Channel::L1, Channel::L2],
+                ..remote("hallway", 0x123456)
+            }]
+        );
+    }
+
+    #[test]
+    fn replayed_and_far_ahead_rolling_codes_are_rejected() {
+        let remotes = LearnedRemotes::in_memory();
+        remotes
+            .learn(LearnedRemote {
+                rolling_code: u16::MAX - 1,
+                ..remote("hallway", 0x123456)
+            })
+            .unwrap();
+
+        assert!(remotes.heard(0x123456, u16::MAX).unwrap().is_some());
+        // Codes wrap around.
+        assert!(remotes.heard(0x123456, 3).unwrap().is_some());
+        let stale = remotes.heard(0x123456, 3).unwrap_err();
+        assert_eq!((stale.rolling_code, stale.last), (3, 3));
+        assert!(remotes.heard(0x123456, u16::MAX).is_err());
+        assert!(remotes.heard(0x123456, 104).is_err());
+        assert!(remotes.heard(0x123456, 103).unwrap().is_some());
+        assert_eq!(remotes.list()[0].rolling_code, 103);
+    }
+
+    #[test]
+    fn relearning_keeps_bindings_and_addresses_stay_unique() {
+        let remotes = LearnedRemotes::in_memory();
+        remotes.learn(remote("hallway", 0x123456)).unwrap();
+        remotes.bind("hallway", vec![Channel::L3]).unwrap();
+
+        let relearned = remotes.learn(remote("hallway", 0x0000AA)).unwrap();
+        assert_eq!(relearned.remote_id, 0x0000AA);
+        assert_eq!(relearned.channels, vec![Channel::L3]);
+
+        let err = remotes.learn(remote("kitchen", 0x0000AA)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "remote 0000AA is already learned as `hallway`"
+        );
+        assert!(remotes.bind("kitchen", vec![Channel::L1]).is_err());
+        assert_eq!(remotes.forget("hallway").unwrap().unwrap().name, "hallway");
+        assert!(remotes.list().is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Captured pulse trains: Flipper `.sub` RAW files and plain microsecond
+//! lists such as rtl_433 `.ook` pulse data.
+
+use anyhow::{bail, Context, Result};
+
+/// Level runs in capture order: `(high, duration_us)`.
+pub type Runs = Vec<(bool, u32)>;
+
+/// Parse a capture. Flipper `.sub` files must be `Protocol: RAW`. Any other
+/// text is read as durations, ignoring `;` and `#` comments: signed values
+/// give their level by sign (positive is high), unsigned values alternate
+/// starting high. Adjacent runs of the same level are merged.
+pub fn parse(text: &str) -> Result<Runs> {
+    let runs = if text.starts_with("Filetype: Flipper SubGhz") {
+        parse_sub(text)?
+    } else {
+        parse_durations(text)?
+    };
+    if runs.is_empty() {
+        bail!("capture contains no pulses");
+    }
+    Ok(runs)
+}
+
+fn parse_sub(text: &str) -> Result<Runs> {
+    let protocol = text
+        .lines()
+        .find_map(|line| line.strip_prefix("Protocol:"))
+        .map(str::trim);
+    if protocol != Some("RAW") {
+        bail!(
+            "only RAW .sub captures can be decoded (this file is protocol {})",
+            protocol.unwrap_or("unknown")
+        );
+    }
+    let mut values = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let Some(data) = line.strip_prefix("RAW_Data:") else {
+            continue;
+        };
+        for token in data.split_whitespace() {
+            values.push(
+                token
+                    .parse::<i64>()
+                    .with_context(|| format!("line {}: invalid duration `{token}`", index + 1))?,
+            );
+        }
+    }
+    signed_runs(&values)
+}
+
+fn parse_durations(text: &str) -> Result<Runs> {
+    let mut values = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split([';', '#']).next().unwrap_or_default();
+        for token in line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+        {
+            values.push(
+                token
+                    .parse::<i64>()
+                    .with_context(|| format!("line {}: invalid duration `{token}`", index + 1))?,
+            );
+        }
+    }
+    if values.iter().any(|value| *value < 0) {
+        return signed_runs(&values);
+    }
+    let mut runs = Runs::new();
+    for (index, value) in values.into_iter().enumerate() {
+        push_run(&mut runs, index % 2 == 0, duration(value)?);
+    }
+    Ok(runs)
+}
+
+fn signed_runs(values: &[i64]) -> Result<Runs> {
+    let mut runs = Runs::new();
+    for value in values {
+        if *value == 0 {
+            bail!("durations must not be zero");
+        }
+        push_run(&mut runs, *value > 0, duration(value.abs())?);
+    }
+    Ok(runs)
+}
+
+fn duration(value: i64) -> Result<u32> {
+    u32::try_from(value).with_context(|| format!("duration {value} µs is out of range"))
+}
+
+fn push_run(runs: &mut Runs, high: bool, duration_us: u32) {
+    match runs.last_mut() {
+        Some((level, total)) if *level == high => *total = total.saturating_add(duration_us),
+        _ => runs.push((high, duration_us)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flipper_raw_and_plain_duration_lists() {
+        let sub = "Filetype: Flipper SubGhz RAW File\nVersion: 1\nFrequency: 433420000\n\
+                   Preset: FuriHalSubGhzPresetOok650Async\nProtocol: RAW\n\
+                   RAW_Data: 4550 -640 640\nRAW_Data: 640 -1280\n";
+        assert_eq!(
+            parse(sub).unwrap(),
+            vec![(true, 4550), (false, 640), (true, 1280), (false, 1280)]
+        );
+
+        let ook = ";pulse data\n;freq1 433420000\n4550 640\n640 1280 # tail\n";
+        assert_eq!(
+            parse(ook).unwrap(),
+            vec![(true, 4550), (false, 640), (true, 640), (false, 1280)]
+        );
+        assert_eq!(
+            parse("-640, 640, 640").unwrap(),
+            vec![(false, 640), (true, 1280)]
+        );
+    }
+
+    #[test]
+    fn rejects_non_raw_sub_files_and_bad_durations() {
+        let key = "Filetype: Flipper SubGhz Key File\nProtocol: Somfy Telis\nKey: 00\n";
+        assert!(parse(key)
+            .unwrap_err()
+            .to_string()
+            .contains("protocol Somfy Telis"));
+        assert!(parse("640 abc").is_err());
+        assert!(parse("; nothing here\n").is_err());
+    }
+}
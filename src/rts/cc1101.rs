@@ -1,17 +1,25 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 #[cfg(not(target_os = "linux"))]
 use std::fs::File;
 use std::io::Write;
+use std::time::{Duration, Instant};
 
 const WRITE_BURST: u8 = 0x40;
+const READ_BURST: u8 = 0xC0;
 
+const REG_IOCFG2: u8 = 0x00;
 const REG_IOCFG0: u8 = 0x02;
 const REG_PKTCTRL0: u8 = 0x08;
+const REG_FSCTRL1: u8 = 0x0B;
 const REG_FREQ2: u8 = 0x0D;
 const REG_MDMCFG4: u8 = 0x10;
 const REG_MDMCFG3: u8 = 0x11;
 const REG_MDMCFG2: u8 = 0x12;
 const REG_MCSM0: u8 = 0x18;
+const REG_AGCCTRL2: u8 = 0x1B;
+const REG_AGCCTRL1: u8 = 0x1C;
+const REG_AGCCTRL0: u8 = 0x1D;
+const REG_FREND1: u8 = 0x21;
 const REG_FREND0: u8 = 0x22;
 const REG_FSCAL3: u8 = 0x23;
 const REG_FSCAL2: u8 = 0x24;
@@ -21,15 +29,31 @@ const REG_TEST2: u8 = 0x2C;
 const REG_TEST1: u8 = 0x2D;
 const REG_TEST0: u8 = 0x2E;
 const REG_PATABLE: u8 = 0x3E;
+const REG_TXFIFO: u8 = 0x3F;
+const STATUS_TXBYTES: u8 = 0x3A;
 
 const STROBE_SRES: u8 = 0x30;
+const STROBE_SRX: u8 = 0x34;
 const STROBE_STX: u8 = 0x35;
 const STROBE_SIDLE: u8 = 0x36;
+const STROBE_SFTX: u8 = 0x3B;
 
 const FREQ_433_42_26MHZ: [u8; 3] = [0x10, 0xAB, 0x85];
 
+/// Symbol period in packet mode: DRATE_E = 8, DRATE_M = 248 gives
+/// 12.497 kBaud, so every Somfy timing is a whole number of symbols to
+/// within 0.3% and a 640 µs half-symbol is exactly 8.
+pub const PACKET_SYMBOL_US: u32 = 80;
+const TX_FIFO_LEN: usize = 64;
+/// Refill once this many bytes are free, about 10 ms of air time.
+const TX_FIFO_REFILL: usize = 16;
+const TX_FIFO_POLL: Duration = Duration::from_millis(5);
+const TXBYTES_UNDERFLOW: u8 = 0x80;
+
 pub trait SpiDevice {
     fn write(&mut self, bytes: &[u8]) -> Result<()>;
+    /// Full-duplex transfer: the bytes clocked in while `bytes` go out.
+    fn transfer(&mut self, bytes: &[u8]) -> Result<Vec<u8>>;
 }
 
 #[cfg(not(target_os = "linux"))]
@@ -38,6 +62,12 @@ impl SpiDevice for File {
         Write::write_all(self, bytes)?;
         Ok(())
     }
+
+    /// The local test sink reads back as an idle radio with an empty FIFO.
+    fn transfer(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+        Write::write_all(self, bytes)?;
+        Ok(vec![0; bytes.len()])
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -46,6 +76,13 @@ impl SpiDevice for spidev::Spidev {
         Write::write_all(self, bytes)?;
         Ok(())
     }
+
+    fn transfer(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let mut received = vec![0; bytes.len()];
+        let mut transfer = spidev::SpidevTransfer::read_write(bytes, &mut received);
+        spidev::Spidev::transfer(self, &mut transfer)?;
+        Ok(received)
+    }
 }
 
 #[derive(Debug)]
@@ -89,6 +126,105 @@ impl<S: SpiDevice> Cc1101<S> {
         self.idle()
     }
 
+    /// Also demodulate in RX: raw async OOK data on GDO2, with a ~325 kHz
+    /// channel filter and AGC tuned for OOK. Async TX settings are unchanged;
+    /// packet TX is switched back to async serial mode at the init data rate.
+    pub fn configure_async_rx_on_gdo2(&mut self) -> Result<()> {
+        self.write_register(REG_IOCFG2, 0x0D)?;
+        self.write_register(REG_PKTCTRL0, 0x30)?;
+        self.write_register(REG_FSCTRL1, 0x06)?;
+        self.write_register(REG_MDMCFG4, 0x55)?;
+        self.write_register(REG_MDMCFG3, 0x83)?;
+        self.write_register(REG_AGCCTRL2, 0x03)?;
+        self.write_register(REG_AGCCTRL1, 0x00)?;
+        self.write_register(REG_AGCCTRL0, 0x91)?;
+        self.write_register(REG_FREND1, 0x56)
+    }
+
+    /// Switch TX to the FIFO at [`PACKET_SYMBOL_US`]: normal packet format
+    /// with infinite length, and no preamble, sync word, CRC or whitening, so
+    /// the FIFO bits go on air as they are. GDO0 is released.
+    pub fn configure_packet_tx(&mut self) -> Result<()> {
+        self.write_register(REG_IOCFG0, 0x2E)?;
+        self.write_register(REG_PKTCTRL0, 0x02)?;
+        self.write_register(REG_MDMCFG4, 0xF8)?;
+        self.write_register(REG_MDMCFG3, 0xF8)
+    }
+
+    /// Transmit `bytes` through the TX FIFO, refilling it as it drains, then
+    /// return to idle. Infinite length mode ends in a FIFO underflow once
+    /// the last byte is out, which is why the FIFO is flushed afterwards.
+    pub fn transmit_fifo(&mut self, bytes: &[u8]) -> Result<()> {
+        let result = self.stream_fifo(bytes);
+        let idle_result = self.idle().and_then(|()| self.strobe(STROBE_SFTX));
+        result?;
+        idle_result
+    }
+
+    fn stream_fifo(&mut self, bytes: &[u8]) -> Result<()> {
+        self.strobe(STROBE_SFTX)?;
+        let (first, mut rest) = bytes.split_at(bytes.len().min(TX_FIFO_LEN));
+        self.write_burst(REG_TXFIFO, first)?;
+        self.tx()?;
+        while !rest.is_empty() {
+            let queued = self.tx_fifo_bytes()?;
+            let room = TX_FIFO_LEN.saturating_sub(queued);
+            if room < TX_FIFO_REFILL.min(rest.len()) {
+                std::thread::sleep(TX_FIFO_POLL);
+                continue;
+            }
+            let (chunk, remaining) = rest.split_at(room.min(rest.len()));
+            self.write_burst(REG_TXFIFO, chunk)?;
+            rest = remaining;
+        }
+
+        let byte_time = Duration::from_micros(u64::from(PACKET_SYMBOL_US) * 8);
+        let deadline = Instant::now() + byte_time * TX_FIFO_LEN as u32 * 2;
+        loop {
+            match self.read_status(STATUS_TXBYTES)? {
+                status if status & TXBYTES_UNDERFLOW != 0 || status == 0 => break,
+                _ if Instant::now() > deadline => bail!("CC1101 TX FIFO did not drain"),
+                _ => std::thread::sleep(TX_FIFO_POLL),
+            }
+        }
+        // The last byte leaves the shift register after the FIFO empties.
+        std::thread::sleep(byte_time);
+        Ok(())
+    }
+
+    /// Bytes waiting in the TX FIFO. An underflow while data is still
+    /// queued means SPI fell behind the air rate and the frame is corrupt.
+    fn tx_fifo_bytes(&mut self) -> Result<usize> {
+        let status = self.read_status(STATUS_TXBYTES)?;
+        if status & TXBYTES_UNDERFLOW != 0 {
+            bail!("CC1101 TX FIFO underflowed while streaming");
+        }
+        Ok(usize::from(status & !TXBYTES_UNDERFLOW))
+    }
+
+    /// Status registers can change mid-read (CC1101 errata); read until two
+    /// consecutive values agree.
+    fn read_status(&mut self, address: u8) -> Result<u8> {
+        let mut last = None;
+        for _ in 0..4 {
+            let value = self
+                .spi
+                .transfer(&[address | READ_BURST, 0])?
+                .get(1)
+                .copied()
+                .unwrap_or_default();
+            if last == Some(value) {
+                return Ok(value);
+            }
+            last = Some(value);
+        }
+        bail!("CC1101 status register {address:#04x} did not settle")
+    }
+
+    pub fn rx(&mut self) -> Result<()> {
+        self.strobe(STROBE_SRX)
+    }
+
     pub fn tx(&mut self) -> Result<()> {
         self.strobe(STROBE_STX)
     }
@@ -127,6 +263,11 @@ mod tests {
             self.writes.push(bytes.to_vec());
             Ok(())
         }
+
+        /// Reads back as a FIFO that drains instantly.
+        fn transfer(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+            Ok(vec![0; bytes.len()])
+        }
     }
 
     #[test]
@@ -150,6 +291,47 @@ mod tests {
         assert_eq!(writes.last().unwrap(), &vec![STROBE_SIDLE]);
     }
 
+    #[test]
+    fn configures_async_rx_output_on_gdo2() {
+        let mut cc1101 = Cc1101::new(FakeSpi::default());
+
+        cc1101.configure_async_rx_on_gdo2().unwrap();
+        cc1101.rx().unwrap();
+
+        let writes = cc1101.into_inner().writes;
+        assert_eq!(writes[0], vec![REG_IOCFG2, 0x0D]);
+        assert!(writes.contains(&vec![REG_PKTCTRL0, 0x30]));
+        assert!(writes.contains(&vec![REG_MDMCFG4, 0x55]));
+        assert!(writes.contains(&vec![REG_MDMCFG3, 0x83]));
+        assert_eq!(writes.last().unwrap(), &vec![STROBE_SRX]);
+    }
+
+    #[test]
+    fn streams_packet_tx_through_the_fifo() {
+        let mut cc1101 = Cc1101::new(FakeSpi::default());
+        let bytes: Vec<u8> = (0..=199).collect();
+
+        cc1101.configure_packet_tx().unwrap();
+        cc1101.transmit_fifo(&bytes).unwrap();
+
+        let writes = cc1101.into_inner().writes;
+        assert!(writes.contains(&vec![REG_PKTCTRL0, 0x02]));
+        assert!(writes.contains(&vec![REG_MDMCFG4, 0xF8]));
+        let stx = writes.iter().position(|w| w == &vec![STROBE_STX]).unwrap();
+        let bursts: Vec<&Vec<u8>> = writes
+            .iter()
+            .filter(|w| w[0] == REG_TXFIFO | WRITE_BURST)
+            .collect();
+        assert_eq!(bursts[0].len(), 1 + TX_FIFO_LEN);
+        assert_eq!(writes[stx - 1], *bursts[0]);
+        let streamed: Vec<u8> = bursts.iter().flat_map(|w| w[1..].to_vec()).collect();
+        assert_eq!(streamed, bytes);
+        assert_eq!(
+            writes[writes.len() - 2..],
+            [vec![STROBE_SIDLE], vec![STROBE_SFTX]]
+        );
+    }
+
     #[test]
     fn exposes_tx_and_idle_strobes() {
         let mut cc1101 = Cc1101::new(FakeSpi::default());
@@ -0,0 +1,222 @@
+//! Manchester demodulation of received RTS frames, the inverse of
+//! [`crate::rts::waveform`].
+
+use serde::Serialize;
+
+use crate::rts::frame::{DecodedFrame, RtsFrame, FRAME_LEN};
+use crate::rts::waveform::{HARDWARE_SYNC_HIGH_US, MANCHESTER_HALF_SYMBOL_US, WAKEUP_HIGH_US};
+
+const FRAME_HALF_SYMBOLS: usize = FRAME_LEN * 8 * 2;
+
+/// Highs in this range are taken as the software sync (nominally 4550 µs).
+/// Hardware sync (2560 µs) and wake-up (9415 µs) pulses fall outside it.
+const SOFTWARE_SYNC_MIN_US: u32 = 3_500;
+const SOFTWARE_SYNC_MAX_US: u32 = 6_000;
+
+/// One frame found in a capture, with the preamble that led up to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CapturedFrame {
+    /// A wake-up pulse preceded the frame; only the first of a press has one.
+    pub wakeup: bool,
+    /// Hardware sync pulses: 2 on the first frame, 7 on repeats.
+    pub hardware_syncs: usize,
+    /// Bytes as received, still obfuscated.
+    #[serde(serialize_with = "serialize_hex")]
+    pub bytes: [u8; FRAME_LEN],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<DecodedFrame>,
+    /// Why the bytes are not a valid frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn serialize_hex<S: serde::Serializer>(
+    bytes: &[u8; FRAME_LEN],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(bytes))
+}
+
+/// Find and decode every frame in a capture of level runs. A receiver can
+/// miss the wake-up and the first hardware syncs, so only the software sync
+/// is required; the rest of the preamble is reported as seen.
+pub fn decode_capture(runs: impl IntoIterator<Item = (bool, u32)>) -> Vec<CapturedFrame> {
+    let mut demodulator = Demodulator::new();
+    let mut wakeup = false;
+    let mut hardware_syncs = 0;
+    let mut frames = Vec::new();
+    for (high, duration_us) in runs {
+        if high && within(duration_us, WAKEUP_HIGH_US) {
+            wakeup = true;
+            hardware_syncs = 0;
+        } else if high && within(duration_us, HARDWARE_SYNC_HIGH_US) {
+            hardware_syncs += 1;
+        }
+        let Some(bytes) = demodulator.push(high, duration_us) else {
+            continue;
+        };
+        let (frame, error) = match RtsFrame::decode(bytes) {
+            Ok(frame) => (Some(frame), None),
+            Err(e) => (None, Some(format!("{e:#}"))),
+        };
+        frames.push(CapturedFrame {
+            wakeup,
+            hardware_syncs,
+            bytes,
+            frame,
+            error,
+        });
+        wakeup = false;
+        hardware_syncs = 0;
+    }
+    frames
+}
+
+/// Within 30% of `nominal_us`.
+fn within(duration_us: u32, nominal_us: u32) -> bool {
+    (nominal_us * 7 / 10..=nominal_us * 13 / 10).contains(&duration_us)
+}
+
+/// Accepts a level held for `duration_us` as one or two half-symbols.
+/// Handheld remotes run a few percent off the nominal 640 µs.
+fn half_symbols(duration_us: u32) -> Option<usize> {
+    let half = MANCHESTER_HALF_SYMBOL_US;
+    match duration_us {
+        d if d < half / 2 => None,
+        d if d < half * 3 / 2 => Some(1),
+        d if d < half * 5 / 2 => Some(2),
+        _ => None,
+    }
+}
+
+/// Turns the received level, one run at a time, back into obfuscated frame
+/// bytes. Anything that does not fit the Somfy framing resets it to hunt
+/// for the next software sync.
+#[derive(Debug, Default)]
+pub struct Demodulator {
+    /// Half-symbol levels since the software sync; `None` while hunting.
+    halves: Option<Vec<bool>>,
+}
+
+impl Demodulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a run of `high` that lasted `duration_us`. Returns the frame
+    /// bytes once its last bit has been received.
+    pub fn push(&mut self, high: bool, duration_us: u32) -> Option<[u8; FRAME_LEN]> {
+        if high && (SOFTWARE_SYNC_MIN_US..=SOFTWARE_SYNC_MAX_US).contains(&duration_us) {
+            // The sync low is one half-symbol ahead of the first bit.
+            self.halves = Some(Vec::with_capacity(1 + FRAME_HALF_SYMBOLS));
+            return None;
+        }
+        let halves = self.halves.as_mut()?;
+        let needed = 1 + FRAME_HALF_SYMBOLS - halves.len();
+        let count = match half_symbols(duration_us) {
+            Some(count) if count <= needed => count,
+            // A trailing 0 bit ends low and runs into the inter-frame gap.
+            _ if !high && needed == 1 && duration_us > MANCHESTER_HALF_SYMBOL_US => 1,
+            _ => {
+                self.halves = None;
+                return None;
+            }
+        };
+        halves.extend(std::iter::repeat_n(high, count));
+        if halves.len() < 1 + FRAME_HALF_SYMBOLS {
+            return None;
+        }
+        let halves = self.halves.take()?;
+        manchester_bytes(&halves[1..])
+    }
+}
+
+/// Low-then-high is a 1, high-then-low a 0, most significant bit first.
+fn manchester_bytes(halves: &[bool]) -> Option<[u8; FRAME_LEN]> {
+    let mut bytes = [0u8; FRAME_LEN];
+    for (index, pair) in halves.chunks_exact(2).enumerate() {
+        let bit = match pair {
+            [false, true] => 1,
+            [true, false] => 0,
+            _ => return None,
+        };
+        bytes[index / 8] |= bit << (7 - index % 8);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rts::frame::{RtsCommand, RtsFrame};
+    use crate::rts::waveform;
+
+    /// Level runs a receiver would see for `pulses`, scaled by `percent`.
+    fn runs(pulses: &[waveform::GpioPulse], percent: u32) -> Vec<(bool, u32)> {
+        let mut runs: Vec<(bool, u32)> = Vec::new();
+        for pulse in pulses {
+            let high = pulse.gpio_on != 0;
+            let duration = pulse.us_delay * percent / 100;
+            match runs.last_mut() {
+                Some((level, total)) if *level == high => *total += duration,
+                _ => runs.push((high, duration)),
+            }
+        }
+        runs
+    }
+
+    #[test]
+    fn demodulates_every_repeat_of_a_transmitted_waveform() {
+        for (command, rolling_code) in [(RtsCommand::Up, 0x00A7), (RtsCommand::Down, 0x1234)] {
+            let frame = RtsFrame::encode(command, rolling_code, 0x123456).unwrap();
+            for percent in [94, 100, 106] {
+                let mut demodulator = Demodulator::new();
+                let frames: Vec<[u8; FRAME_LEN]> = runs(&waveform::build(frame, 18), percent)
+                    .into_iter()
+                    .filter_map(|(high, duration)| demodulator.push(high, duration))
+                    .collect();
+                assert_eq!(frames, vec![frame.bytes(); waveform::FRAME_COUNT]);
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_captures_with_their_preamble() {
+        let frame = RtsFrame::encode(RtsCommand::Down, 42, 0x123456).unwrap();
+        let mut pulses = waveform::build(frame, 18);
+        // Flip the last repeat's final bit: its two halves precede the gap.
+        let last_bit = pulses.len() - 3;
+        pulses.swap(last_bit, last_bit + 1);
+
+        let frames = decode_capture(runs(&pulses, 103));
+        assert_eq!(frames.len(), 4);
+        assert_eq!((frames[0].wakeup, frames[0].hardware_syncs), (true, 2));
+        assert_eq!((frames[1].wakeup, frames[1].hardware_syncs), (false, 7));
+        let decoded = frames[0].frame.unwrap();
+        assert_eq!(
+            (decoded.command, decoded.rolling_code, decoded.remote_id),
+            (RtsCommand::Down, 42, 0x123456)
+        );
+        assert_eq!(frames[3].frame, None);
+        assert_eq!(
+            frames[3].error.as_deref(),
+            Some("RTS frame checksum mismatch")
+        );
+    }
+
+    #[test]
+    fn noise_resets_to_the_next_software_sync() {
+        let frame = RtsFrame::encode(RtsCommand::Stop, 7, 0xABCDEF).unwrap();
+        let mut demodulator = Demodulator::new();
+        assert_eq!(demodulator.push(true, 4_550), None);
+        assert_eq!(demodulator.push(false, 640), None);
+        assert_eq!(demodulator.push(true, 100), None);
+        assert_eq!(demodulator.push(false, 640), None);
+
+        let decoded = runs(&waveform::build(frame, 18), 100)
+            .into_iter()
+            .find_map(|(high, duration)| demodulator.push(high, duration))
+            .unwrap();
+        assert_eq!(RtsFrame::decode(decoded).unwrap().command, RtsCommand::Stop);
+    }
+}
@@ -0,0 +1,136 @@
+//! Waveforms handed to other transmitters (`somfy rts export`): the pulse
+//! train the driver would clock out, as a Flipper `.sub` RAW file or a list
+//! of signed microsecond durations.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::core::Channel;
+use crate::rts::frame::{RtsCommand, RtsFrame};
+use crate::rts::state::RtsStateStore;
+use crate::rts::waveform;
+
+/// Carrier frequency written to `.sub` files.
+const FREQUENCY_HZ: u32 = 433_420_000;
+/// Flipper firmware splits `RAW_Data` at this many values per line.
+const SUB_VALUES_PER_LINE: usize = 512;
+
+/// `POST /rts/export` body.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportRequest {
+    pub channel: Channel,
+    pub command: RtsCommand,
+    #[serde(default)]
+    pub long: bool,
+}
+
+/// One exported press. Its rolling code has been consumed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedWaveform {
+    pub channel: Channel,
+    pub command: RtsCommand,
+    pub long: bool,
+    pub remote_id: u32,
+    pub rolling_code: u16,
+    /// Level runs in order: positive is high, negative is low.
+    pub durations_us: Vec<i64>,
+}
+
+/// Reserve the next rolling code for `channel`, build the waveform
+/// [`waveform::build`] (or [`waveform::build_long`]) would transmit, and
+/// commit the code. Nothing is transmitted, so the code is consumed as soon
+/// as the waveform exists: whoever replays it may or may not send it.
+pub fn reserve(
+    state: &mut RtsStateStore,
+    channel: Channel,
+    command: RtsCommand,
+    long: bool,
+) -> Result<ExportedWaveform> {
+    let rolling_code = state.reserve_rolling_code(channel)?;
+    let remote_id = state.channel(channel)?.remote_id;
+    let frame = RtsFrame::encode(command, rolling_code, remote_id)?;
+    // The GPIO only sets the pigpio mask, which is not exported.
+    let pulses = if long {
+        waveform::build_long(frame, 0)
+    } else {
+        waveform::build(frame, 0)
+    };
+    state.commit_rolling_code(channel, rolling_code)?;
+
+    let mut durations_us: Vec<i64> = Vec::new();
+    for pulse in pulses {
+        let duration = i64::from(pulse.us_delay);
+        let signed = if pulse.gpio_on != 0 {
+            duration
+        } else {
+            -duration
+        };
+        match durations_us.last_mut() {
+            Some(last) if last.signum() == signed.signum() => *last += signed,
+            _ => durations_us.push(signed),
+        }
+    }
+    Ok(ExportedWaveform {
+        channel,
+        command,
+        long,
+        remote_id,
+        rolling_code,
+        durations_us,
+    })
+}
+
+impl ExportedWaveform {
+    /// Flipper Zero SubGhz RAW file.
+    pub fn to_sub(&self) -> String {
+        let mut sub = format!(
+            "Filetype: Flipper SubGhz RAW File\nVersion: 1\nFrequency: {FREQUENCY_HZ}\n\
+             Preset: FuriHalSubGhzPresetOok650Async\nProtocol: RAW\n"
+        );
+        for line in self.durations_us.chunks(SUB_VALUES_PER_LINE) {
+            let values: Vec<String> = line.iter().map(i64::to_string).collect();
+            sub.push_str(&format!("RAW_Data: {}\n", values.join(" ")));
+        }
+        sub
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rts::capture;
+    use crate::rts::decoder::decode_capture;
+    use crate::rts::state::{RtsState, STATE_FILE};
+
+    #[test]
+    fn exports_decodable_waveforms_and_never_reuses_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE);
+        let mut state = RtsStateStore::load_or_init(&path, 4).unwrap();
+
+        let first = reserve(&mut state, Channel::L2, RtsCommand::Up, false).unwrap();
+        let second = reserve(&mut state, Channel::L2, RtsCommand::Up, false).unwrap();
+        assert_eq!(second.rolling_code, first.rolling_code + 1);
+
+        let frames = decode_capture(capture::parse(&first.to_sub()).unwrap());
+        assert_eq!(frames.len(), waveform::FRAME_COUNT);
+        let frame = frames[0].frame.unwrap();
+        assert_eq!(
+            (frame.command, frame.rolling_code, frame.remote_id),
+            (RtsCommand::Up, first.rolling_code, first.remote_id)
+        );
+        let long = reserve(&mut state, Channel::L2, RtsCommand::Prog, true).unwrap();
+        let frames = decode_capture(capture::parse(&long.to_sub()).unwrap());
+        assert_eq!(frames.len(), waveform::FRAME_COUNT_LONG);
+
+        // A restart resumes past every code handed out before it.
+        drop(state);
+        let mut reloaded = RtsStateStore::load_or_init(&path, 4).unwrap();
+        let next = reserve(&mut reloaded, Channel::L2, RtsCommand::Down, false).unwrap();
+        assert!(next.rolling_code > long.rolling_code);
+        let saved: RtsState =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved.channels[&Channel::L2].reserved_until > next.rolling_code);
+    }
+}
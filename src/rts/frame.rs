@@ -1,11 +1,13 @@
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 
 use crate::core::Command;
 
 pub const FRAME_LEN: usize = 7;
 pub const DEFAULT_KEY: u8 = 0xA7;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RtsCommand {
     Stop,
     Up,
@@ -22,6 +24,16 @@ impl RtsCommand {
             Self::Prog => 0x8,
         }
     }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x1 => Some(Self::Stop),
+            0x2 => Some(Self::Up),
+            0x4 => Some(Self::Down),
+            0x8 => Some(Self::Prog),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<Command> for RtsCommand {
@@ -29,7 +41,8 @@ impl TryFrom<Command> for RtsCommand {
 
     fn try_from(command: Command) -> Result<Self> {
         match command {
-            Command::Stop => Ok(Self::Stop),
+            // The RTS middle button is both Stop and My; the motor decides.
+            Command::Stop | Command::My => Ok(Self::Stop),
             Command::Up => Ok(Self::Up),
             Command::Down => Ok(Self::Down),
             Command::Prog | Command::ProgLong => Ok(Self::Prog),
@@ -38,6 +51,17 @@ impl TryFrom<Command> for RtsCommand {
     }
 }
 
+impl From<RtsCommand> for Command {
+    fn from(command: RtsCommand) -> Self {
+        match command {
+            RtsCommand::Stop => Self::Stop,
+            RtsCommand::Up => Self::Up,
+            RtsCommand::Down => Self::Down,
+            RtsCommand::Prog => Self::Prog,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct RtsFrame {
     bytes: [u8; FRAME_LEN],
@@ -66,6 +90,37 @@ impl RtsFrame {
     pub fn bytes(self) -> [u8; FRAME_LEN] {
         self.bytes
     }
+
+    /// Inverse of [`Self::encode`] for received bytes: de-obfuscate, verify
+    /// the key nibble and checksum, and split out the fields.
+    pub fn decode(bytes: [u8; FRAME_LEN]) -> Result<DecodedFrame> {
+        let mut plain = bytes;
+        deobfuscate(&mut plain);
+        if plain[0] >> 4 != DEFAULT_KEY >> 4 {
+            bail!("unexpected RTS key byte {:#04x}", plain[0]);
+        }
+        // The checksum nibble cancels the XOR of every other nibble.
+        if checksum(plain) != 0 {
+            bail!("RTS frame checksum mismatch");
+        }
+        let code = plain[1] >> 4;
+        let Some(command) = RtsCommand::from_code(code) else {
+            bail!("unsupported RTS command code {code:#x}");
+        };
+        Ok(DecodedFrame {
+            command,
+            rolling_code: u16::from_be_bytes([plain[2], plain[3]]),
+            remote_id: u32::from_be_bytes([0, plain[4], plain[5], plain[6]]),
+        })
+    }
+}
+
+/// Fields of a received frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DecodedFrame {
+    pub command: RtsCommand,
+    pub rolling_code: u16,
+    pub remote_id: u32,
 }
 
 pub fn checksum(bytes: [u8; FRAME_LEN]) -> u8 {
@@ -78,6 +133,12 @@ pub fn obfuscate(bytes: &mut [u8; FRAME_LEN]) {
     }
 }
 
+pub fn deobfuscate(bytes: &mut [u8; FRAME_LEN]) {
+    for i in (1..FRAME_LEN).rev() {
+        bytes[i] ^= bytes[i - 1];
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,6 +153,7 @@ mod tests {
             RtsCommand::try_from(Command::Stop).unwrap(),
             RtsCommand::Stop
         );
+        assert_eq!(RtsCommand::try_from(Command::My).unwrap(), RtsCommand::Stop);
         assert!(RtsCommand::try_from(Command::Select).is_err());
     }
 
@@ -114,6 +176,33 @@ mod tests {
         assert_eq!(frame.bytes(), [0xA7, 0x82, 0x82, 0x25, 0x37, 0x03, 0x55]);
     }
 
+    #[test]
+    fn decodes_encoded_frames_and_rejects_corruption() {
+        for command in [
+            RtsCommand::Stop,
+            RtsCommand::Up,
+            RtsCommand::Down,
+            RtsCommand::Prog,
+        ] {
+            let frame = RtsFrame::encode(command, 0xBEEF, 0x123456).unwrap();
+            assert_eq!(
+                RtsFrame::decode(frame.bytes()).unwrap(),
+                DecodedFrame {
+                    command,
+                    rolling_code: 0xBEEF,
+                    remote_id: 0x123456,
+                }
+            );
+        }
+
+        let mut bytes = RtsFrame::encode(RtsCommand::Up, 0x00A7, 0x123456)
+            .unwrap()
+            .bytes();
+        bytes[6] ^= 0x10;
+        assert!(RtsFrame::decode(bytes).is_err());
+        assert!(RtsFrame::decode([0; FRAME_LEN]).is_err());
+    }
+
     #[test]
     fn rejects_invalid_remote_ids() {
         assert!(RtsFrame::encode(RtsCommand::Up, 1, 0).is_err());
@@ -1,4 +1,7 @@
+pub mod capture;
 pub mod cc1101;
+pub mod decoder;
+pub mod export;
 pub mod frame;
 pub mod pigpio;
 pub mod state;
@@ -13,7 +13,9 @@ pub const STATE_FILE: &str = "rts.json";
 pub const SCHEMA_VERSION: u32 = 1;
 pub const DEFAULT_RESERVE_SIZE: u16 = 16;
 
-const CHANNELS: [Channel; 5] = [
+/// Virtual remotes created with a fresh state file. Channels beyond L4 get
+/// their own remote on first use (see [`RtsStateStore::reserve_rolling_code`]).
+const DEFAULT_CHANNELS: [Channel; 5] = [
     Channel::L1,
     Channel::L2,
     Channel::L3,
@@ -95,6 +97,14 @@ impl RtsStateStore {
             .ok_or_else(|| anyhow::anyhow!("missing RTS channel state for {channel}"))
     }
 
+    pub fn channel_for_remote(&self, remote_id: u32) -> Option<Channel> {
+        self.state
+            .channels
+            .iter()
+            .find(|(_, state)| state.remote_id == remote_id)
+            .map(|(channel, _)| *channel)
+    }
+
     pub fn next_on_wire(&self, channel: Channel) -> Result<u16> {
         self.next_on_wire
             .get(&channel)
@@ -103,6 +113,7 @@ impl RtsStateStore {
     }
 
     pub fn reserve_rolling_code(&mut self, channel: Channel) -> Result<u16> {
+        self.ensure_channel(channel)?;
         let next = self.next_on_wire(channel)?;
         let reserved_until = self.channel(channel)?.reserved_until;
         if next == reserved_until {
@@ -117,6 +128,31 @@ impl RtsStateStore {
         Ok(next)
     }
 
+    /// Allocate a new virtual remote for a channel that has never transmitted.
+    fn ensure_channel(&mut self, channel: Channel) -> Result<()> {
+        if self.state.channels.contains_key(&channel) {
+            return Ok(());
+        }
+        let mut used = self
+            .state
+            .channels
+            .values()
+            .map(|state| state.remote_id)
+            .collect();
+        let remote_id = unique_remote_id(&mut used);
+        self.state.channels.insert(
+            channel,
+            RtsChannelState {
+                remote_id,
+                reserved_until: 1,
+            },
+        );
+        self.next_on_wire.insert(channel, 1);
+        save_to(&self.path, &self.state)?;
+        tracing::info!(%channel, remote_id, "allocated RTS virtual remote");
+        Ok(())
+    }
+
     pub fn commit_rolling_code(&mut self, channel: Channel, code: u16) -> Result<()> {
         let next = self.next_on_wire(channel)?;
         if code != next {
@@ -130,7 +166,7 @@ impl RtsStateStore {
 impl RtsState {
     pub fn generate() -> Self {
         let mut used = BTreeSet::new();
-        let channels = CHANNELS
+        let channels = DEFAULT_CHANNELS
             .into_iter()
             .map(|channel| {
                 let remote_id = unique_remote_id(&mut used);
@@ -175,11 +211,7 @@ fn validate_state(state: &RtsState) -> Result<()> {
     }
 
     let mut remote_ids = BTreeSet::new();
-    for channel in CHANNELS {
-        let state = state
-            .channels
-            .get(&channel)
-            .ok_or_else(|| anyhow::anyhow!("RTS state missing channel {channel}"))?;
+    for (channel, state) in &state.channels {
         if state.remote_id == 0 || state.remote_id > 0xFF_FFFF {
             bail!("RTS state channel {channel} has invalid remote_id");
         }
@@ -225,7 +257,7 @@ mod tests {
 
         assert_eq!(store.selected_channel(), Channel::L1);
         assert!(state_path(&dir).exists());
-        for channel in CHANNELS {
+        for channel in DEFAULT_CHANNELS {
             assert!(store.channel(channel).unwrap().remote_id > 0);
             assert_eq!(store.channel(channel).unwrap().reserved_until, 1);
             assert_eq!(store.next_on_wire(channel).unwrap(), 1);
@@ -253,7 +285,7 @@ mod tests {
         let state = RtsState::generate();
         let ids: BTreeSet<u32> = state.channels.values().map(|c| c.remote_id).collect();
 
-        assert_eq!(ids.len(), CHANNELS.len());
+        assert_eq!(ids.len(), DEFAULT_CHANNELS.len());
         assert!(ids.iter().all(|id| (1..=0xFF_FFFF).contains(id)));
     }
 
@@ -300,6 +332,25 @@ mod tests {
         assert_eq!(store.next_on_wire(Channel::L2).unwrap(), 1);
     }
 
+    #[test]
+    fn channels_beyond_defaults_get_a_persisted_unique_remote_on_first_reserve() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = state_path(&dir);
+        let mut store = RtsStateStore::load_or_init(&path, 16).unwrap();
+        let l7 = Channel::Individual(7);
+        assert!(store.channel(l7).is_err());
+
+        assert_eq!(store.reserve_rolling_code(l7).unwrap(), 1);
+        let remote_id = store.channel(l7).unwrap().remote_id;
+        assert!(DEFAULT_CHANNELS
+            .iter()
+            .all(|channel| store.channel(*channel).unwrap().remote_id != remote_id));
+
+        let reloaded = RtsStateStore::load_or_init(&path, 16).unwrap();
+        assert_eq!(reloaded.channel(l7).unwrap().remote_id, remote_id);
+        assert_eq!(reloaded.channel(l7).unwrap().reserved_until, 17);
+    }
+
     #[test]
     fn rolling_codes_wrap_at_u16_max() {
         let dir = tempfile::tempdir().unwrap();
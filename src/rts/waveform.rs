@@ -102,6 +102,28 @@ fn append_manchester_bit(pulses: &mut Vec<GpioPulse>, mask: u32, bit: u8) {
     }
 }
 
+/// Sample `pulses` into bits of `symbol_us` each, MSB first, for a radio
+/// that shifts its TX FIFO out at that rate. Run edges are rounded against
+/// the running total, so rounding never accumulates; the last byte is
+/// padded low.
+pub fn fifo_bytes(pulses: &[GpioPulse], symbol_us: u32) -> Vec<u8> {
+    let symbol_us = u64::from(symbol_us);
+    let mut bits: Vec<bool> = Vec::new();
+    let mut elapsed_us = 0u64;
+    for pulse in pulses {
+        elapsed_us += u64::from(pulse.us_delay);
+        let end = ((elapsed_us + symbol_us / 2) / symbol_us) as usize;
+        bits.resize(end.max(bits.len()), pulse.gpio_on != 0);
+    }
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0u8, |byte, (index, bit)| {
+                byte | (u8::from(*bit) << (7 - index))
+            })
+        })
+        .collect()
+}
+
 fn high(pulses: &mut Vec<GpioPulse>, mask: u32, us_delay: u32) {
     pulses.push(GpioPulse {
         gpio_on: mask,
@@ -181,6 +203,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fifo_bytes_sample_the_waveform_at_the_symbol_rate() {
+        use crate::rts::decoder::decode_capture;
+
+        let pulses = build(test_frame(), GPIO);
+        let bytes = fifo_bytes(&pulses, 80);
+        let total_us: u32 = pulses.iter().map(|pulse| pulse.us_delay).sum();
+        assert_eq!(bytes.len(), (total_us / 80).div_ceil(8) as usize);
+        // Wake-up high: 9415 µs rounds to 118 symbols.
+        assert_eq!(&bytes[..14], &[0xFF; 14]);
+        assert_eq!(bytes[14], 0xFC);
+
+        let mut runs: Vec<(bool, u32)> = Vec::new();
+        for bit in 0..bytes.len() * 8 {
+            let high = (bytes[bit / 8] >> (7 - bit % 8)) & 1 == 1;
+            match runs.last_mut() {
+                Some((level, total)) if *level == high => *total += 80,
+                _ => runs.push((high, 80)),
+            }
+        }
+        let frames = decode_capture(runs);
+        assert_eq!(frames.len(), FRAME_COUNT);
+        assert!(frames
+            .iter()
+            .all(|frame| frame.bytes == test_frame().bytes() && frame.frame.is_some()));
+    }
+
     #[test]
     fn repeat_frame_omits_wakeup_and_uses_seven_hardware_sync_cycles() {
         let pulses = build_n(test_frame(), GPIO, 2);
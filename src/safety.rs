@@ -0,0 +1,235 @@
+//! `[safety]` inputs: wind, rain, or other dry contacts that retract blinds
+//! and hold them up until the condition has cleared.
+
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::config::{SafetyInputOptions, SafetyOptions};
+use crate::controller::BlindController;
+use crate::core::Channel;
+use crate::gpio;
+
+/// First delay before retrying a failed retract; doubles up to
+/// [`RETRACT_RETRY_MAX`] until the retract succeeds or the input clears.
+const RETRACT_RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRACT_RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Published state of one `[[safety.inputs]]` entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SafetyStatus {
+    pub name: String,
+    pub channels: Vec<Channel>,
+    pub active: bool,
+    /// Unix seconds the input tripped, while active.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+}
+
+/// A downward move refused while a safety input holds the blind up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafetyHold {
+    pub channel: Channel,
+    pub input: String,
+}
+
+impl fmt::Display for SafetyHold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is held up while the `{}` safety input is active",
+            self.channel, self.input
+        )
+    }
+}
+
+impl std::error::Error for SafetyHold {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Transition {
+    Trip,
+    Clear,
+}
+
+/// Input level filter: trips once the input has been active for `debounce`,
+/// and clears once it has been inactive for `hold_off`.
+#[derive(Debug)]
+pub(crate) struct Debouncer {
+    debounce: Duration,
+    hold_off: Duration,
+    level: bool,
+    tripped: bool,
+    pending: Option<Instant>,
+}
+
+impl Debouncer {
+    pub(crate) fn new(debounce: Duration, hold_off: Duration) -> Self {
+        Self {
+            debounce,
+            hold_off,
+            level: false,
+            tripped: false,
+            pending: None,
+        }
+    }
+
+    /// Record the input level read at `now`.
+    pub(crate) fn set_level(&mut self, active: bool, now: Instant) {
+        if active == self.level {
+            return;
+        }
+        self.level = active;
+        // A level that matches the current state cancels the pending change.
+        self.pending = (active != self.tripped)
+            .then(|| now + if active { self.debounce } else { self.hold_off });
+    }
+
+    /// When the pending trip or clear is due.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.pending
+    }
+
+    pub(crate) fn poll(&mut self, now: Instant) -> Option<Transition> {
+        self.pending.filter(|due| *due <= now)?;
+        self.pending = None;
+        self.tripped = !self.tripped;
+        Some(if self.tripped {
+            Transition::Trip
+        } else {
+            Transition::Clear
+        })
+    }
+}
+
+/// Watch every configured input. Returns no tasks when `[safety]` has none.
+pub(crate) fn start(
+    controller: Arc<BlindController>,
+    options: &SafetyOptions,
+    chip: &str,
+) -> Vec<JoinHandle<()>> {
+    options
+        .inputs
+        .iter()
+        .map(|input| {
+            tokio::spawn(watch_input(
+                controller.clone(),
+                input.clone(),
+                chip.to_string(),
+                Debouncer::new(
+                    Duration::from_millis(options.debounce_ms),
+                    Duration::from_secs(options.hold_off_secs),
+                ),
+            ))
+        })
+        .collect()
+}
+
+async fn watch_input(
+    controller: Arc<BlindController>,
+    input: SafetyInputOptions,
+    chip: String,
+    mut debouncer: Debouncer,
+) {
+    let (levels, mut rx) = watch::channel(false);
+    let watcher = gpio::watch_level(&chip, input.gpio, input.active_low, levels);
+    tokio::pin!(watcher);
+    tracing::info!(input = %input.name, gpio = input.gpio, "watching safety input");
+    let mut watching = true;
+    // When to retry a failed retract, and the delay after that attempt.
+    let mut retry: Option<(Instant, Duration)> = None;
+    loop {
+        let deadline = debouncer.deadline().filter(|_| watching);
+        let retry_at = retry.map(|(at, _)| at);
+        tokio::select! {
+            biased;
+            result = &mut watcher, if watching => {
+                // An input that can no longer be read fails safe: it trips
+                // and stays tripped until the service restarts.
+                watching = false;
+                if let Err(e) = result {
+                    tracing::error!(input = %input.name, "safety input stopped: {e:#}; treating it as tripped");
+                }
+                retry = retract(&controller, &input.name, RETRACT_RETRY_MIN).await;
+            }
+            changed = rx.changed(), if watching => {
+                if changed.is_ok() {
+                    let active = *rx.borrow_and_update();
+                    tracing::debug!(input = %input.name, active, "safety input level");
+                    debouncer.set_level(active, Instant::now());
+                }
+            }
+            () = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                match debouncer.poll(Instant::now()) {
+                    Some(Transition::Trip) => {
+                        tracing::warn!(input = %input.name, "safety input tripped; retracting blinds");
+                        retry = retract(&controller, &input.name, RETRACT_RETRY_MIN).await;
+                    }
+                    Some(Transition::Clear) => {
+                        tracing::info!(input = %input.name, "safety input cleared");
+                        retry = None;
+                        controller.clear_safety(&input.name);
+                    }
+                    None => {}
+                }
+            }
+            () = tokio::time::sleep_until(retry_at.unwrap_or_else(Instant::now)), if retry_at.is_some() => {
+                let delay = retry.map_or(RETRACT_RETRY_MIN, |(_, delay)| delay);
+                retry = retract(&controller, &input.name, delay).await;
+            }
+        }
+        if !watching && retry.is_none() {
+            return;
+        }
+    }
+}
+
+/// Trip `name` and retract its blinds. On failure, returns when to try again
+/// (after `delay`) and the doubled delay for the attempt after that.
+async fn retract(
+    controller: &Arc<BlindController>,
+    name: &str,
+    delay: Duration,
+) -> Option<(Instant, Duration)> {
+    match controller.trip_safety(name).await {
+        Ok(_) => None,
+        Err(e) => {
+            tracing::error!(input = %name, retry_in = ?delay, "failed to retract blinds: {e:#}");
+            Some((Instant::now() + delay, (delay * 2).min(RETRACT_RETRY_MAX)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_after_debounce_and_clears_after_hold_off() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(secs(5), secs(600));
+
+        // A gust shorter than the debounce window is ignored.
+        debouncer.set_level(true, start);
+        debouncer.set_level(false, start + secs(3));
+        assert_eq!(debouncer.deadline(), None);
+
+        debouncer.set_level(true, start + secs(10));
+        assert_eq!(debouncer.deadline(), Some(start + secs(15)));
+        assert_eq!(debouncer.poll(start + secs(14)), None);
+        assert_eq!(debouncer.poll(start + secs(15)), Some(Transition::Trip));
+
+        // Activity during the hold-off restarts it.
+        debouncer.set_level(false, start + secs(20));
+        debouncer.set_level(true, start + secs(300));
+        assert_eq!(debouncer.deadline(), None);
+        debouncer.set_level(false, start + secs(310));
+        assert_eq!(debouncer.poll(start + secs(900)), None);
+        assert_eq!(debouncer.poll(start + secs(910)), Some(Transition::Clear));
+        assert_eq!(debouncer.poll(start + secs(2_000)), None);
+    }
+}
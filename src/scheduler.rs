@@ -0,0 +1,693 @@
+//! Time-based `[[schedule]]` entries fired inside `somfy serve`.
+//!
+//! Rules are evaluated in local civil time of the entry's time zone. A time
+//! skipped by a DST gap fires at the first instant after the gap; a time
+//! repeated by a DST fold fires once, at its first occurrence. Only future
+//! runs are ever scheduled, so missed runs are not replayed after a restart.
+//! `sun` rules fire relative to sunrise or sunset at `[location]`, computed
+//! offline by [`crate::solar`].
+
+use anyhow::{anyhow, bail, Context, Result};
+use jiff::civil::{Date, Time};
+use jiff::tz::TimeZone;
+use jiff::{SignedDuration, Timestamp, Zoned};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::config::{AppConfig, LocationOptions, ScheduleDay, ScheduleOptions, SunEvent};
+use crate::controller::BlindController;
+use crate::history::CommandSource;
+use crate::positioning::inventory::BlindInventory;
+use crate::service::{
+    dispatch_command, dispatch_scene, ensure_configured_channel, validate_command_request,
+    CommandRequest,
+};
+use crate::solar::sun_event;
+
+/// Longest sleep between checks, so wall-clock jumps (NTP sync on a Pi
+/// without an RTC) are noticed promptly.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+/// Runs noticed later than this, e.g. after a suspend or clock jump, are
+/// skipped instead of fired.
+const LATE_LIMIT: Duration = Duration::from_secs(120);
+/// Search horizon for the next run; rules like `0 0 31 2 *` never match.
+const MAX_SEARCH_DAYS: i32 = 4 * 366;
+
+/// What a schedule entry does when it fires.
+#[derive(Clone, Debug)]
+pub(crate) enum ScheduleAction {
+    Command(CommandRequest),
+    Scene(String),
+}
+
+impl std::fmt::Display for ScheduleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command(request) => {
+                write!(f, "{}", request.command)?;
+                if let Some(value) = request.value {
+                    write!(f, " {value}")?;
+                }
+                if let Some(channel) = request.channel {
+                    write!(f, " {channel}")?;
+                }
+                if let Some(group) = &request.group {
+                    write!(f, " group `{group}`")?;
+                }
+                Ok(())
+            }
+            Self::Scene(name) => write!(f, "scene `{name}`"),
+        }
+    }
+}
+
+/// A validated `[[schedule]]` entry.
+#[derive(Clone, Debug)]
+pub(crate) struct Schedule {
+    pub name: String,
+    /// The rule as written, for display.
+    pub rule_text: String,
+    /// Matching days, and the times of day unless `sun` is set.
+    pub rule: Rule,
+    pub sun: Option<SunTrigger>,
+    pub timezone: TimeZone,
+    pub action: ScheduleAction,
+}
+
+impl Schedule {
+    /// First run strictly after `after`.
+    pub(crate) fn next_after(&self, after: Timestamp) -> Option<Zoned> {
+        // A sun event on the previous local day can land after midnight with
+        // a large offset, so start one day early.
+        let mut date = after
+            .to_zoned(self.timezone.clone())
+            .date()
+            .yesterday()
+            .ok()?;
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.rule.matches_date(date) {
+                let mut runs: Vec<Zoned> = match &self.sun {
+                    Some(sun) => sun.run_on(date, &self.timezone).into_iter().collect(),
+                    None => self
+                        .rule
+                        .times()
+                        .filter_map(|time| {
+                            date.to_datetime(time).to_zoned(self.timezone.clone()).ok()
+                        })
+                        .collect(),
+                };
+                runs.retain(|run| run.timestamp() > after);
+                if let Some(run) = runs.into_iter().next() {
+                    return Some(run);
+                }
+            }
+            date = date.tomorrow().ok()?;
+        }
+        None
+    }
+}
+
+/// A `sun` rule: sunrise or sunset plus an offset, clamped to local times.
+#[derive(Clone, Debug)]
+pub(crate) struct SunTrigger {
+    pub event: SunEvent,
+    pub offset: SignedDuration,
+    pub not_before: Option<Time>,
+    pub not_after: Option<Time>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl SunTrigger {
+    /// Run for local day `date`; `None` on days without the sun event.
+    fn run_on(&self, date: Date, timezone: &TimeZone) -> Option<Zoned> {
+        let at = sun_event(date, self.latitude, self.longitude, self.event)?
+            .checked_add(self.offset)
+            .ok()?;
+        let mut local = at.to_zoned(timezone.clone()).datetime();
+        if let Some(earliest) = self.not_before.map(|time| date.to_datetime(time)) {
+            local = local.max(earliest);
+        }
+        if let Some(latest) = self.not_after.map(|time| date.to_datetime(time)) {
+            local = local.min(latest);
+        }
+        local.to_zoned(timezone.clone()).ok()
+    }
+}
+
+/// Validate every `[[schedule]]` entry against the configured inventory.
+pub(crate) fn schedules_from_config(config: &AppConfig) -> Result<Vec<Schedule>> {
+    let blinds = BlindInventory::from_config(config);
+    let mut schedules: Vec<Schedule> = Vec::with_capacity(config.schedule.len());
+    for options in &config.schedule {
+        if options.name.trim().is_empty() {
+            bail!("schedule.name must not be empty");
+        }
+        if schedules
+            .iter()
+            .any(|schedule| schedule.name == options.name)
+        {
+            bail!(
+                "schedule.name `{}` is used by more than one entry",
+                options.name
+            );
+        }
+        let schedule = schedule_from_options(config, &blinds, options)
+            .map_err(|e| anyhow!("schedule `{}`: {e:#}", options.name))?;
+        schedules.push(schedule);
+    }
+    Ok(schedules)
+}
+
+fn schedule_from_options(
+    config: &AppConfig,
+    blinds: &BlindInventory,
+    options: &ScheduleOptions,
+) -> Result<Schedule> {
+    if options.sun.is_none()
+        && (options.offset_minutes.is_some()
+            || options.not_before.is_some()
+            || options.not_after.is_some())
+    {
+        bail!("offset_minutes, not_before, and not_after only apply to `sun`");
+    }
+    let days = if options.days.is_empty() {
+        "daily".to_string()
+    } else {
+        let days: Vec<String> = options
+            .days
+            .iter()
+            .map(|day| format!("{day:?}").to_lowercase())
+            .collect();
+        format!("on {}", days.join(","))
+    };
+    let (rule, sun, rule_text) = match (&options.at, &options.cron, options.sun) {
+        (Some(at), None, None) => {
+            let time = parse_time_of_day("at", at)?;
+            (
+                Rule::daily(time, &options.days),
+                None,
+                format!("at {at} {days}"),
+            )
+        }
+        (None, Some(cron), None) => {
+            if !options.days.is_empty() {
+                bail!("days only applies to `at` and `sun`; put the days in the cron expression");
+            }
+            (Rule::cron(cron)?, None, cron.clone())
+        }
+        (None, None, Some(event)) => {
+            let sun = sun_trigger(config.location.as_ref(), options, event)?;
+            let text = sun_rule_text(options, event, &days);
+            (
+                Rule::daily(Time::midnight(), &options.days),
+                Some(sun),
+                text,
+            )
+        }
+        _ => bail!("set exactly one of `at`, `cron`, or `sun`"),
+    };
+    let timezone = match &options.timezone {
+        Some(name) => TimeZone::get(name).with_context(|| format!("unknown time zone `{name}`"))?,
+        None => TimeZone::system(),
+    };
+    let action = match (&options.command, &options.scene) {
+        (Some(command), None) => {
+            let request = CommandRequest {
+                command: command.clone(),
+                channel: options.channel,
+                group: options.group.clone(),
+                value: options.value,
+            };
+            let parsed = validate_command_request(config.driver, blinds, request.clone())
+                .map_err(|e| anyhow!("{e}"))?;
+            ensure_configured_channel(blinds, &parsed).map_err(|e| anyhow!("{e}"))?;
+            ScheduleAction::Command(request)
+        }
+        (None, Some(scene)) => {
+            if options.channel.is_some() || options.group.is_some() || options.value.is_some() {
+                bail!("channel, group, and value only apply to `command`");
+            }
+            if blinds.scene_named(scene).is_none() {
+                bail!("unknown scene `{scene}`; see [[scenes]]");
+            }
+            ScheduleAction::Scene(scene.clone())
+        }
+        _ => bail!("set exactly one of `command` or `scene`"),
+    };
+    Ok(Schedule {
+        name: options.name.clone(),
+        rule_text,
+        rule,
+        sun,
+        timezone,
+        action,
+    })
+}
+
+fn sun_trigger(
+    location: Option<&LocationOptions>,
+    options: &ScheduleOptions,
+    event: SunEvent,
+) -> Result<SunTrigger> {
+    let Some(location) = location else {
+        bail!("`sun` needs [location] latitude and longitude");
+    };
+    let not_before = options
+        .not_before
+        .as_deref()
+        .map(|text| parse_time_of_day("not_before", text))
+        .transpose()?;
+    let not_after = options
+        .not_after
+        .as_deref()
+        .map(|text| parse_time_of_day("not_after", text))
+        .transpose()?;
+    if let (Some(earliest), Some(latest)) = (not_before, not_after) {
+        if earliest > latest {
+            bail!("not_before must not be later than not_after");
+        }
+    }
+    Ok(SunTrigger {
+        event,
+        offset: SignedDuration::from_mins(i64::from(options.offset_minutes.unwrap_or(0))),
+        not_before,
+        not_after,
+        latitude: location.latitude,
+        longitude: location.longitude,
+    })
+}
+
+/// `sunset +30m, not before 07:00, on mon,fri`.
+fn sun_rule_text(options: &ScheduleOptions, event: SunEvent, days: &str) -> String {
+    let mut text = format!("{event:?}").to_lowercase();
+    match options.offset_minutes {
+        Some(offset) if offset != 0 => text.push_str(&format!(" {offset:+}m")),
+        _ => {}
+    }
+    if let Some(earliest) = &options.not_before {
+        text.push_str(&format!(", not before {earliest}"));
+    }
+    if let Some(latest) = &options.not_after {
+        text.push_str(&format!(", not after {latest}"));
+    }
+    format!("{text}, {days}")
+}
+
+/// Local time of day written as `HH:MM`.
+fn parse_time_of_day(field: &str, text: &str) -> Result<Time> {
+    text.split_once(':')
+        .and_then(|(hour, minute)| Some((hour.parse::<i8>().ok()?, minute.parse::<i8>().ok()?)))
+        .and_then(|(hour, minute)| Time::new(hour, minute, 0, 0).ok())
+        .with_context(|| format!("{field} must be HH:MM, got `{text}`"))
+}
+
+/// Set of matching minutes, hours, days, months, and weekdays, as bitsets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Rule {
+    minutes: u64,
+    hours: u32,
+    /// Bits 1..=31.
+    days_of_month: u32,
+    /// Bits 1..=12.
+    months: u16,
+    /// Bits 0..=6, Sunday = 0.
+    weekdays: u8,
+    /// Cron matches either field when both day fields are restricted.
+    day_of_month_any: bool,
+    weekday_any: bool,
+}
+
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+impl Rule {
+    /// `time` on `days`, or every day when `days` is empty.
+    fn daily(time: Time, days: &[ScheduleDay]) -> Self {
+        let weekdays = days.iter().fold(0u8, |bits, day| {
+            bits | 1
+                << match day {
+                    ScheduleDay::Sun => 0,
+                    ScheduleDay::Mon => 1,
+                    ScheduleDay::Tue => 2,
+                    ScheduleDay::Wed => 3,
+                    ScheduleDay::Thu => 4,
+                    ScheduleDay::Fri => 5,
+                    ScheduleDay::Sat => 6,
+                }
+        });
+        Self {
+            minutes: 1 << time.minute(),
+            hours: 1 << time.hour(),
+            days_of_month: full_range(1, 31) as u32,
+            months: full_range(1, 12) as u16,
+            weekdays: if days.is_empty() { 0x7f } else { weekdays },
+            day_of_month_any: true,
+            weekday_any: days.is_empty(),
+        }
+    }
+
+    /// Five-field cron expression with `*`, lists, ranges, steps, and
+    /// three-letter month and weekday names. Weekday `7` is Sunday.
+    fn cron(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            bail!("cron needs five fields (minute hour day month weekday), got `{expr}`");
+        };
+        let weekdays = parse_field(dow, 0, 7, &WEEKDAY_NAMES, 0).context("cron weekday")?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[], 0).context("cron minute")?,
+            hours: parse_field(hour, 0, 23, &[], 0).context("cron hour")? as u32,
+            days_of_month: parse_field(dom, 1, 31, &[], 0).context("cron day of month")? as u32,
+            months: parse_field(month, 1, 12, &MONTH_NAMES, 1).context("cron month")? as u16,
+            // Fold 7 onto Sunday.
+            weekdays: ((weekdays | weekdays >> 7) & 0x7f) as u8,
+            day_of_month_any: dom.starts_with('*'),
+            weekday_any: dow.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: Date) -> bool {
+        if self.months & 1 << date.month() == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & 1 << date.day() != 0;
+        let dow = self.weekdays & 1 << date.weekday().to_sunday_zero_offset() != 0;
+        match (self.day_of_month_any, self.weekday_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// Matching times of day, in order.
+    fn times(&self) -> impl Iterator<Item = Time> + '_ {
+        (0..24i8)
+            .filter(|hour| self.hours & 1 << hour != 0)
+            .flat_map(move |hour| {
+                (0..60i8)
+                    .filter(|minute| self.minutes & 1 << minute != 0)
+                    .map(move |minute| Time::constant(hour, minute, 0, 0))
+            })
+    }
+}
+
+fn full_range(min: u32, max: u32) -> u64 {
+    (min..=max).fold(0, |bits, value| bits | 1 << value)
+}
+
+/// One cron field as a bitset. `names` map to `offset`, `offset + 1`, ...
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], offset: u32) -> Result<u64> {
+    let value = |text: &str| -> Result<u32> {
+        let lower = text.to_ascii_lowercase();
+        let value = match names.iter().position(|name| *name == lower) {
+            Some(index) => index as u32 + offset,
+            None => text
+                .parse()
+                .with_context(|| format!("`{text}` is not a number"))?,
+        };
+        if !(min..=max).contains(&value) {
+            bail!("{value} is outside {min}-{max}");
+        }
+        Ok(value)
+    };
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .with_context(|| format!("invalid step in `{item}`"))?;
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end of the field.
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if start > end {
+            bail!("range `{range}` runs backwards");
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// Start the scheduler task, or return `None` when nothing is scheduled.
+pub(crate) fn start(
+    controller: Arc<BlindController>,
+    schedules: Vec<Schedule>,
+) -> Option<JoinHandle<()>> {
+    if schedules.is_empty() {
+        return None;
+    }
+    for schedule in &schedules {
+        if let Some(next) = schedule.next_after(Timestamp::now()) {
+            tracing::info!(schedule = %schedule.name, next = %next, "schedule loaded");
+        }
+    }
+    Some(tokio::spawn(run(controller, schedules)))
+}
+
+async fn run(controller: Arc<BlindController>, schedules: Vec<Schedule>) {
+    let mut runner = Runner::new(schedules, Timestamp::now());
+    loop {
+        let now = Timestamp::now();
+        for (schedule, fire) in runner.poll(now) {
+            if !fire {
+                tracing::warn!(schedule = %schedule.name, "skipping run missed by more than {LATE_LIMIT:?}");
+                continue;
+            }
+            let controller = controller.clone();
+            tokio::spawn(async move { fire_schedule(&controller, &schedule).await });
+        }
+        let sleep = runner
+            .next_due()
+            .and_then(|next| Duration::try_from(next.duration_since(now)).ok())
+            .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP));
+        tokio::time::sleep(sleep).await;
+    }
+}
+
+async fn fire_schedule(controller: &Arc<BlindController>, schedule: &Schedule) {
+    tracing::info!(schedule = %schedule.name, action = %schedule.action, "schedule fired");
+    let source = CommandSource::Scheduler {
+        schedule: schedule.name.clone(),
+    };
+    let result = match &schedule.action {
+        ScheduleAction::Command(request) => dispatch_command(controller, request.clone(), source)
+            .await
+            .map(|_| ()),
+        ScheduleAction::Scene(name) => dispatch_scene(controller, name, source).await.map(|_| ()),
+    };
+    if let Err(e) = result {
+        tracing::error!(schedule = %schedule.name, error = %e, "scheduled command failed");
+    }
+}
+
+/// Next-run bookkeeping, separate from the clock so it can be tested.
+struct Runner {
+    entries: Vec<(Schedule, Option<Timestamp>)>,
+}
+
+impl Runner {
+    fn new(schedules: Vec<Schedule>, now: Timestamp) -> Self {
+        let entries = schedules
+            .into_iter()
+            .map(|schedule| {
+                let next = schedule.next_after(now).map(|next| next.timestamp());
+                (schedule, next)
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Entries due at `now`, paired with whether they are recent enough to
+    /// fire. Each is rescheduled strictly after `now`, so a run is never
+    /// repeated and a backlog is never replayed.
+    fn poll(&mut self, now: Timestamp) -> Vec<(Schedule, bool)> {
+        let mut due = Vec::new();
+        for (schedule, next) in &mut self.entries {
+            let Some(at) = *next else {
+                continue;
+            };
+            if at > now {
+                continue;
+            }
+            let late = Duration::try_from(now.duration_since(at)).unwrap_or_default();
+            due.push((schedule.clone(), late <= LATE_LIMIT));
+            *next = schedule.next_after(now).map(|next| next.timestamp());
+        }
+        due
+    }
+
+    fn next_due(&self) -> Option<Timestamp> {
+        self.entries.iter().filter_map(|(_, next)| *next).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(rule: Rule, timezone: &str) -> Schedule {
+        Schedule {
+            name: "test".to_string(),
+            rule_text: String::new(),
+            rule,
+            sun: None,
+            timezone: TimeZone::get(timezone).unwrap(),
+            action: ScheduleAction::Scene("movie".to_string()),
+        }
+    }
+
+    fn at(text: &str) -> Timestamp {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn cron_parses_lists_ranges_steps_and_names() {
+        let rule = Rule::cron("*/15 7,19 * jan-mar mon-fri").unwrap();
+        assert_eq!(rule.minutes, 1 | 1 << 15 | 1 << 30 | 1 << 45);
+        assert_eq!(rule.hours, 1 << 7 | 1 << 19);
+        assert_eq!(rule.months, 0b1110);
+        assert_eq!(rule.weekdays, 0b011_1110);
+        assert_eq!(Rule::cron("0 0 * * 7").unwrap().weekdays, 1);
+
+        for (expr, message) in [
+            ("0 0 * *", "five fields"),
+            ("60 0 * * *", "cron minute"),
+            ("0 0 * * fri-mon", "cron weekday"),
+            ("*/0 0 * * *", "cron minute"),
+        ] {
+            let err = format!("{:#}", Rule::cron(expr).unwrap_err());
+            assert!(err.contains(message), "{expr}: {err}");
+        }
+    }
+
+    #[test]
+    fn next_run_follows_weekdays_and_cron_day_semantics() {
+        let weekdays = schedule(
+            Rule::daily(
+                Time::constant(7, 30, 0, 0),
+                &[ScheduleDay::Mon, ScheduleDay::Fri],
+            ),
+            "UTC",
+        );
+        // 2026-10-17 is a Saturday.
+        let next = weekdays.next_after(at("2026-10-17T12:00:00Z")).unwrap();
+        assert_eq!(next.timestamp(), at("2026-10-19T07:30:00Z"));
+        let next = weekdays.next_after(next.timestamp()).unwrap();
+        assert_eq!(next.timestamp(), at("2026-10-23T07:30:00Z"));
+
+        // Restricted day of month and weekday match either, as in cron.
+        let either = schedule(Rule::cron("0 9 1 * sun").unwrap(), "UTC");
+        let next = either.next_after(at("2026-10-17T12:00:00Z")).unwrap();
+        assert_eq!(next.timestamp(), at("2026-10-18T09:00:00Z"));
+        let next = either.next_after(at("2026-10-25T12:00:00Z")).unwrap();
+        assert_eq!(next.timestamp(), at("2026-11-01T09:00:00Z"));
+
+        assert!(schedule(Rule::cron("0 0 31 2 *").unwrap(), "UTC")
+            .next_after(at("2026-10-17T12:00:00Z"))
+            .is_none());
+    }
+
+    #[test]
+    fn dst_gap_fires_after_the_gap_and_fold_fires_once() {
+        let night = schedule(
+            Rule::daily(Time::constant(2, 30, 0, 0), &[]),
+            "Europe/Paris",
+        );
+
+        // 2026-03-29: clocks jump from 02:00 to 03:00 CET -> CEST.
+        let next = night.next_after(at("2026-03-28T12:00:00Z")).unwrap();
+        assert_eq!(next.timestamp(), at("2026-03-29T01:30:00Z"));
+        assert_eq!(next.time(), Time::constant(3, 30, 0, 0));
+
+        // 2026-10-25: 02:30 happens twice; only the CEST one fires.
+        let first = night.next_after(at("2026-10-24T12:00:00Z")).unwrap();
+        assert_eq!(first.timestamp(), at("2026-10-25T00:30:00Z"));
+        let next = night.next_after(first.timestamp()).unwrap();
+        assert_eq!(next.timestamp(), at("2026-10-26T01:30:00Z"));
+    }
+
+    #[test]
+    fn sun_rules_apply_offsets_and_clamps() {
+        let paris = LocationOptions {
+            latitude: 48.8566,
+            longitude: 2.3522,
+        };
+        let sun = |event, offset_minutes, not_before: Option<&str>| {
+            let options = ScheduleOptions {
+                offset_minutes,
+                not_before: not_before.map(str::to_string),
+                ..ScheduleOptions::default()
+            };
+            let mut schedule = schedule(Rule::daily(Time::midnight(), &[]), "Europe/Paris");
+            schedule.sun = Some(sun_trigger(Some(&paris), &options, event).unwrap());
+            schedule
+        };
+        let local = |schedule: &Schedule, after: &str| {
+            schedule
+                .next_after(at(after))
+                .unwrap()
+                .strftime("%Y-%m-%d %H:%M")
+                .to_string()
+        };
+
+        // Sunset in Paris on 2024-03-20 is 19:03 CET.
+        let evening = sun(SunEvent::Sunset, Some(30), None);
+        assert_eq!(local(&evening, "2024-03-20T12:00:00Z"), "2024-03-20 19:34");
+        assert_eq!(local(&evening, "2024-03-20T18:40:00Z"), "2024-03-21 19:35");
+
+        // Sunrise is 06:52 CET; `not_before` holds it until 07:00.
+        let morning = sun(SunEvent::Sunrise, None, Some("07:00"));
+        assert_eq!(local(&morning, "2024-03-19T12:00:00Z"), "2024-03-20 07:00");
+        // Late June sunrise (05:47 CEST) is also held back.
+        assert_eq!(local(&morning, "2024-06-20T12:00:00Z"), "2024-06-21 07:00");
+        // In December the sun rises after 07:00, so the clamp has no effect.
+        assert_eq!(local(&morning, "2024-12-20T12:00:00Z"), "2024-12-21 08:41");
+
+        let early = sun(SunEvent::Sunrise, Some(-20), None);
+        assert_eq!(local(&early, "2024-03-19T12:00:00Z"), "2024-03-20 06:32");
+
+        let err = sun_trigger(None, &ScheduleOptions::default(), SunEvent::Sunset).unwrap_err();
+        assert!(err.to_string().contains("[location]"), "{err}");
+    }
+
+    #[test]
+    fn runner_fires_once_and_skips_runs_missed_while_away() {
+        let hourly = schedule(Rule::cron("0 * * * *").unwrap(), "UTC");
+        let mut runner = Runner::new(vec![hourly], at("2026-10-17T12:00:00Z"));
+        assert_eq!(runner.next_due(), Some(at("2026-10-17T13:00:00Z")));
+
+        assert!(runner.poll(at("2026-10-17T12:59:59Z")).is_empty());
+        let due = runner.poll(at("2026-10-17T13:00:01Z"));
+        assert_eq!(due.len(), 1);
+        assert!(due[0].1);
+        assert!(runner.poll(at("2026-10-17T13:00:30Z")).is_empty());
+
+        // Back after several missed hours: one late notice, no backlog.
+        let due = runner.poll(at("2026-10-17T17:20:00Z"));
+        assert_eq!(due.len(), 1);
+        assert!(!due[0].1);
+        assert_eq!(runner.next_due(), Some(at("2026-10-17T18:00:00Z")));
+    }
+}
@@ -1,22 +1,38 @@
 use crate::controller::BlindController;
+use crate::core::Channel;
 use crate::embed;
-use crate::service::{dispatch_command, CommandError, CommandRequest};
+use crate::history::{CommandSource, HistoryEntry, HistoryFilter};
+use crate::locks::Lock;
+use crate::positioning::state::{
+    snapshot_deltas, BlindPosition, PositionConfidence, PositionDelta, PositionSource,
+    STATUS_DECREASING, STATUS_INCREASING,
+};
+use crate::remotes::{
+    BindRequest, LearnRequest, LearnTimedOut, LearnedRemote, DEFAULT_LEARN_TIMEOUT_SECS,
+};
+use crate::rts::export::{ExportRequest, ExportedWaveform};
+use crate::safety::SafetyStatus;
+use crate::service::{
+    dispatch_command, dispatch_lock, dispatch_position_targets, dispatch_scene, dispatch_unlock,
+    CommandError, CommandRequest, LockRequest, PositionTarget,
+};
 use anyhow::Result;
 use axum::extract::ws::{Message, WebSocket};
-use axum::extract::{ConnectInfo, Query, State, WebSocketUpgrade};
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, Path, Query, State, WebSocketUpgrade};
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
-use axum::routing::post;
+use axum::routing::{delete, post, put};
 use axum::{routing::get, Json, Router};
 use futures_util::{
     sink::SinkExt,
-    stream::{self, StreamExt},
+    stream::{self, SplitSink, StreamExt},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tower_http::trace::DefaultMakeSpan;
 use tower_http::trace::TraceLayer;
 
@@ -27,6 +43,17 @@ pub(crate) fn base_url() -> String {
     format!("http://{HTTP_HOST}:{HTTP_PORT}")
 }
 
+/// Request header naming the client; the CLI sends `cli` so its commands
+/// are told apart from other HTTP callers in the history.
+pub(crate) const CLIENT_HEADER: &str = "x-somfy-client";
+
+fn http_source(headers: &HeaderMap) -> CommandSource {
+    match headers.get(CLIENT_HEADER).map(|value| value.as_bytes()) {
+        Some(b"cli") => CommandSource::Cli,
+        _ => CommandSource::Http,
+    }
+}
+
 /// Application state shared across all routes
 pub struct AppState {
     pub controller: Arc<BlindController>,
@@ -42,8 +69,13 @@ impl AppState {
 #[derive(Debug, Deserialize)]
 struct WsQueryParams {
     name: Option<String>,
+    /// Envelope protocol version; omitted for the legacy plain-text protocol.
+    v: Option<u8>,
 }
 
+/// Current WebSocket envelope version, selected with `/ws?v=1`.
+const WS_PROTOCOL_VERSION: u8 = 1;
+
 /// Starts the HTTP server with all routes and middleware
 pub async fn serve(shared_state: Arc<AppState>) -> Result<()> {
     let app = create_router(shared_state);
@@ -63,8 +95,26 @@ pub async fn serve(shared_state: Arc<AppState>) -> Result<()> {
 fn create_router(shared_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/channel", get(handle_channel))
+        .route("/blinds", get(handle_blinds))
+        .route(
+            "/positions",
+            get(handle_positions).post(handle_set_positions),
+        )
+        .route("/scenes", get(handle_scenes))
+        .route("/scenes/{name}", post(handle_activate_scene))
         .route("/events", get(handle_events))
         .route("/command", post(handle_command))
+        .route("/history", get(handle_history))
+        .route("/locks", get(handle_locks).post(handle_lock))
+        .route("/locks/{channel}", delete(handle_unlock))
+        .route("/safety", get(handle_safety))
+        .route("/rts/export", post(handle_rts_export))
+        .route("/remotes", get(handle_remotes))
+        .route("/remotes/learn", post(handle_learn_remote))
+        .route(
+            "/remotes/{name}",
+            put(handle_bind_remote).delete(handle_forget_remote),
+        )
         .route("/ws", get(ws_handler))
         .fallback(embed::static_handler)
         .with_state(shared_state)
@@ -79,49 +129,625 @@ async fn handle_channel(State(state): State<Arc<AppState>>) -> String {
     state.controller.current_selection().to_string()
 }
 
-/// Streams channel selection changes as server-sent events.
+/// Configured blind inventory entry returned by `GET /blinds`.
+#[derive(Debug, Serialize)]
+struct BlindInfo {
+    aid: u64,
+    name: String,
+    channel: Channel,
+    /// Where the position estimate came from and how far to trust it.
+    source: PositionSource,
+    confidence: PositionConfidence,
+    /// Moves settled between the end stops since the blind last reached one.
+    interior_moves: u32,
+    /// Unix seconds of the last end-stop arrival, if one has been recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homed_at: Option<u64>,
+}
+
+/// Returns the configured `[[blinds]]` inventory in config order.
+async fn handle_blinds(State(state): State<Arc<AppState>>) -> Json<Vec<BlindInfo>> {
+    let provenance = state.controller.provenance_snapshot().await;
+    let blinds = state
+        .controller
+        .blinds()
+        .iter()
+        .map(|blind| {
+            let provenance = provenance.get(&blind.aid).copied().unwrap_or_default();
+            BlindInfo {
+                aid: blind.aid,
+                name: blind.name.clone(),
+                channel: blind.channel,
+                source: provenance.source,
+                confidence: provenance.confidence(),
+                interior_moves: provenance.interior_moves,
+                homed_at: provenance.homed_at,
+            }
+        })
+        .collect();
+    Json(blinds)
+}
+
+/// Direction of travel reported by `GET /positions` and position events.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum MotionStatus {
+    Opening,
+    Closing,
+    Stopped,
+}
+
+impl MotionStatus {
+    fn from_hap(status: u8) -> Self {
+        match status {
+            STATUS_INCREASING => Self::Opening,
+            STATUS_DECREASING => Self::Closing,
+            _ => Self::Stopped,
+        }
+    }
+}
+
+/// One blind's changed fields in a `position` SSE event or WebSocket message.
+#[derive(Debug, Serialize)]
+struct PositionUpdate {
+    aid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<MotionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_tilt: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_tilt: Option<i8>,
+}
+
+impl From<&PositionDelta> for PositionUpdate {
+    fn from(delta: &PositionDelta) -> Self {
+        Self {
+            aid: delta.aid,
+            current: delta.current,
+            target: delta.target,
+            status: delta.status.map(MotionStatus::from_hap),
+            current_tilt: delta.current_tilt,
+            target_tilt: delta.target_tilt,
+        }
+    }
+}
+
+/// Server-to-client WebSocket message. Legacy connections get none of these
+/// pushes: selection stays plain text and positions are not sent.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsEvent {
+    Selection {
+        channel: String,
+    },
+    Position {
+        deltas: Vec<PositionUpdate>,
+    },
+    /// The request with this `id` completed.
+    Ack {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<serde_json::Value>,
+    },
+    /// The request with this `id` was rejected or failed.
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<serde_json::Value>,
+        code: &'static str,
+        message: String,
+        /// Seconds until a retry can succeed, e.g. for a resting motor.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        retry_after: Option<u64>,
+    },
+}
+
+/// Versioned frame around a [`WsEvent`]; `v` is omitted on legacy connections.
+#[derive(Debug, Serialize)]
+struct WsFrame {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    v: Option<u8>,
+    #[serde(flatten)]
+    event: WsEvent,
+}
+
+/// Client-to-server envelope: `{"v":1,"id":7,"type":"command","payload":{...}}`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WsEnvelope {
+    v: u8,
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+/// Request carried by a client envelope.
+#[derive(Debug)]
+enum WsRequest {
+    Command(CommandRequest),
+    Positions(Vec<PositionTarget>),
+}
+
+/// A decoded client text frame.
+#[derive(Debug)]
+enum WsIncoming {
+    /// Bare `CommandRequest` JSON from a pre-envelope client; no reply is sent.
+    Legacy(CommandRequest),
+    Request {
+        id: Option<serde_json::Value>,
+        request: WsRequest,
+    },
+    /// Envelope that cannot be served; rejected with `code`.
+    Rejected {
+        id: Option<serde_json::Value>,
+        code: &'static str,
+        message: String,
+    },
+    /// Neither an envelope nor a legacy command.
+    Unreadable(String),
+}
+
+/// Decode a client text frame. Objects with a `v` field are envelopes; any
+/// other JSON is read as a legacy command.
+fn parse_ws_message(text: &str) -> WsIncoming {
+    let rejected = |id, code, message: String| WsIncoming::Rejected { id, code, message };
+    let value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return WsIncoming::Unreadable(e.to_string()),
+    };
+    if value.get("v").is_none() {
+        return match serde_json::from_value(value) {
+            Ok(request) => WsIncoming::Legacy(request),
+            Err(e) => WsIncoming::Unreadable(e.to_string()),
+        };
+    }
+    let id = value.get("id").cloned();
+    let envelope: WsEnvelope = match serde_json::from_value(value) {
+        Ok(envelope) => envelope,
+        Err(e) => return rejected(id, "malformed", e.to_string()),
+    };
+    if envelope.v != WS_PROTOCOL_VERSION {
+        return rejected(
+            envelope.id,
+            "unsupported_version",
+            format!("unsupported protocol version {}", envelope.v),
+        );
+    }
+    let request = match envelope.kind.as_str() {
+        "command" => serde_json::from_value(envelope.payload).map(WsRequest::Command),
+        "positions" => serde_json::from_value(envelope.payload).map(WsRequest::Positions),
+        other => {
+            return rejected(
+                envelope.id,
+                "unknown_type",
+                format!("unknown message type `{other}`"),
+            )
+        }
+    };
+    match request {
+        Ok(request) => WsIncoming::Request {
+            id: envelope.id,
+            request,
+        },
+        Err(e) => rejected(envelope.id, "malformed", e.to_string()),
+    }
+}
+
+/// Every blind's full state, sent when a client connects or falls behind.
+async fn position_snapshot_updates(controller: &BlindController) -> Vec<PositionUpdate> {
+    let positions = controller.position_snapshot().await;
+    snapshot_deltas(controller.blinds(), &positions)
+        .iter()
+        .map(PositionUpdate::from)
+        .collect()
+}
+
+/// Next batch of position updates for a web client. A client that fell behind
+/// the broadcast is resynced from the snapshot, like the HomeKit bridge.
+/// `None` once the controller is gone.
+async fn next_position_updates(
+    controller: &BlindController,
+    rx: &mut broadcast::Receiver<Arc<[PositionDelta]>>,
+) -> Option<Vec<PositionUpdate>> {
+    match rx.recv().await {
+        Ok(deltas) => Some(deltas.iter().map(PositionUpdate::from).collect()),
+        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+            tracing::warn!(
+                skipped,
+                "position broadcast lagged; resyncing web client from snapshot"
+            );
+            Some(position_snapshot_updates(controller).await)
+        }
+        Err(broadcast::error::RecvError::Closed) => None,
+    }
+}
+
+fn position_event(updates: &[PositionUpdate]) -> Event {
+    Event::default()
+        .event("position")
+        .json_data(updates)
+        .unwrap_or_else(|e| {
+            tracing::warn!("failed to encode position event: {e}");
+            Event::default().comment("position event unavailable")
+        })
+}
+
+/// Estimated blind position returned by `GET /positions`.
+#[derive(Debug, Serialize)]
+struct PositionInfo {
+    aid: u64,
+    name: String,
+    channel: Channel,
+    current: u8,
+    target: u8,
+    status: MotionStatus,
+    source: PositionSource,
+    confidence: PositionConfidence,
+    /// Slat angles, reported for venetian blinds only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_tilt: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_tilt: Option<i8>,
+}
+
+/// Returns the estimated position of every configured blind in config order.
+async fn handle_positions(State(state): State<Arc<AppState>>) -> Json<Vec<PositionInfo>> {
+    Json(position_infos(&state.controller, None).await)
+}
+
+/// Sets targets for several blinds at once, then returns their positions.
+async fn handle_set_positions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(targets): Json<Vec<PositionTarget>>,
+) -> Response {
+    tracing::info!(count = targets.len(), "batch position targets received");
+    match dispatch_position_targets(&state.controller, &targets, http_source(&headers)).await {
+        Ok(aids) => Json(position_infos(&state.controller, Some(&aids)).await).into_response(),
+        Err(e) => command_error_response(e),
+    }
+}
+
+/// Configured scene returned by `GET /scenes`.
+#[derive(Debug, Serialize)]
+struct SceneInfo {
+    name: String,
+    /// HomeKit switch accessory id, when the scene is bridged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aid: Option<u64>,
+    positions: Vec<SceneTargetInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct SceneTargetInfo {
+    aid: u64,
+    channel: Channel,
+    target: u8,
+}
+
+/// Returns the configured scenes in config order.
+async fn handle_scenes(State(state): State<Arc<AppState>>) -> Json<Vec<SceneInfo>> {
+    let blinds = state.controller.blinds();
+    Json(
+        blinds
+            .scenes()
+            .map(|scene| SceneInfo {
+                name: scene.name.clone(),
+                aid: scene.aid,
+                positions: scene
+                    .targets
+                    .iter()
+                    .filter_map(|(aid, target)| {
+                        let blind = blinds.find(*aid)?;
+                        Some(SceneTargetInfo {
+                            aid: *aid,
+                            channel: blind.channel,
+                            target: *target,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+/// Activates a scene, then returns the positions of its blinds.
+async fn handle_activate_scene(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    tracing::info!(scene = %name, "scene activation received");
+    match dispatch_scene(&state.controller, &name, http_source(&headers)).await {
+        Ok(aids) => Json(position_infos(&state.controller, Some(&aids)).await).into_response(),
+        Err(e) => command_error_response(e),
+    }
+}
+
+/// Positions for the configured blinds, limited to `aids` when given.
+async fn position_infos(controller: &BlindController, aids: Option<&[u64]>) -> Vec<PositionInfo> {
+    let provenance = controller.provenance_snapshot().await;
+    controller
+        .position_snapshot()
+        .await
+        .into_iter()
+        .filter(|position| aids.is_none_or(|aids| aids.contains(&position.aid)))
+        .filter_map(|position| {
+            let blind = controller.blinds().find(position.aid)?;
+            let provenance = provenance.get(&position.aid).copied().unwrap_or_default();
+            let venetian = blind.is_venetian();
+            let BlindPosition {
+                aid,
+                current,
+                target,
+                status,
+                current_tilt,
+                target_tilt,
+            } = position;
+            Some(PositionInfo {
+                aid,
+                name: blind.name.clone(),
+                channel: blind.channel,
+                current,
+                target,
+                status: MotionStatus::from_hap(status),
+                source: provenance.source,
+                confidence: provenance.confidence(),
+                current_tilt: venetian.then_some(current_tilt),
+                target_tilt: venetian.then_some(target_tilt),
+            })
+        })
+        .collect()
+}
+
+/// Streams channel selection changes, position updates, and safety input
+/// changes as server-sent events. Each starts with the current state.
+/// Presses heard from other RTS remotes follow as `remote` events.
 async fn handle_events(
     State(state): State<Arc<AppState>>,
 ) -> Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>> {
     let mut rx = state.controller.subscribe_selection();
     rx.mark_changed();
-    let stream = stream::unfold(rx, |mut rx| async move {
+    let selection = stream::unfold(rx, |mut rx| async move {
         rx.changed().await.ok()?;
         let channel = rx.borrow_and_update().to_string();
         Some((Ok(Event::default().event("selection").data(channel)), rx))
     });
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    let controller = state.controller.clone();
+    let position_rx = controller.subscribe_positions();
+    let initial = {
+        let controller = controller.clone();
+        stream::once(async move {
+            Ok(position_event(
+                &position_snapshot_updates(&controller).await,
+            ))
+        })
+    };
+    let positions = initial.chain(stream::unfold(
+        (controller, position_rx),
+        |(controller, mut rx)| async move {
+            let updates = next_position_updates(&controller, &mut rx).await?;
+            Some((Ok(position_event(&updates)), (controller, rx)))
+        },
+    ));
+
+    let mut rx = state.controller.subscribe_safety();
+    rx.mark_changed();
+    let safety = stream::unfold(rx, |mut rx| async move {
+        rx.changed().await.ok()?;
+        let statuses = rx.borrow_and_update().clone();
+        let event = Event::default()
+            .event("safety")
+            .json_data(statuses)
+            .unwrap_or_else(|e| {
+                tracing::warn!("failed to encode safety event: {e}");
+                Event::default().comment("safety event unavailable")
+            });
+        Some((Ok(event), rx))
+    });
+
+    let remote_rx = state.controller.subscribe_remote_presses();
+    let remotes = stream::unfold(remote_rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(press) => {
+                    let event = Event::default()
+                        .event("remote")
+                        .json_data(press)
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("failed to encode remote event: {e}");
+                            Event::default().comment("remote event unavailable")
+                        });
+                    return Some((Ok(event), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream::select(
+        stream::select(selection, positions),
+        stream::select(safety, remotes),
+    ))
+    .keep_alive(KeepAlive::default())
 }
 
 /// Handles command requests via HTTP
 async fn handle_command(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(payload): Json<CommandRequest>,
 ) -> Response {
-    match execute_command(&state, payload).await {
+    match execute_command(&state, payload, http_source(&headers)).await {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+        Err(e) => command_error_response(e),
+    }
+}
+
+/// Returns executed commands, newest first, filtered by channel and time.
+async fn handle_history(
+    State(state): State<Arc<AppState>>,
+    Query(filter): Query<HistoryFilter>,
+) -> Json<Vec<HistoryEntry>> {
+    Json(state.controller.history().query(&filter))
+}
+
+/// Returns every safety input with whether it is active, in config order.
+async fn handle_safety(State(state): State<Arc<AppState>>) -> Json<Vec<SafetyStatus>> {
+    Json(state.controller.safety_status())
+}
+
+/// Returns the active maintenance locks, oldest first.
+async fn handle_locks(State(state): State<Arc<AppState>>) -> Json<Vec<Lock>> {
+    Json(state.controller.locks().list())
+}
+
+/// Locks a channel (every blind when `channel` is omitted) and returns the lock.
+async fn handle_lock(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<LockRequest>,
+) -> Response {
+    tracing::info!(channel = ?request.channel, until = ?request.until, "lock received");
+    match dispatch_lock(&state.controller, request, http_source(&headers)).await {
+        Ok(lock) => Json(lock).into_response(),
+        Err(e) => command_error_response(e),
+    }
+}
+
+/// Removes the lock on exactly `channel` and returns it; `404` when the
+/// channel was not locked.
+async fn handle_unlock(
+    State(state): State<Arc<AppState>>,
+    Path(channel): Path<Channel>,
+    headers: HeaderMap,
+) -> Response {
+    match dispatch_unlock(&state.controller, channel, http_source(&headers)) {
+        Ok(Some(lock)) => Json(lock).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("{channel} is not locked")).into_response(),
+        Err(e) => command_error_response(e),
     }
 }
 
-async fn execute_command(state: &AppState, payload: CommandRequest) -> Result<(), String> {
+/// Builds an RTS waveform for another transmitter, consuming a rolling code.
+async fn handle_rts_export(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ExportRequest>,
+) -> Response {
+    if !state.controller.blinds().contains_channel(request.channel) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "channel {} is not configured in [[blinds]]",
+                request.channel
+            ),
+        )
+            .into_response();
+    }
+    match state
+        .controller
+        .export_rts(request.channel, request.command, request.long)
+        .await
+    {
+        Ok(exported) => Json::<ExportedWaveform>(exported).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response(),
+    }
+}
+
+async fn handle_remotes(State(state): State<Arc<AppState>>) -> Json<Vec<LearnedRemote>> {
+    Json(state.controller.learned_remotes().list())
+}
+
+/// Waits for the next press from a remote that is not one of ours and
+/// records it under `name`; `408` when nothing is heard in time.
+async fn handle_learn_remote(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<LearnRequest>,
+) -> Response {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return (StatusCode::BAD_REQUEST, "remote name must not be empty").into_response();
+    }
+    let timeout =
+        std::time::Duration::from_secs(request.timeout_secs.unwrap_or(DEFAULT_LEARN_TIMEOUT_SECS));
+    tracing::info!(name, "listening for a remote to learn");
+    match state.controller.learn_remote(name, timeout).await {
+        Ok(remote) => Json(remote).into_response(),
+        Err(e) if e.is::<LearnTimedOut>() => {
+            (StatusCode::REQUEST_TIMEOUT, e.to_string()).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response(),
+    }
+}
+
+/// Replaces the channels a learned remote moves.
+async fn handle_bind_remote(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<BindRequest>,
+) -> Response {
+    match state.controller.bind_remote(&name, request.channels) {
+        Ok(remote) => Json(remote).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response(),
+    }
+}
+
+/// Removes a learned remote and returns it; `404` when there is none.
+async fn handle_forget_remote(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    match state.controller.learned_remotes().forget(&name) {
+        Ok(Some(remote)) => Json(remote).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("no learned remote named `{name}`"),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")).into_response(),
+    }
+}
+
+async fn execute_command(
+    state: &AppState,
+    payload: CommandRequest,
+    source: CommandSource,
+) -> Result<(), CommandError> {
     tracing::info!(
         command = %payload.command,
         ?payload.channel,
         ?payload.value,
         "remote command received"
     );
-    dispatch_command(&state.controller, payload)
-        .await
-        .map_err(map_command_error)?;
+    dispatch_command(&state.controller, payload, source).await?;
     tracing::info!("remote command completed");
     Ok(())
 }
 
-fn map_command_error(err: CommandError) -> String {
+/// `429 Too Many Requests` with `Retry-After` for errors that clear by
+/// waiting, `423 Locked` for locked or safety-held channels, `400 Bad
+/// Request` otherwise.
+fn command_error_response(err: CommandError) -> Response {
     tracing::error!(error = %err, "remote command failed");
-    err.to_string()
+    match err.retry_after_secs() {
+        Some(secs) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, secs.to_string())],
+            err.to_string(),
+        )
+            .into_response(),
+        None if matches!(err, CommandError::Locked(_) | CommandError::SafetyHold(_)) => {
+            (StatusCode::LOCKED, err.to_string()).into_response()
+        }
+        None => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    }
 }
 
 /// Handles WebSocket upgrade requests
@@ -130,24 +756,50 @@ async fn ws_handler(
     State(state): State<Arc<AppState>>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Query(params): Query<WsQueryParams>,
-) -> impl IntoResponse {
+) -> Response {
+    if let Some(v) = params.v.filter(|v| *v != WS_PROTOCOL_VERSION) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("unsupported protocol version {v}"),
+        )
+            .into_response();
+    }
     let client_name = params.name.unwrap_or_else(|| "anonymous".to_string());
     let port = addr.port();
     tracing::info!("[{}:{}] New WebSocket connection", client_name, port);
-    ws.on_upgrade(move |socket| websocket(socket, state, client_name, port))
+    ws.on_upgrade(move |socket| websocket(socket, state, client_name, port, params.v))
 }
 
-/// Manages WebSocket connections and message handling
-async fn websocket(stream: WebSocket, state: Arc<AppState>, client_name: String, port: u16) {
+/// Manages WebSocket connections and message handling. `version` is `None`
+/// for legacy clients, which get plain-text selection pushes only: they read
+/// every text frame as a channel name.
+async fn websocket(
+    stream: WebSocket,
+    state: Arc<AppState>,
+    client_name: String,
+    port: u16,
+    version: Option<u8>,
+) {
     let (mut sink, mut stream) = stream.split();
     let mut rx_channel = state.controller.subscribe_selection();
+    let mut rx_positions = state.controller.subscribe_positions();
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::unbounded_channel::<WsEvent>();
     let mut ping_interval = tokio::time::interval(std::time::Duration::from_secs(30));
 
-    // Send initial channel state.
+    // Send initial channel and position state.
     let selection = rx_channel.borrow().to_string();
-    if sink.send(Message::Text(selection.into())).await.is_err() {
+    if send_selection(&mut sink, version, selection).await.is_err() {
         return;
     }
+    if version.is_some() {
+        let snapshot = position_snapshot_updates(&state.controller).await;
+        if send_ws_event(&mut sink, version, WsEvent::Position { deltas: snapshot })
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
 
     loop {
         tokio::select! {
@@ -163,53 +815,93 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, client_name: String,
                     break;
                 }
                 let selection = rx_channel.borrow().to_string();
-                if sink.send(Message::Text(selection.into())).await.is_err() {
+                if send_selection(&mut sink, version, selection).await.is_err() {
+                    break;
+                }
+            }
+            // Forward position changes to v1 clients.
+            updates = next_position_updates(&state.controller, &mut rx_positions), if version.is_some() => {
+                let Some(deltas) = updates else {
+                    break;
+                };
+                if send_ws_event(&mut sink, version, WsEvent::Position { deltas }).await.is_err() {
+                    break;
+                }
+            }
+            // Acks and errors from finished requests; replies always use the
+            // envelope, since only envelope requests get one.
+            Some(reply) = reply_rx.recv() => {
+                if send_ws_event(&mut sink, Some(WS_PROTOCOL_VERSION), reply).await.is_err() {
                     break;
                 }
             }
             // Handle incoming messages
             msg = stream.next() => {
                 match msg {
-                    Some(Ok(Message::Text(text))) => {
-                        match serde_json::from_str::<CommandRequest>(&text) {
-                            Ok(payload) => {
-                                let command = payload.command.clone();
-                                let channel = payload.channel;
-                                let value = payload.value;
-                                let state = state.clone();
-                                let client_name = client_name.clone();
-                                tokio::spawn(async move {
-                                    match execute_command(&state, payload).await {
-                                        Ok(_) => {
-                                            tracing::info!(
-                                                "[{}:{}] {} {:?} value={:?}",
-                                                client_name,
-                                                port,
-                                                command,
-                                                channel,
-                                                value
-                                            )
-                                        }
-                                        Err(e) => {
-                                            tracing::error!(
-                                                "[{}:{}] Command execution failed: {}",
-                                                client_name,
-                                                port,
-                                                e
-                                            );
-                                        }
-                                    }
+                    Some(Ok(Message::Text(text))) => match parse_ws_message(&text) {
+                        WsIncoming::Legacy(payload) => {
+                            let state = state.clone();
+                            let client_name = client_name.clone();
+                            tokio::spawn(async move {
+                                let summary = command_summary(&payload);
+                                let source = CommandSource::Ws {
+                                    client: client_name.clone(),
+                                };
+                                if let Err(e) = execute_command(&state, payload, source).await {
+                                    tracing::error!(
+                                        "[{}:{}] Command execution failed: {}",
+                                        client_name,
+                                        port,
+                                        e
+                                    );
+                                } else {
+                                    tracing::info!("[{}:{}] {}", client_name, port, summary);
+                                }
+                            });
+                        }
+                        WsIncoming::Request { id, request } => {
+                            let state = state.clone();
+                            let reply_tx = reply_tx.clone();
+                            let source = CommandSource::Ws {
+                                client: client_name.clone(),
+                            };
+                            tokio::spawn(async move {
+                                let reply = execute_ws_request(&state, id, request, source).await;
+                                let _ = reply_tx.send(reply);
+                            });
+                        }
+                        WsIncoming::Rejected { id, code, message } => {
+                            tracing::error!(
+                                "Invalid message received from client {}:{}: {}",
+                                client_name,
+                                port,
+                                message
+                            );
+                            let _ = reply_tx.send(WsEvent::Error {
+                                id,
+                                code,
+                                message,
+                                retry_after: None,
+                            });
+                        }
+                        WsIncoming::Unreadable(message) => {
+                            tracing::error!(
+                                "Invalid JSON received from client {}:{}: {}",
+                                client_name,
+                                port,
+                                message
+                            );
+                            // Legacy clients never got replies; keep it that way.
+                            if version.is_some() {
+                                let _ = reply_tx.send(WsEvent::Error {
+                                    id: None,
+                                    code: "malformed",
+                                    message,
+                                    retry_after: None,
                                 });
                             }
-                            Err(_) => {
-                                tracing::error!(
-                                    "Invalid JSON received from client: {}:{}",
-                                    client_name,
-                                    port
-                                );
-                            }
                         }
-                    }
+                    },
                     Some(Ok(_)) => {} // Ignore other message types (Pong, etc.)
                     Some(Err(_)) | None => break, // Connection closed or error
                 }
@@ -217,3 +909,359 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, client_name: String,
         }
     }
 }
+
+fn command_summary(payload: &CommandRequest) -> String {
+    format!(
+        "{} {:?} value={:?}",
+        payload.command, payload.channel, payload.value
+    )
+}
+
+/// Run one envelope request and build its `ack` or `error` reply.
+async fn execute_ws_request(
+    state: &AppState,
+    id: Option<serde_json::Value>,
+    request: WsRequest,
+    source: CommandSource,
+) -> WsEvent {
+    let result = match request {
+        WsRequest::Command(payload) => dispatch_command(&state.controller, payload, source)
+            .await
+            .map(|_| ()),
+        WsRequest::Positions(targets) => {
+            dispatch_position_targets(&state.controller, &targets, source)
+                .await
+                .map(|_| ())
+        }
+    };
+    match result {
+        Ok(()) => WsEvent::Ack { id },
+        Err(e) => {
+            tracing::error!(error = %e, "websocket request failed");
+            WsEvent::Error {
+                id,
+                code: e.code(),
+                message: e.to_string(),
+                retry_after: e.retry_after_secs(),
+            }
+        }
+    }
+}
+
+/// Push the selected channel: plain text on legacy connections, a
+/// `selection` frame otherwise.
+async fn send_selection(
+    sink: &mut SplitSink<WebSocket, Message>,
+    version: Option<u8>,
+    channel: String,
+) -> Result<(), axum::Error> {
+    match version {
+        None => sink.send(Message::Text(channel.into())).await,
+        Some(_) => send_ws_event(sink, version, WsEvent::Selection { channel }).await,
+    }
+}
+
+/// Send a JSON event frame to a WebSocket client.
+async fn send_ws_event(
+    sink: &mut SplitSink<WebSocket, Message>,
+    version: Option<u8>,
+    event: WsEvent,
+) -> Result<(), axum::Error> {
+    let text = match serde_json::to_string(&WsFrame { v: version, event }) {
+        Ok(text) => text,
+        Err(e) => {
+            tracing::warn!("failed to encode WebSocket event: {e}");
+            return Ok(());
+        }
+    };
+    sink.send(Message::Text(text.into())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::fake_four_blinds;
+
+    #[tokio::test]
+    async fn positions_route_reads_and_sets_batch_targets() {
+        let state = Arc::new(AppState::new(fake_four_blinds(10).await));
+
+        let Json(positions) = handle_positions(State(state.clone())).await;
+        assert_eq!(positions.len(), 4);
+        assert_eq!(positions[0].aid, 2);
+        assert_eq!(positions[0].name, "Blind 1");
+        assert_eq!((positions[0].current, positions[0].target), (100, 100));
+
+        let targets = vec![
+            PositionTarget { aid: 2, target: 40 },
+            PositionTarget { aid: 3, target: 0 },
+        ];
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_HEADER, "cli".parse().unwrap());
+        let response =
+            handle_set_positions(State(state.clone()), headers.clone(), Json(targets)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let moved: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(moved.as_array().map(Vec::len), Some(2));
+        assert_eq!(moved[0]["target"], 40);
+        assert_eq!(moved[0]["status"], "closing");
+        assert_eq!(moved[1]["aid"], 3);
+
+        let rejected = vec![PositionTarget { aid: 9, target: 40 }];
+        let response = handle_set_positions(State(state.clone()), headers, Json(rejected)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let Json(history) = handle_history(
+            State(state),
+            Query(HistoryFilter {
+                channel: Some(Channel::L1),
+                ..HistoryFilter::default()
+            }),
+        )
+        .await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].source, CommandSource::Cli);
+        assert_eq!(history[0].target, Some(40));
+    }
+
+    #[tokio::test]
+    async fn position_updates_resync_from_snapshot_after_lag() {
+        let controller = fake_four_blinds(10).await;
+        let mut rx = controller.subscribe_positions();
+
+        let delta = PositionDelta {
+            aid: 2,
+            current: Some(60),
+            target: None,
+            status: None,
+            current_tilt: None,
+            target_tilt: None,
+        };
+        controller.emit_position_deltas_for_test(&[delta]);
+        let updates = next_position_updates(&controller, &mut rx).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(WsEvent::Position { deltas: updates }).unwrap(),
+            serde_json::json!({"type": "position", "deltas": [{"aid": 2, "current": 60}]})
+        );
+
+        for _ in 0..100 {
+            controller.emit_position_deltas_for_test(&[delta]);
+        }
+        let updates = next_position_updates(&controller, &mut rx).await.unwrap();
+        assert_eq!(updates.len(), 4);
+        assert_eq!(
+            serde_json::to_value(&updates[0]).unwrap(),
+            serde_json::json!({"aid": 2, "current": 100, "target": 100, "status": "stopped"})
+        );
+    }
+
+    #[test]
+    fn ws_messages_accept_envelopes_and_legacy_commands() {
+        assert!(matches!(
+            parse_ws_message(r#"{"command":"up","channel":"L1"}"#),
+            WsIncoming::Legacy(CommandRequest { ref command, .. }) if command == "up"
+        ));
+        assert!(matches!(
+            parse_ws_message(
+                r#"{"v":1,"id":7,"type":"command","payload":{"command":"target","value":40}}"#
+            ),
+            WsIncoming::Request {
+                id: Some(_),
+                request: WsRequest::Command(_),
+            }
+        ));
+        assert!(matches!(
+            parse_ws_message(r#"{"v":1,"type":"positions","payload":[{"aid":2,"target":0}]}"#),
+            WsIncoming::Request {
+                id: None,
+                request: WsRequest::Positions(ref targets),
+            } if targets.len() == 1
+        ));
+        assert!(matches!(parse_ws_message("L1"), WsIncoming::Unreadable(_)));
+
+        for (text, expected) in [
+            (
+                r#"{"v":2,"id":"a","type":"command"}"#,
+                "unsupported_version",
+            ),
+            (r#"{"v":1,"id":"a","type":"dance"}"#, "unknown_type"),
+            (
+                r#"{"v":1,"id":"a","type":"command","payload":{"led":"L1"}}"#,
+                "malformed",
+            ),
+        ] {
+            let incoming = parse_ws_message(text);
+            assert!(
+                matches!(
+                    incoming,
+                    WsIncoming::Rejected { ref id, code, .. }
+                        if code == expected && *id == Some(serde_json::json!("a"))
+                ),
+                "{text}: {incoming:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn ws_requests_reply_with_ack_or_command_error() {
+        let state = AppState::new(fake_four_blinds(10).await);
+        let id = Some(serde_json::json!(7));
+        let request = |body: &str| match parse_ws_message(body) {
+            WsIncoming::Request { request, .. } => Some(request),
+            _ => None,
+        };
+
+        let ack = execute_ws_request(
+            &state,
+            id.clone(),
+            request(r#"{"v":1,"type":"positions","payload":[{"aid":2,"target":40}]}"#).unwrap(),
+            CommandSource::Ws {
+                client: "tablet".to_string(),
+            },
+        )
+        .await;
+        assert_eq!(
+            serde_json::to_value(WsFrame {
+                v: Some(WS_PROTOCOL_VERSION),
+                event: ack,
+            })
+            .unwrap(),
+            serde_json::json!({"v": 1, "type": "ack", "id": 7})
+        );
+
+        let error = execute_ws_request(
+            &state,
+            id,
+            request(r#"{"v":1,"type":"command","payload":{"command":"target","value":101}}"#)
+                .unwrap(),
+            CommandSource::Ws {
+                client: "tablet".to_string(),
+            },
+        )
+        .await;
+        assert_eq!(
+            serde_json::to_value(WsFrame {
+                v: Some(WS_PROTOCOL_VERSION),
+                event: error,
+            })
+            .unwrap(),
+            serde_json::json!({
+                "v": 1,
+                "type": "error",
+                "id": 7,
+                "code": "invalid",
+                "message": "target position must be between 0 and 100",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn locked_channels_refuse_moves_until_unlocked() {
+        let state = Arc::new(AppState::new(fake_four_blinds(10).await));
+        let command = |command: &str| CommandRequest {
+            command: command.to_string(),
+            channel: Some(Channel::L1),
+            group: None,
+            value: None,
+        };
+
+        let response = handle_lock(
+            State(state.clone()),
+            HeaderMap::new(),
+            Json(LockRequest {
+                channel: Some(Channel::L1),
+                reason: Some("servicing".to_string()),
+                ..LockRequest::default()
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let Json(locks) = handle_locks(State(state.clone())).await;
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].channel, Channel::L1);
+
+        let response = handle_command(
+            State(state.clone()),
+            HeaderMap::new(),
+            Json(command("down")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::LOCKED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "L1 is locked (servicing) until unlocked");
+        let response = handle_command(
+            State(state.clone()),
+            HeaderMap::new(),
+            Json(command("stop")),
+        )
+        .await;
+        // The blind is idle, so Stop would move it to My.
+        assert_eq!(response.status(), StatusCode::LOCKED);
+        assert!(state.controller.operations().iter().all(|operation| {
+            !matches!(
+                operation,
+                crate::driver::ProtocolOperation::FakeCommand {
+                    command: crate::core::Command::Down,
+                    ..
+                }
+            )
+        }));
+
+        let response =
+            handle_unlock(State(state.clone()), Path(Channel::L1), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response =
+            handle_unlock(State(state.clone()), Path(Channel::L1), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = handle_command(
+            State(state.clone()),
+            HeaderMap::new(),
+            Json(command("down")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let Json(history) = handle_history(State(state), Query(HistoryFilter::default())).await;
+        let commands: Vec<&str> = history
+            .iter()
+            .rev()
+            .map(|entry| entry.command.as_str())
+            .collect();
+        assert_eq!(commands, ["lock", "down", "stop", "unlock", "down"]);
+    }
+
+    #[tokio::test]
+    async fn resting_motor_errors_tell_clients_when_to_retry() {
+        use crate::positioning::duty_cycle::MotorResting;
+
+        let error = CommandError::MotorResting(MotorResting {
+            name: "Blind 2".to_string(),
+            channel: Channel::L2,
+            available_in: std::time::Duration::from_millis(41_500),
+        });
+        let response = command_error_response(error.clone());
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+
+        let event = WsEvent::Error {
+            id: None,
+            code: error.code(),
+            message: error.to_string(),
+            retry_after: error.retry_after_secs(),
+        };
+        assert_eq!(
+            serde_json::to_value(event).unwrap(),
+            serde_json::json!({
+                "type": "error",
+                "code": "motor_resting",
+                "message": "Blind 2 (L2) has used its motor run-time budget; available again in 42s",
+                "retry_after": 42,
+            })
+        );
+    }
+}
@@ -9,6 +9,12 @@ use crate::config::DriverKind;
 use crate::controller::BlindController;
 use crate::core::{Channel, Command};
 use crate::driver::{CommandOutcome, TELIS_PROG_UNAVAILABLE};
+use crate::history::{CommandSource, HistoryEntry};
+use crate::locks::{ChannelLocked, Lock};
+use crate::positioning::duty_cycle::MotorResting;
+use crate::positioning::inventory::BlindInventory;
+use crate::positioning::state::{unix_now, TILT_MAX};
+use crate::safety::SafetyHold;
 
 /// Validated command ready for dispatch.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +27,19 @@ pub(crate) enum ControlRequest {
         channel: Option<Channel>,
         position: u8,
     },
+    /// Venetian slat angle in degrees (-90..=90).
+    Tilt { channel: Option<Channel>, angle: i8 },
+}
+
+impl ControlRequest {
+    /// Explicit channel, if any; `None` acts on the current selection.
+    pub(crate) fn channel(&self) -> Option<Channel> {
+        match self {
+            Self::Driver { channel, .. }
+            | Self::Position { channel, .. }
+            | Self::Tilt { channel, .. } => *channel,
+        }
+    }
 }
 
 /// HTTP/JSON command body (`POST /command`, WebSocket text, CLI remote POST).
@@ -29,8 +48,12 @@ pub(crate) enum ControlRequest {
 pub(crate) struct CommandRequest {
     pub command: String,
     pub channel: Option<Channel>,
+    /// Configured `[[groups]]` name; alternative to a `G<n>` channel.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// `target` position (0..=100) or `tilt` angle (-90..=90).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub value: Option<u8>,
+    pub value: Option<i16>,
 }
 
 impl CommandRequest {
@@ -39,21 +62,44 @@ impl CommandRequest {
             ControlRequest::Driver { command, channel } => Self {
                 command: command.to_string(),
                 channel,
+                group: None,
                 value: None,
             },
             ControlRequest::Position { channel, position } => Self {
                 command: "target".to_string(),
                 channel,
-                value: Some(position),
+                group: None,
+                value: Some(i16::from(position)),
+            },
+            ControlRequest::Tilt { channel, angle } => Self {
+                command: "tilt".to_string(),
+                channel,
+                group: None,
+                value: Some(i16::from(angle)),
             },
         }
     }
 }
 
+/// One blind of a `POST /positions` batch.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PositionTarget {
+    pub aid: u64,
+    /// Target position (0..=100).
+    pub target: i16,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum CommandError {
     Invalid(String),
     PairingUnavailable,
+    /// A motor has used its duty-cycle budget and must cool down first.
+    MotorResting(MotorResting),
+    /// The channel is locked for maintenance.
+    Locked(ChannelLocked),
+    /// A downward move refused while a safety input is active.
+    SafetyHold(SafetyHold),
 }
 
 impl std::fmt::Display for CommandError {
@@ -61,6 +107,32 @@ impl std::fmt::Display for CommandError {
         match self {
             Self::Invalid(msg) => write!(f, "{msg}"),
             Self::PairingUnavailable => write!(f, "{TELIS_PROG_UNAVAILABLE}"),
+            Self::MotorResting(resting) => write!(f, "{resting}"),
+            Self::Locked(locked) => write!(f, "{locked}"),
+            Self::SafetyHold(hold) => write!(f, "{hold}"),
+        }
+    }
+}
+
+impl CommandError {
+    /// Stable machine-readable name for API error replies.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::Invalid(_) => "invalid",
+            Self::PairingUnavailable => "pairing_unavailable",
+            Self::MotorResting(_) => "motor_resting",
+            Self::Locked(_) => "locked",
+            Self::SafetyHold(_) => "safety_hold",
+        }
+    }
+
+    /// Seconds until the command can succeed, for errors that clear by waiting.
+    pub(crate) fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            Self::MotorResting(resting) => Some(resting.retry_after_secs()),
+            Self::Invalid(_) | Self::PairingUnavailable | Self::Locked(_) | Self::SafetyHold(_) => {
+                None
+            }
         }
     }
 }
@@ -68,7 +140,16 @@ impl std::fmt::Display for CommandError {
 impl std::error::Error for CommandError {}
 
 fn command_error(err: anyhow::Error) -> CommandError {
-    CommandError::Invalid(format!("{err:?}"))
+    if let Some(resting) = err.downcast_ref::<MotorResting>() {
+        return CommandError::MotorResting(resting.clone());
+    }
+    if let Some(hold) = err.downcast_ref::<SafetyHold>() {
+        return CommandError::SafetyHold(hold.clone());
+    }
+    match err.downcast_ref::<ChannelLocked>() {
+        Some(locked) => CommandError::Locked(locked.clone()),
+        None => CommandError::Invalid(format!("{err:?}")),
+    }
 }
 
 /// Validate a command request. Does not touch hardware.
@@ -76,16 +157,24 @@ fn parse_command(request: CommandRequest) -> Result<ControlRequest, CommandError
     let CommandRequest {
         command,
         channel,
+        group,
         value,
     } = request;
+    if let Some(group) = group {
+        return Err(CommandError::Invalid(format!("unknown group `{group}`")));
+    }
     if command == "target" {
         let position = target_position_value(value)?;
         return Ok(ControlRequest::Position { channel, position });
     }
+    if command == "tilt" {
+        let angle = tilt_angle_value(value)?;
+        return Ok(ControlRequest::Tilt { channel, angle });
+    }
 
     if value.is_some() {
         return Err(CommandError::Invalid(
-            "value is only valid with target".to_string(),
+            "value is only valid with target or tilt".to_string(),
         ));
     }
 
@@ -97,8 +186,13 @@ fn parse_command(request: CommandRequest) -> Result<ControlRequest, CommandError
                 "prog and prog_long require a channel".to_string(),
             ));
         }
+        (Command::Select, Some(Channel::Group(_))) => {
+            return Err(CommandError::Invalid(
+                "select does not accept a group channel".to_string(),
+            ));
+        }
         (Command::Select, channel) => channel,
-        (Command::Up | Command::Down | Command::Stop, channel) => channel,
+        (Command::Up | Command::Down | Command::Stop | Command::My, channel) => channel,
     };
     Ok(ControlRequest::Driver {
         command: cmd,
@@ -106,9 +200,9 @@ fn parse_command(request: CommandRequest) -> Result<ControlRequest, CommandError
     })
 }
 
-fn target_position_value(value: Option<u8>) -> Result<u8, CommandError> {
-    match value {
-        Some(position) if position <= 100 => Ok(position),
+fn target_position_value(value: Option<i16>) -> Result<u8, CommandError> {
+    match value.map(u8::try_from) {
+        Some(Ok(position)) if position <= 100 => Ok(position),
         Some(_) => Err(CommandError::Invalid(
             "target position must be between 0 and 100".to_string(),
         )),
@@ -116,6 +210,46 @@ fn target_position_value(value: Option<u8>) -> Result<u8, CommandError> {
     }
 }
 
+fn tilt_angle_value(value: Option<i16>) -> Result<i8, CommandError> {
+    match value.map(i8::try_from) {
+        Some(Ok(angle)) if (-TILT_MAX..=TILT_MAX).contains(&angle) => Ok(angle),
+        Some(_) => Err(CommandError::Invalid(
+            "tilt angle must be between -90 and 90".to_string(),
+        )),
+        None => Err(CommandError::Invalid("tilt requires a value".to_string())),
+    }
+}
+
+/// Validate a batch of per-blind targets. Every aid must be a configured
+/// blind and appear once. Does not touch hardware.
+pub(crate) fn validate_position_targets(
+    blinds: &BlindInventory,
+    targets: &[PositionTarget],
+) -> Result<Vec<(u64, u8)>, CommandError> {
+    if targets.is_empty() {
+        return Err(CommandError::Invalid(
+            "positions require at least one target".to_string(),
+        ));
+    }
+    let mut validated: Vec<(u64, u8)> = Vec::with_capacity(targets.len());
+    for target in targets {
+        if blinds.find(target.aid).is_none() {
+            return Err(CommandError::Invalid(format!(
+                "aid {} is not a configured blind",
+                target.aid
+            )));
+        }
+        if validated.iter().any(|(aid, _)| *aid == target.aid) {
+            return Err(CommandError::Invalid(format!(
+                "aid {} is listed more than once",
+                target.aid
+            )));
+        }
+        validated.push((target.aid, target_position_value(Some(target.target))?));
+    }
+    Ok(validated)
+}
+
 /// Reject pairing commands when the active driver cannot transmit them.
 fn ensure_pairing_for_kind(kind: DriverKind, command: Command) -> Result<(), CommandError> {
     if matches!(command, Command::Prog | Command::ProgLong) && !kind.supports_pairing() {
@@ -124,12 +258,33 @@ fn ensure_pairing_for_kind(kind: DriverKind, command: Command) -> Result<(), Com
     Ok(())
 }
 
+/// Replace a `group` name with its `G<n>` channel.
+fn resolve_group_name(
+    blinds: &BlindInventory,
+    mut request: CommandRequest,
+) -> Result<CommandRequest, CommandError> {
+    let Some(name) = request.group.take() else {
+        return Ok(request);
+    };
+    if request.channel.is_some() {
+        return Err(CommandError::Invalid(
+            "channel and group are mutually exclusive".to_string(),
+        ));
+    }
+    let group = blinds
+        .group_named(&name)
+        .ok_or_else(|| CommandError::Invalid(format!("unknown group `{name}`")))?;
+    request.channel = Some(group.channel);
+    Ok(request)
+}
+
 /// Parse a command request and apply driver pairing rules. Does not touch hardware.
 pub(crate) fn validate_command_request(
     kind: DriverKind,
+    blinds: &BlindInventory,
     request: CommandRequest,
 ) -> Result<ControlRequest, CommandError> {
-    let parsed = parse_command(request)?;
+    let parsed = parse_command(resolve_group_name(blinds, request)?)?;
     validate_control_request(kind, parsed)
 }
 
@@ -144,21 +299,235 @@ pub(crate) fn validate_control_request(
     Ok(request)
 }
 
+/// Reject channels that do not map to a configured `[[blinds]]` entry.
+pub(crate) fn ensure_configured_channel(
+    blinds: &BlindInventory,
+    request: &ControlRequest,
+) -> Result<(), CommandError> {
+    match request.channel() {
+        Some(channel) if !blinds.contains_channel(channel) => Err(CommandError::Invalid(format!(
+            "channel {channel} is not configured in [[blinds]]"
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Validate and dispatch a command. `select` changes selection; action commands
-/// with an explicit channel target that channel directly.
+/// with an explicit channel target that channel directly. The outcome is
+/// recorded in the command history, including validation failures.
 pub(crate) async fn dispatch_command(
     controller: &Arc<BlindController>,
     request: CommandRequest,
+    source: CommandSource,
 ) -> Result<CommandOutcome, CommandError> {
-    let parsed = validate_command_request(controller.driver_kind(), request)?;
-    dispatch_control_request(controller, parsed).await
+    let command = request.command.clone();
+    let value = request.value;
+    let mut channel = request.channel;
+    let result =
+        match validate_command_request(controller.driver_kind(), controller.blinds(), request) {
+            Ok(parsed) => {
+                channel = parsed.channel();
+                dispatch_control_request(controller, parsed, source.clone()).await
+            }
+            Err(e) => Err(e),
+        };
+    // Commands without a channel act on (or, for `select`, move) the selection.
+    let channel = channel.unwrap_or_else(|| controller.current_selection());
+    controller.history().record(HistoryEntry::now(
+        source,
+        command,
+        Some(channel),
+        value,
+        result.as_ref().map(|_| ()),
+    ));
+    result
+}
+
+/// Move several blinds in one controller operation, so blinds sharing a
+/// direction can start together. Records one history entry per blind.
+pub(crate) async fn dispatch_position_targets(
+    controller: &Arc<BlindController>,
+    targets: &[PositionTarget],
+    source: CommandSource,
+) -> Result<Vec<u64>, CommandError> {
+    let result = match validate_position_targets(controller.blinds(), targets) {
+        Ok(validated) => controller
+            .set_target_positions(validated, source.clone())
+            .await
+            .context("executing batch target positions")
+            .map(|_| ())
+            .map_err(command_error),
+        Err(e) => Err(e),
+    };
+    for target in targets {
+        let channel = controller
+            .blinds()
+            .find(target.aid)
+            .map(|blind| blind.channel);
+        controller.history().record(HistoryEntry::now(
+            source.clone(),
+            "target",
+            channel,
+            Some(target.target),
+            result.as_ref().map(|_| ()),
+        ));
+    }
+    result.map(|()| targets.iter().map(|target| target.aid).collect())
+}
+
+/// Activate a `[[scenes]]` preset: every blind in one `set_target_positions`
+/// call, so `plan_motion` can still start blinds together. Records one
+/// history entry per blind.
+pub(crate) async fn dispatch_scene(
+    controller: &Arc<BlindController>,
+    name: &str,
+    source: CommandSource,
+) -> Result<Vec<u64>, CommandError> {
+    let Some(scene) = controller.blinds().scene_named(name) else {
+        let err = CommandError::Invalid(format!("unknown scene `{name}`"));
+        controller.history().record(HistoryEntry::now(
+            source,
+            format!("scene:{name}"),
+            None,
+            None,
+            Err(&err),
+        ));
+        return Err(err);
+    };
+    let result = controller
+        .set_target_positions(scene.targets.clone(), source.clone())
+        .await
+        .with_context(|| format!("activating scene `{name}`"))
+        .map(|_| ())
+        .map_err(command_error);
+    for (aid, target) in &scene.targets {
+        let channel = controller.blinds().find(*aid).map(|blind| blind.channel);
+        controller.history().record(HistoryEntry::now(
+            source.clone(),
+            format!("scene:{name}"),
+            channel,
+            Some(i16::from(*target)),
+            result.as_ref().map(|_| ()),
+        ));
+    }
+    result.map(|()| scene.targets.iter().map(|(aid, _)| *aid).collect())
+}
+
+/// `POST /locks` body. Omitting `channel` locks every blind.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LockRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    /// Unix seconds at which the lock lapses; omitted to hold until unlocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Lock a channel for maintenance, stopping any of its blinds still moving.
+/// Recorded in the command history as `lock`.
+pub(crate) async fn dispatch_lock(
+    controller: &Arc<BlindController>,
+    request: LockRequest,
+    source: CommandSource,
+) -> Result<Lock, CommandError> {
+    let channel = request.channel.unwrap_or(Channel::All);
+    let since = unix_now();
+    let result = if !controller.blinds().contains_channel(channel) {
+        Err(CommandError::Invalid(format!(
+            "channel {channel} is not configured in [[blinds]]"
+        )))
+    } else if request.until.is_some_and(|until| until <= since) {
+        Err(CommandError::Invalid(
+            "lock expiry must be in the future".to_string(),
+        ))
+    } else {
+        let lock = Lock {
+            channel,
+            reason: request.reason.filter(|reason| !reason.trim().is_empty()),
+            since,
+            until: request.until,
+        };
+        controller
+            .lock_channel(lock.clone())
+            .await
+            .with_context(|| format!("locking {channel}"))
+            .map(|_| lock)
+            .map_err(command_error)
+    };
+    controller.history().record(HistoryEntry::now(
+        source,
+        "lock",
+        Some(channel),
+        None,
+        result.as_ref().map(|_| ()),
+    ));
+    result
+}
+
+/// Remove the lock on `channel`. `Ok(None)` when it was not locked.
+pub(crate) fn dispatch_unlock(
+    controller: &BlindController,
+    channel: Channel,
+    source: CommandSource,
+) -> Result<Option<Lock>, CommandError> {
+    let result = controller
+        .locks()
+        .remove(channel)
+        .with_context(|| format!("unlocking {channel}"))
+        .map_err(command_error);
+    if !matches!(result, Ok(None)) {
+        controller.history().record(HistoryEntry::now(
+            source,
+            "unlock",
+            Some(channel),
+            None,
+            result.as_ref().map(|_| ()),
+        ));
+    }
+    result
 }
 
 pub(crate) async fn dispatch_control_request(
     controller: &Arc<BlindController>,
     request: ControlRequest,
+    source: CommandSource,
 ) -> Result<CommandOutcome, CommandError> {
+    ensure_configured_channel(controller.blinds(), &request)?;
     match request {
+        // Groups fan out through the shared position path so `plan_motion`
+        // can start them on a paired group remote or one blind at a time.
+        ControlRequest::Driver {
+            command: command @ (Command::Up | Command::Down),
+            channel: Some(channel @ Channel::Group(_)),
+        } => {
+            let position = if command == Command::Up { 100 } else { 0 };
+            controller
+                .set_target_for_channel(Some(channel), position, source)
+                .await
+                .with_context(|| format!("executing {command:?} command on {channel}"))
+                .map_err(command_error)?;
+            Ok(CommandOutcome {
+                inferred_position: None,
+            })
+        }
+        // My needs the blind's stored favourite position, so the controller
+        // plans the resulting timed move itself.
+        ControlRequest::Driver {
+            command: Command::My,
+            channel,
+        } => {
+            controller
+                .execute_my(channel)
+                .await
+                .context("executing My command")
+                .map_err(command_error)?;
+            Ok(CommandOutcome {
+                inferred_position: None,
+            })
+        }
         ControlRequest::Driver {
             command: cmd,
             channel,
@@ -169,7 +538,7 @@ pub(crate) async fn dispatch_control_request(
             .map_err(command_error),
         ControlRequest::Position { channel, position } => {
             controller
-                .set_target_for_channel(channel, position)
+                .set_target_for_channel(channel, position, source)
                 .await
                 .with_context(|| format!("executing target position to {position}%"))
                 .map_err(command_error)?;
@@ -177,20 +546,33 @@ pub(crate) async fn dispatch_control_request(
                 inferred_position: None,
             })
         }
+        ControlRequest::Tilt { channel, angle } => {
+            controller
+                .set_tilt_for_channel(channel, angle)
+                .await
+                .with_context(|| format!("executing tilt to {angle}°"))
+                .map_err(command_error)?;
+            Ok(CommandOutcome {
+                inferred_position: None,
+            })
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{DriverConfig, DriverKind, PositioningOptions};
+    use crate::config::{DriverConfig, DriverKind, GroupOptions, PositioningOptions};
     use crate::driver::ProtocolOperation;
+    use crate::history::{HistoryFilter, Outcome};
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     fn parse(command: &str, channel: Option<Channel>) -> Result<ControlRequest, CommandError> {
         parse_command(CommandRequest {
             command: command.to_string(),
             channel,
+            group: None,
             value: None,
         })
     }
@@ -214,9 +596,11 @@ mod tests {
     fn validate_rejects_telis_pairing_before_dispatch() {
         let err = validate_command_request(
             DriverKind::Telis,
+            &BlindInventory::default(),
             CommandRequest {
                 command: "prog".to_string(),
                 channel: Some(Channel::L1),
+                group: None,
                 value: None,
             },
         )
@@ -230,6 +614,7 @@ mod tests {
             ("select", Command::Select, Some(Channel::L2)),
             ("select", Command::Select, None),
             ("up", Command::Up, Some(Channel::L1)),
+            ("my", Command::My, None),
             ("prog", Command::Prog, Some(Channel::L1)),
             ("prog_long", Command::ProgLong, Some(Channel::L1)),
         ] {
@@ -284,6 +669,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn position_targets_require_known_unique_blinds_in_range() {
+        let blinds = BlindInventory::default();
+        let targets = |body: &str| serde_json::from_str::<Vec<PositionTarget>>(body).unwrap();
+
+        assert_eq!(
+            validate_position_targets(
+                &blinds,
+                &targets(r#"[{"aid":2,"target":40},{"aid":3,"target":0}]"#)
+            )
+            .unwrap(),
+            vec![(2, 40), (3, 0)]
+        );
+        for (body, message) in [
+            ("[]", "at least one target"),
+            (
+                r#"[{"aid":9,"target":40}]"#,
+                "aid 9 is not a configured blind",
+            ),
+            (
+                r#"[{"aid":2,"target":40},{"aid":2,"target":60}]"#,
+                "aid 2 is listed more than once",
+            ),
+            (r#"[{"aid":2,"target":101}]"#, "between 0 and 100"),
+        ] {
+            let err = validate_position_targets(&blinds, &targets(body)).unwrap_err();
+            assert!(err.to_string().contains(message), "{body}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_accepts_tilt_angle_and_rejects_out_of_range() {
+        let req = serde_json::from_str::<CommandRequest>(
+            r#"{"command":"tilt","channel":"L1","value":-45}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_command(req).unwrap(),
+            ControlRequest::Tilt {
+                channel: Some(Channel::L1),
+                angle: -45,
+            }
+        );
+
+        let req =
+            serde_json::from_str::<CommandRequest>(r#"{"command":"tilt","value":91}"#).unwrap();
+        let err = parse_command(req).unwrap_err();
+        assert!(err.to_string().contains("between -90 and 90"), "{err}");
+    }
+
     #[test]
     fn parse_preserves_optional_target_channel() {
         let with_channel = serde_json::from_str::<CommandRequest>(
@@ -334,12 +769,116 @@ mod tests {
         assert!(err.to_string().contains("unknown field"));
     }
 
+    fn living_room() -> BlindInventory {
+        BlindInventory::default().with_groups(&[GroupOptions {
+            name: "living room".to_string(),
+            channel: Channel::Group(1),
+            blinds: vec![Channel::L1, Channel::L3],
+            aid: None,
+            paired: false,
+        }])
+    }
+
+    #[test]
+    fn validate_resolves_group_name_to_group_channel() {
+        let request: CommandRequest =
+            serde_json::from_str(r#"{"command":"down","group":"living room"}"#).unwrap();
+
+        assert_eq!(
+            validate_command_request(DriverKind::Rts, &living_room(), request).unwrap(),
+            ControlRequest::Driver {
+                command: Command::Down,
+                channel: Some(Channel::Group(1)),
+            }
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_or_ambiguous_groups() {
+        for (body, message) in [
+            (
+                r#"{"command":"up","group":"attic"}"#,
+                "unknown group `attic`",
+            ),
+            (
+                r#"{"command":"up","channel":"L1","group":"living room"}"#,
+                "mutually exclusive",
+            ),
+            (
+                r#"{"command":"select","group":"living room"}"#,
+                "select does not accept a group",
+            ),
+        ] {
+            let request: CommandRequest = serde_json::from_str(body).unwrap();
+            let err = validate_command_request(DriverKind::Rts, &living_room(), request)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(message), "{body}: {err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_group_down_fans_out_to_member_targets() {
+        let controller = Arc::new(
+            BlindController::with_driver_and_positions_for_test(
+                DriverConfig::fake(),
+                living_room(),
+                PositioningOptions::default(),
+                HashMap::from([(2, 100), (3, 100), (4, 100)]),
+            )
+            .await
+            .unwrap(),
+        );
+
+        dispatch_command(
+            &controller,
+            CommandRequest {
+                command: "down".to_string(),
+                channel: None,
+                group: Some("living room".to_string()),
+                value: None,
+            },
+            CommandSource::Http,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            controller.operations(),
+            vec![
+                ProtocolOperation::FakeCommand {
+                    channel: Channel::L1,
+                    command: Command::Down,
+                },
+                ProtocolOperation::FakeCommand {
+                    channel: Channel::L3,
+                    command: Command::Down,
+                },
+            ]
+        );
+        assert_eq!(controller.position_for_aid(2).await.target, 0);
+        assert_eq!(controller.position_for_aid(3).await.target, 100);
+        assert_eq!(controller.position_for_aid(4).await.target, 0);
+
+        let history = controller.history().query(&HistoryFilter::default());
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].source, CommandSource::Http);
+        assert_eq!(history[0].command, "down");
+        assert_eq!(history[0].channel, Some(Channel::Group(1)));
+        assert_eq!(history[0].outcome, Outcome::Ok);
+    }
+
     #[tokio::test]
     async fn dispatch_target_without_channel_uses_current_selection() {
         let controller = Arc::new(
-            BlindController::with_driver(DriverConfig::fake(), PositioningOptions::default())
-                .await
-                .unwrap(),
+            BlindController::with_driver_and_positions_for_test(
+                DriverConfig::fake(),
+                BlindInventory::default(),
+                PositioningOptions::default(),
+                HashMap::new(),
+            )
+            .await
+            .unwrap(),
         );
         controller
             .execute(Command::Select, Some(Channel::L2))
@@ -351,8 +890,10 @@ mod tests {
             CommandRequest {
                 command: "target".to_string(),
                 channel: None,
+                group: None,
                 value: Some(50),
             },
+            CommandSource::Cli,
         )
         .await
         .unwrap();
@@ -0,0 +1,168 @@
+//! Offline sunrise and sunset times from latitude and longitude.
+//!
+//! Uses the NOAA solar calculator equations, which agree with published
+//! almanac tables to about a minute between the polar circles.
+
+use jiff::civil::Date;
+use jiff::{SignedDuration, Timestamp};
+
+use crate::config::SunEvent;
+
+/// Solar zenith at sunrise and sunset: 90° plus refraction and the solar
+/// disc radius, so the event is the upper limb touching the horizon.
+const ZENITH_DEGREES: f64 = 90.833;
+/// Julian day of 1970-01-01T00:00Z.
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// Julian day of the J2000.0 epoch.
+const J2000_JD: f64 = 2_451_545.0;
+
+/// Time of `event` on the solar day `date` at `longitude`, or `None` when
+/// the sun stays above or below the horizon all day (polar day or night).
+///
+/// `latitude` is positive north and `longitude` positive east, in degrees.
+pub(crate) fn sun_event(
+    date: Date,
+    latitude: f64,
+    longitude: f64,
+    event: SunEvent,
+) -> Option<Timestamp> {
+    let midnight = date.to_zoned(jiff::tz::TimeZone::UTC).ok()?.timestamp();
+    // Start from local solar noon and refine once at the estimated event
+    // time; the second pass moves the result by well under a minute.
+    let mut minutes = 720.0 - 4.0 * longitude;
+    for _ in 0..2 {
+        let jd = julian_day(midnight) + minutes / 1440.0;
+        minutes = event_minutes(jd, latitude, longitude, event)?;
+    }
+    let offset = SignedDuration::from_secs_f64(minutes * 60.0);
+    midnight.checked_add(offset).ok()
+}
+
+fn julian_day(at: Timestamp) -> f64 {
+    UNIX_EPOCH_JD + at.as_second() as f64 / 86_400.0
+}
+
+/// Minutes after UTC midnight of `event` for the solar position at `jd`.
+fn event_minutes(jd: f64, latitude: f64, longitude: f64, event: SunEvent) -> Option<f64> {
+    let t = (jd - J2000_JD) / 36_525.0;
+
+    let mean_longitude = (280.466_46 + t * (36_000.769_83 + t * 0.000_303_2)).rem_euclid(360.0);
+    let mean_anomaly = 357.529_11 + t * (35_999.050_29 - 0.000_153_7 * t);
+    let eccentricity = 0.016_708_634 - t * (0.000_042_037 + 0.000_000_126_7 * t);
+    let m = mean_anomaly.to_radians();
+    let center = m.sin() * (1.914_602 - t * (0.004_817 + 0.000_014 * t))
+        + (2.0 * m).sin() * (0.019_993 - 0.000_101 * t)
+        + (3.0 * m).sin() * 0.000_289;
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_longitude =
+        (mean_longitude + center - 0.005_69 - 0.004_78 * omega.sin()).to_radians();
+    let mean_obliquity =
+        23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.000_59 - t * 0.001_813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.002_56 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+
+    let y = (obliquity / 2.0).tan().powi(2);
+    let l0 = mean_longitude.to_radians();
+    let equation_of_time = 4.0
+        * (y * (2.0 * l0).sin() - 2.0 * eccentricity * m.sin()
+            + 4.0 * eccentricity * y * m.sin() * (2.0 * l0).cos()
+            - 0.5 * y * y * (4.0 * l0).sin()
+            - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+        .to_degrees();
+
+    let lat = latitude.to_radians();
+    let cos_hour_angle = ZENITH_DEGREES.to_radians().cos() / (lat.cos() * declination.cos())
+        - lat.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let noon = 720.0 - 4.0 * longitude - equation_of_time;
+    Some(match event {
+        SunEvent::Sunrise => noon - 4.0 * hour_angle,
+        SunEvent::Sunset => noon + 4.0 * hour_angle,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+    use jiff::tz::TimeZone;
+
+    /// Seconds between the computed `event` and a published local `HH:MM`.
+    fn error_secs(
+        day: Date,
+        latitude: f64,
+        longitude: f64,
+        event: SunEvent,
+        tz: &str,
+        published: &str,
+    ) -> i64 {
+        let computed = sun_event(day, latitude, longitude, event).unwrap();
+        let (hour, minute) = published.split_once(':').unwrap();
+        let published = day
+            .at(hour.parse().unwrap(), minute.parse().unwrap(), 0, 0)
+            .to_zoned(TimeZone::get(tz).unwrap())
+            .unwrap();
+        (computed.as_second() - published.timestamp().as_second()).abs()
+    }
+
+    #[test]
+    fn matches_published_sunrise_tables() {
+        // Published local times, rounded to the minute.
+        let cases = [
+            // London, summer solstice.
+            (
+                date(2024, 6, 21),
+                51.5074,
+                -0.1278,
+                "Europe/London",
+                "04:43",
+                "21:21",
+            ),
+            // New York, winter solstice.
+            (
+                date(2024, 12, 21),
+                40.7128,
+                -74.0060,
+                "America/New_York",
+                "07:16",
+                "16:32",
+            ),
+            // Sydney, southern summer solstice.
+            (
+                date(2024, 12, 21),
+                -33.8688,
+                151.2093,
+                "Australia/Sydney",
+                "05:41",
+                "20:05",
+            ),
+            // Paris, spring equinox.
+            (
+                date(2024, 3, 20),
+                48.8566,
+                2.3522,
+                "Europe/Paris",
+                "06:52",
+                "19:03",
+            ),
+        ];
+        for (day, latitude, longitude, tz, sunrise, sunset) in cases {
+            for (event, published) in [(SunEvent::Sunrise, sunrise), (SunEvent::Sunset, sunset)] {
+                let error = error_secs(day, latitude, longitude, event, tz, published);
+                assert!(error <= 60, "{tz} {event:?} off by {error}s");
+            }
+        }
+    }
+
+    #[test]
+    fn polar_day_and_night_have_no_events() {
+        // Tromsø: midnight sun in June, polar night in December.
+        for day in [date(2024, 6, 21), date(2024, 12, 21)] {
+            assert!(sun_event(day, 69.6492, 18.9553, SunEvent::Sunrise).is_none());
+            assert!(sun_event(day, 69.6492, 18.9553, SunEvent::Sunset).is_none());
+        }
+    }
+}
@@ -3,8 +3,12 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::config::{BlindTimingOptions, DriverConfig, PositioningOptions};
+use crate::config::{
+    BlindKind, BlindOptions, BlindTimingOptions, DriverConfig, PositioningOptions,
+};
 use crate::controller::BlindController;
+use crate::core::Channel;
+use crate::positioning::inventory::BlindInventory;
 
 /// Per-channel open/close timings with the same duration on L1–L4.
 pub fn uniform_positioning(ms: u64) -> PositioningOptions {
@@ -12,25 +16,52 @@ pub fn uniform_positioning(ms: u64) -> PositioningOptions {
         open_ms: ms,
         close_ms: ms,
         slack_ms: 0,
+        my_position: None,
+        tilt_ms: None,
+        open_curve: Vec::new(),
+        close_curve: Vec::new(),
+        rehome_after_moves: None,
+        rehome_after_hours: None,
     };
-    PositioningOptions {
-        l1: timing.clone(),
-        l2: timing.clone(),
-        l3: timing.clone(),
-        l4: timing,
+    let mut positioning = PositioningOptions::default();
+    for channel in Channel::TELIS_ROWS {
+        positioning.set_timing(channel, timing.clone());
     }
+    positioning
 }
 
 /// L1 timing override only; other channels keep defaults (tests that drive blind 1 / aid 2).
 pub fn uniform_positioning_l1_ms(ms: u64) -> PositioningOptions {
-    PositioningOptions {
-        l1: BlindTimingOptions {
+    let mut positioning = PositioningOptions::default();
+    positioning.set_timing(
+        Channel::L1,
+        BlindTimingOptions {
             open_ms: ms,
             close_ms: ms,
             slack_ms: 0,
+            my_position: None,
+            tilt_ms: None,
+            open_curve: Vec::new(),
+            close_curve: Vec::new(),
+            rehome_after_moves: None,
+            rehome_after_hours: None,
         },
-        ..PositioningOptions::default()
-    }
+    );
+    positioning
+}
+
+/// Inventory of `(aid, channel)` blinds named `Blind <aid>`.
+pub fn inventory(blinds: &[(u64, Channel)]) -> BlindInventory {
+    let options = blinds
+        .iter()
+        .map(|(aid, channel)| BlindOptions {
+            name: format!("Blind {aid}"),
+            channel: *channel,
+            aid: *aid,
+            kind: BlindKind::Roller,
+        })
+        .collect::<Vec<_>>();
+    BlindInventory::from_options(&options)
 }
 
 /// Initial current positions for the four HomeKit blind accessories (aids 2–5).
@@ -51,6 +82,7 @@ pub async fn fake_controller(
     Arc::new(
         BlindController::with_driver_and_positions_for_test(
             DriverConfig::fake(),
+            BlindInventory::default(),
             positioning,
             positions,
         )